@@ -15,6 +15,10 @@ pub struct Cli {
     #[arg(long, global = true, required = false, default_value = "8081")]
     pub port: String,
 
+    /// Токен доступа вместо сохранённой сессии (для CI)
+    #[arg(long, global = true, env = "BLOG_TOKEN", hide_env_values = true)]
+    pub token: Option<String>,
+
     /// Команда для выполнения
     #[command(subcommand)]
     pub command: Commands,
@@ -28,6 +32,18 @@ pub enum Commands {
     /// Вход в систему
     Login(LoginArgs),
 
+    /// Выход из системы с отзывом refresh токена
+    Logout(LogoutArgs),
+
+    /// Смена и сброс пароля
+    Password(PasswordArgs),
+
+    /// Подтверждение email
+    Email(EmailArgs),
+
+    /// Двухфакторная аутентификация (TOTP)
+    TwoFactor(TwoFactorArgs),
+
     /// Создание нового ресурса
     Create(CreateArgs),
 
@@ -42,6 +58,39 @@ pub enum Commands {
 
     /// Список ресурсов
     List(ListArgs),
+
+    /// Посты авторов, на которых вы подписаны
+    Feed(FeedArgs),
+
+    /// Полнотекстовый поиск по постам
+    Search(SearchArgs),
+
+    /// Реакция на пост
+    React(ReactArgs),
+
+    /// Закладки: сохранённые для чтения посты
+    Bookmarks(BookmarksArgs),
+
+    /// История изменений поста
+    History(HistoryArgs),
+
+    /// Восстановление поста из ревизии
+    Restore(RestoreArgs),
+
+    /// Загрузка файла (картинка или PDF)
+    Upload(UploadArgs),
+
+    /// Управление пользователями, только для администратора
+    Admin(AdminArgs),
+
+    /// Токены доступа для автоматизации
+    Token(TokenArgs),
+
+    /// Профили авторов
+    Profile(ProfileArgs),
+
+    /// Выгрузка данных и удаление аккаунта
+    Account(AccountArgs),
 }
 
 #[derive(Args, Debug)]
@@ -60,14 +109,107 @@ pub struct RegisterArgs {
 }
 
 #[derive(Args, Debug)]
-pub struct LoginArgs {
-    /// Имя пользователя
+pub struct LogoutArgs {
+    /// Завершить сессии на всех устройствах
     #[arg(long)]
+    pub all: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct PasswordArgs {
+    #[command(subcommand)]
+    pub command: PasswordCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum PasswordCommand {
+    /// Смена пароля, остальные сессии завершаются
+    Change {
+        /// Текущий пароль
+        #[arg(long)]
+        current: String,
+
+        /// Новый пароль
+        #[arg(long)]
+        new: String,
+    },
+
+    /// Запросить письмо со ссылкой для сброса пароля
+    Reset {
+        #[arg(long)]
+        email: String,
+    },
+
+    /// Задать новый пароль по токену из письма
+    Confirm {
+        /// Токен из ссылки в письме
+        #[arg(long)]
+        token: String,
+
+        /// Новый пароль
+        #[arg(long)]
+        new: String,
+    },
+}
+
+#[derive(Args, Debug)]
+pub struct EmailArgs {
+    #[command(subcommand)]
+    pub command: EmailCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum EmailCommand {
+    /// Подтвердить email по токену из письма
+    Verify {
+        /// Токен из ссылки в письме
+        #[arg(long)]
+        token: String,
+    },
+
+    /// Отправить письмо для подтверждения ещё раз
+    Resend,
+}
+
+#[derive(Args, Debug)]
+pub struct TwoFactorArgs {
+    #[command(subcommand)]
+    pub command: TwoFactorCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TwoFactorCommand {
+    /// Получить секрет для приложения-аутентификатора и коды восстановления
+    Enroll,
+
+    /// Включить 2FA кодом из приложения
+    Confirm {
+        /// Код из приложения
+        #[arg(long)]
+        code: String,
+    },
+
+    /// Выключить 2FA кодом из приложения или кодом восстановления
+    Disable {
+        /// Код из приложения или код восстановления
+        #[arg(long)]
+        code: String,
+    },
+}
+
+#[derive(Args, Debug)]
+pub struct LoginArgs {
+    /// Имя пользователя или email
+    #[arg(long, alias = "login")]
     pub username: String,
 
     /// Пароль
     #[arg(long)]
     pub password: String,
+
+    /// Код второго фактора, если не указан - будет запрошен при входе
+    #[arg(long)]
+    pub code: Option<String>,
 }
 
 #[derive(Args, Debug)]
@@ -79,6 +221,10 @@ pub struct CreateArgs {
     /// Содержимое
     #[arg(long)]
     pub content: String,
+
+    /// Отложенная публикация, RFC 3339 (например 2026-10-20T09:00:00+03:00)
+    #[arg(long)]
+    pub publish_at: Option<chrono::DateTime<chrono::FixedOffset>>,
 }
 
 #[derive(Args, Debug)]
@@ -101,6 +247,10 @@ pub struct UpdateArgs {
     /// Новое содержимое
     #[arg(long)]
     pub content: Option<String>,
+
+    /// Запланировать публикацию, RFC 3339
+    #[arg(long)]
+    pub publish_at: Option<chrono::DateTime<chrono::FixedOffset>>,
 }
 
 #[derive(Args, Debug)]
@@ -119,4 +269,310 @@ pub struct ListArgs {
     /// Смещение (сколько элементов пропустить)
     #[arg(long, default_value_t = 0)]
     pub offset: i32,
+
+    /// Курсор следующей страницы из предыдущего вывода (вместо смещения)
+    #[arg(long)]
+    pub cursor: Option<String>,
+
+    /// Порядок: сначала новые или сначала самые отмеченные `like`
+    #[arg(long, value_parser = ["latest", "liked"])]
+    pub order: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct FeedArgs {
+    /// Максимальное количество элементов
+    #[arg(long, default_value_t = 10)]
+    pub limit: i32,
+
+    /// Смещение (сколько элементов пропустить)
+    #[arg(long, default_value_t = 0)]
+    pub offset: i32,
+
+    /// Курсор следующей страницы из предыдущего вывода (вместо смещения)
+    #[arg(long)]
+    pub cursor: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct ReactArgs {
+    /// ID поста
+    #[arg(long)]
+    pub id: i64,
+
+    /// Вид реакции
+    #[arg(long, value_parser = ["like", "love", "laugh", "wow"], default_value = "like")]
+    pub kind: String,
+
+    /// Снять реакцию вместо того, чтобы поставить
+    #[arg(long)]
+    pub remove: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct SearchArgs {
+    /// Поисковый запрос
+    #[arg(long)]
+    pub query: String,
+
+    /// Максимальное количество результатов
+    #[arg(long, default_value_t = 10)]
+    pub limit: i32,
+
+    /// Смещение (сколько результатов пропустить)
+    #[arg(long, default_value_t = 0)]
+    pub offset: i32,
+}
+
+#[derive(Args, Debug)]
+pub struct HistoryArgs {
+    /// ID поста
+    #[arg(long)]
+    pub id: i64,
+}
+
+#[derive(Args, Debug)]
+pub struct RestoreArgs {
+    /// ID поста
+    #[arg(long)]
+    pub id: i64,
+
+    /// Номер ревизии
+    #[arg(long)]
+    pub revision: i32,
+}
+
+#[derive(Args, Debug)]
+pub struct UploadArgs {
+    /// Путь к файлу
+    #[arg(long)]
+    pub file: std::path::PathBuf,
+
+    /// ID поста, к которому относится файл
+    #[arg(long)]
+    pub post_id: Option<i64>,
+}
+
+#[derive(Args, Debug)]
+pub struct AdminArgs {
+    #[command(subcommand)]
+    pub command: AdminCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum AdminCommand {
+    /// Список пользователей с ролями
+    Users {
+        #[arg(long, default_value_t = 10)]
+        limit: i32,
+
+        #[arg(long, default_value_t = 0)]
+        offset: i32,
+    },
+
+    /// Сменить роль пользователя
+    Role {
+        /// ID пользователя
+        #[arg(long)]
+        user_id: String,
+
+        /// Новая роль
+        #[arg(long, value_parser = ["reader", "author", "moderator", "admin"])]
+        role: String,
+    },
+}
+
+#[derive(Args, Debug)]
+pub struct TokenArgs {
+    #[command(subcommand)]
+    pub command: TokenCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TokenCommand {
+    /// Создать токен. Значение показывается один раз
+    Create {
+        /// Название, например имя CI задачи
+        #[arg(long)]
+        name: String,
+
+        /// Права токена, можно указать несколько раз.
+        /// comments:write разрешает и комментарии, и реакции
+        #[arg(
+            long = "scope",
+            required = true,
+            value_parser = ["posts:read", "posts:write", "comments:write"]
+        )]
+        scopes: Vec<String>,
+
+        /// Срок действия в днях, без него токен действует до отзыва или смены пароля
+        #[arg(long)]
+        expires_in_days: Option<i64>,
+    },
+
+    /// Список действующих токенов
+    List,
+
+    /// Отозвать токен
+    Revoke {
+        /// ID токена
+        #[arg(long)]
+        id: String,
+    },
+}
+
+#[derive(Args, Debug)]
+pub struct BookmarksArgs {
+    #[command(subcommand)]
+    pub command: BookmarksCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum BookmarksCommand {
+    /// Добавить пост в закладки
+    Add {
+        /// ID поста
+        #[arg(long)]
+        id: i64,
+    },
+
+    /// Убрать пост из закладок
+    Remove {
+        /// ID поста
+        #[arg(long)]
+        id: i64,
+    },
+
+    /// Сохранённые посты, от новых закладок к старым
+    List {
+        /// Максимальное количество постов
+        #[arg(long, default_value_t = 10)]
+        limit: i32,
+
+        /// Смещение (сколько постов пропустить)
+        #[arg(long, default_value_t = 0)]
+        offset: i32,
+    },
+}
+
+#[derive(Args, Debug)]
+pub struct ProfileArgs {
+    #[command(subcommand)]
+    pub command: ProfileCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ProfileCommand {
+    /// Публичный профиль пользователя
+    Show {
+        /// Имя пользователя
+        #[arg(long)]
+        username: String,
+    },
+
+    /// Опубликованные посты автора
+    Posts {
+        /// Имя пользователя
+        #[arg(long)]
+        username: String,
+
+        /// Максимальное количество постов
+        #[arg(long, default_value_t = 10)]
+        limit: i32,
+
+        /// Смещение (сколько постов пропустить)
+        #[arg(long, default_value_t = 0)]
+        offset: i32,
+    },
+
+    /// Подписаться на автора
+    Follow {
+        /// Имя пользователя
+        #[arg(long)]
+        username: String,
+    },
+
+    /// Отписаться от автора
+    Unfollow {
+        /// Имя пользователя
+        #[arg(long)]
+        username: String,
+    },
+
+    /// Подписчики пользователя
+    Followers {
+        /// Имя пользователя
+        #[arg(long)]
+        username: String,
+
+        /// Максимальное количество пользователей
+        #[arg(long, default_value_t = 10)]
+        limit: i32,
+
+        /// Смещение (сколько пользователей пропустить)
+        #[arg(long, default_value_t = 0)]
+        offset: i32,
+    },
+
+    /// Авторы, на которых подписан пользователь
+    Following {
+        /// Имя пользователя
+        #[arg(long)]
+        username: String,
+
+        /// Максимальное количество пользователей
+        #[arg(long, default_value_t = 10)]
+        limit: i32,
+
+        /// Смещение (сколько пользователей пропустить)
+        #[arg(long, default_value_t = 0)]
+        offset: i32,
+    },
+
+    /// Изменить свой профиль. Профиль заменяется целиком: не указанные поля очищаются
+    Update {
+        /// Отображаемое имя
+        #[arg(long)]
+        display_name: Option<String>,
+
+        /// О себе
+        #[arg(long)]
+        bio: Option<String>,
+
+        /// Адрес аватара: http(s) или /api/attachments/{id}
+        #[arg(long)]
+        avatar_url: Option<String>,
+
+        /// Ссылка на сайт или соцсеть, можно указать несколько раз
+        #[arg(long = "link")]
+        links: Vec<String>,
+    },
+}
+
+#[derive(Args, Debug)]
+pub struct AccountArgs {
+    #[command(subcommand)]
+    pub command: AccountCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum AccountCommand {
+    /// Выгрузить все свои данные в JSON файл
+    Export {
+        /// Куда сохранить, по умолчанию имя файла от сервера в текущем каталоге
+        #[arg(long)]
+        output: Option<std::path::PathBuf>,
+    },
+
+    /// Удалить аккаунт. Все сессии и токены доступа перестают действовать
+    Delete {
+        /// Что сделать с постами и комментариями: удалить или оставить от имени [deleted].
+        /// Черновики удаляются в любом случае
+        #[arg(long, value_parser = ["delete", "anonymize"])]
+        posts: String,
+
+        /// Текущий пароль, если не указан - будет запрошен
+        #[arg(long)]
+        password: Option<String>,
+    },
 }
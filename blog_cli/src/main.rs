@@ -2,7 +2,10 @@ mod command;
 mod security;
 
 use clap::Parser;
-use command::{Cli, Commands};
+use command::{
+    AccountCommand, AdminCommand, BookmarksCommand, Cli, Commands, EmailCommand, PasswordCommand,
+    ProfileCommand, TokenCommand, TwoFactorCommand,
+};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -21,9 +24,19 @@ async fn main() -> anyhow::Result<()> {
     }
 
     let mut blog = blog_client::BlogClient::new(transport).await?;
-    if security::has_token() {
-        blog.set_token(security::read_token()?);
+    // Токен доступа не обновляется и не сохраняется в файл
+    let token_from_env = cli.token.is_some();
+    if let Some(token) = cli.token.clone() {
+        blog.set_token(token);
+    } else {
+        if security::has_token() {
+            blog.set_token(security::read_token()?);
+        }
+        if let Some(refresh_token) = security::read_refresh_token() {
+            blog.set_refresh_token(refresh_token);
+        }
     }
+    let refresh_token_before = blog.get_refresh_token().cloned();
 
     let new_token: Option<String> = match &cli.command {
         Commands::Register(args) => {
@@ -36,11 +49,24 @@ async fn main() -> anyhow::Result<()> {
         }
 
         Commands::Login(args) => {
-            let response = blog.login(&args.username, &args.password).await?;
+            let mut response = blog.login(&args.username, &args.password).await?;
+            if let Some(challenge) = response.two_factor_challenge.clone() {
+                let code = match &args.code {
+                    Some(code) => code.clone(),
+                    None => read_line("Код 2FA: ")?,
+                };
+                response = blog.login_two_factor(&challenge, &code).await?;
+            }
             Some(response.token)
         }
         Commands::Create(args) => {
-            let response = blog.create_post(&args.title, &args.content).await?;
+            let response = blog
+                .create_post(
+                    &args.title,
+                    &args.content,
+                    args.publish_at.map(|t| t.timestamp()),
+                )
+                .await?;
 
             if let Some(post) = response.post {
                 print_post(post);
@@ -57,7 +83,12 @@ async fn main() -> anyhow::Result<()> {
         }
         Commands::Update(args) => {
             let response = blog
-                .update_post(args.id, args.title.clone(), args.content.clone())
+                .update_post(
+                    args.id,
+                    args.title.clone(),
+                    args.content.clone(),
+                    args.publish_at.map(|t| t.timestamp()),
+                )
                 .await?;
 
             if let Some(post) = response.post {
@@ -76,26 +107,486 @@ async fn main() -> anyhow::Result<()> {
             None
         }
         Commands::List(args) => {
-            let response = blog.list_posts(args.limit, args.offset).await?;
+            let response = blog
+                .list_posts(
+                    args.limit,
+                    args.offset,
+                    args.cursor.as_deref(),
+                    args.order.as_deref(),
+                )
+                .await?;
+
+            println!("total: {}", response.total);
+            for post in response.post {
+                print_post(post);
+            }
+            if let Some(cursor) = response.next_cursor {
+                println!("next page: --cursor {}", cursor);
+            }
+
+            None
+        }
+        Commands::Search(args) => {
+            let response = blog
+                .search_posts(&args.query, args.limit, args.offset)
+                .await?;
+
+            println!("found: {}", response.total);
+            for hit in response.hits {
+                if let Some(post) = hit.post {
+                    println!("id: {}, \ntitle: {}", post.id, post.title);
+                }
+                println!("snippet: {}", plain_snippet(&hit.snippet));
+            }
 
+            None
+        }
+        Commands::Feed(args) => {
+            let response = blog
+                .get_feed(args.limit, args.offset, args.cursor.as_deref())
+                .await?;
+
+            println!("total: {}", response.total);
             for post in response.post {
                 print_post(post);
             }
+            if let Some(cursor) = response.next_cursor {
+                println!("next page: --cursor {}", cursor);
+            }
+
+            None
+        }
+        Commands::React(args) => {
+            let response = blog.react(args.id, &args.kind, args.remove).await?;
+
+            println!("reactions: {}", format_reactions(&response.counts));
+            if !response.reacted.is_empty() {
+                println!("yours: {}", response.reacted.join(", "));
+            }
+
+            None
+        }
+        Commands::Bookmarks(args) => match args.command {
+            BookmarksCommand::Add { id } => {
+                blog.bookmark(id, false).await?;
+                println!("Пост {} добавлен в закладки", id);
+
+                None
+            }
+            BookmarksCommand::Remove { id } => {
+                blog.bookmark(id, true).await?;
+                println!("Пост {} убран из закладок", id);
+
+                None
+            }
+            BookmarksCommand::List { limit, offset } => {
+                let response = blog.list_bookmarks(limit, offset).await?;
+
+                println!("total: {}", response.total);
+                for post in response.post {
+                    print_post(post);
+                }
+
+                None
+            }
+        },
+        Commands::History(args) => {
+            let response = blog.list_revisions(args.id).await?;
+
+            if response.revisions.is_empty() {
+                println!("Post with id {} has no revisions", args.id);
+            }
+            for revision in response.revisions {
+                print_revision(revision);
+            }
+
+            None
+        }
+        Commands::Restore(args) => {
+            let response = blog.restore_revision(args.id, args.revision).await?;
+
+            if let Some(post) = response.post {
+                println!("Post restored from revision {}", args.revision);
+                print_post(post);
+            };
+
+            None
+        }
+        Commands::Logout(args) => {
+            if args.all {
+                blog.logout_all().await?;
+            } else {
+                blog.logout().await?;
+            }
+            security::delete_token()?;
+            println!("Сессия завершена");
+
+            None
+        }
+        Commands::Password(args) => match &args.command {
+            PasswordCommand::Change { current, new } => {
+                let response = blog.change_password(current, new).await?;
+                println!("Пароль изменён, остальные сессии завершены");
+                Some(response.token)
+            }
+            PasswordCommand::Reset { email } => {
+                blog.request_password_reset(email).await?;
+                println!("Если аккаунт с таким email существует, на него отправлено письмо");
+                None
+            }
+            PasswordCommand::Confirm { token, new } => {
+                blog.confirm_password_reset(token, new).await?;
+                println!("Пароль изменён, войдите с новым паролем");
+                None
+            }
+        },
+        Commands::Email(args) => match &args.command {
+            EmailCommand::Verify { token } => {
+                blog.verify_email(token).await?;
+                println!("Email подтверждён");
+                None
+            }
+            EmailCommand::Resend => {
+                blog.resend_verification().await?;
+                println!("Письмо отправлено");
+                None
+            }
+        },
+        Commands::TwoFactor(args) => match &args.command {
+            TwoFactorCommand::Enroll => {
+                let enrollment = blog.enroll_two_factor().await?;
+                println!("secret: {}", enrollment.secret);
+                println!("uri: {}", enrollment.otpauth_uri);
+                println!("recovery codes:");
+                for code in enrollment.recovery_codes {
+                    println!("  {}", code);
+                }
+                println!("Подтвердите 2FA командой two-factor confirm --code <код>");
+                None
+            }
+            TwoFactorCommand::Confirm { code } => {
+                blog.confirm_two_factor(code).await?;
+                println!("2FA включена");
+                None
+            }
+            TwoFactorCommand::Disable { code } => {
+                blog.disable_two_factor(code).await?;
+                println!("2FA выключена");
+                None
+            }
+        },
+        Commands::Upload(args) => {
+            let data = std::fs::read(&args.file)?;
+            let file_name = args
+                .file
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let response = blog
+                .upload_attachment(&file_name, data, args.post_id)
+                .await?;
+
+            if let Some(attachment) = response.attachment {
+                println!(
+                    "id: {}, \nfile: {} ({}, {} bytes), \nurl: {}",
+                    attachment.id,
+                    attachment.file_name,
+                    attachment.content_type,
+                    attachment.size,
+                    attachment.url
+                );
+            }
 
             None
         }
+        Commands::Admin(args) => match &args.command {
+            AdminCommand::Users { limit, offset } => {
+                let response = blog.list_users(*limit, *offset).await?;
+                for user in response.users {
+                    print_user(user);
+                }
+                None
+            }
+            AdminCommand::Role { user_id, role } => {
+                let response = blog.set_user_role(user_id, role).await?;
+                if let Some(user) = response.user {
+                    println!("Роль изменена");
+                    print_user(user);
+                }
+                None
+            }
+        },
+        Commands::Token(args) => match &args.command {
+            TokenCommand::Create {
+                name,
+                scopes,
+                expires_in_days,
+            } => {
+                let response = blog
+                    .create_personal_token(name, scopes, *expires_in_days)
+                    .await?;
+                if let Some(token) = response.personal_access_token {
+                    print_personal_token(token);
+                }
+                println!("token: {}", response.token);
+                println!("Сохраните токен, повторно он показан не будет");
+                None
+            }
+            TokenCommand::List => {
+                let response = blog.list_personal_tokens().await?;
+                for token in response.tokens {
+                    print_personal_token(token);
+                }
+                None
+            }
+            TokenCommand::Revoke { id } => {
+                blog.revoke_personal_token(id).await?;
+                println!("Токен отозван");
+                None
+            }
+        },
+        Commands::Profile(args) => match &args.command {
+            ProfileCommand::Show { username } => {
+                let response = blog.get_user_profile(username).await?;
+                if let Some(profile) = response.profile {
+                    print_profile(profile);
+                }
+                None
+            }
+            ProfileCommand::Posts {
+                username,
+                limit,
+                offset,
+            } => {
+                let response = blog.list_user_posts(username, *limit, *offset).await?;
+
+                println!("total: {}", response.total);
+                for post in response.post {
+                    print_post(post);
+                }
+                None
+            }
+            ProfileCommand::Follow { username } => {
+                let status = blog.follow(username, false).await?;
+                println!(
+                    "Вы подписаны на {}, подписчиков: {}",
+                    status.username, status.followers
+                );
+                None
+            }
+            ProfileCommand::Unfollow { username } => {
+                let status = blog.follow(username, true).await?;
+                println!(
+                    "Вы отписались от {}, подписчиков: {}",
+                    status.username, status.followers
+                );
+                None
+            }
+            ProfileCommand::Followers {
+                username,
+                limit,
+                offset,
+            } => {
+                let response = blog.list_follows(username, false, *limit, *offset).await?;
+                print_follows(response);
+                None
+            }
+            ProfileCommand::Following {
+                username,
+                limit,
+                offset,
+            } => {
+                let response = blog.list_follows(username, true, *limit, *offset).await?;
+                print_follows(response);
+                None
+            }
+            ProfileCommand::Update {
+                display_name,
+                bio,
+                avatar_url,
+                links,
+            } => {
+                let response = blog
+                    .update_profile(blog_client::blog_grpc::UpdateProfileRequest {
+                        display_name: display_name.clone(),
+                        bio: bio.clone(),
+                        avatar_url: avatar_url.clone(),
+                        links: links.clone(),
+                    })
+                    .await?;
+                if let Some(profile) = response.profile {
+                    print_profile(profile);
+                }
+                None
+            }
+        },
+        Commands::Account(args) => match &args.command {
+            AccountCommand::Export { output } => {
+                let response = blog.export_account().await?;
+                let path = output
+                    .clone()
+                    .unwrap_or_else(|| response.file_name.clone().into());
+                std::fs::write(&path, &response.data)?;
+                println!("Данные сохранены в {}", path.display());
+                None
+            }
+            AccountCommand::Delete { posts, password } => {
+                let password = match password {
+                    Some(password) => password.clone(),
+                    None => read_line("Пароль: ")?,
+                };
+                blog.delete_account(&password, posts).await?;
+                security::delete_token()?;
+                println!("Аккаунт удалён");
+                None
+            }
+        },
     };
 
-    if let Some(new_token) = new_token {
+    // Токены могли прозрачно обновиться во время запроса.
+    // Старый refresh токен после ротации недействителен, поэтому сравниваем его
+    let new_token = new_token.or_else(|| {
+        (blog.get_refresh_token() != refresh_token_before.as_ref())
+            .then(|| blog.get_token().cloned())
+            .flatten()
+    });
+    if let Some(new_token) = new_token.filter(|_| !token_from_env) {
         security::save_token(&new_token)?;
+        if let Some(refresh_token) = blog.get_refresh_token() {
+            security::save_refresh_token(refresh_token)?;
+        }
     }
 
     Ok(())
 }
 
+fn read_line(prompt: &str) -> std::io::Result<String> {
+    use std::io::Write;
+
+    print!("{}", prompt);
+    std::io::stdout().flush()?;
+    let mut line = String::new();
+    std::io::stdin().read_line(&mut line)?;
+    Ok(line.trim().to_string())
+}
+
 fn print_post(post: blog_client::blog_grpc::Post) {
     println!(
         "id: {}, \ntitle: {}, \ncontent: {}",
         post.id, post.title, post.content
     );
+    if let Some(author) = post.author {
+        match author.display_name {
+            Some(display_name) => println!("author: {} ({})", display_name, author.username),
+            None => println!("author: {}", author.username),
+        }
+    }
+    if let Some(publish_at) = post
+        .publish_at
+        .and_then(|t| chrono::DateTime::from_timestamp(t, 0))
+    {
+        println!("scheduled: {}", publish_at.to_rfc3339());
+    }
+    if !post.reactions.is_empty() {
+        println!("reactions: {}", format_reactions(&post.reactions));
+    }
+    if post.bookmarked == Some(true) {
+        println!("bookmarked");
+    }
+}
+
+/// Счётчики реакций в порядке имён, чтобы вывод не менялся от запуска к запуску
+fn format_reactions(counts: &std::collections::HashMap<String, i64>) -> String {
+    let mut counts: Vec<_> = counts.iter().collect();
+    counts.sort();
+    counts
+        .into_iter()
+        .map(|(kind, count)| format!("{} {}", kind, count))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn print_user(user: blog_client::blog_grpc::User) {
+    println!(
+        "id: {}, \nusername: {}, \nemail: {}{}, \nrole: {}",
+        user.id,
+        user.username,
+        user.email,
+        if user.email_verified {
+            ""
+        } else {
+            " (не подтверждён)"
+        },
+        user.role
+    );
+}
+
+fn print_follows(response: blog_client::blog_grpc::ListFollowsResponse) {
+    println!("total: {}", response.total);
+    for user in response.users {
+        let since = chrono::DateTime::from_timestamp(user.followed_at, 0)
+            .map(|t| t.to_rfc3339())
+            .unwrap_or_default();
+        match user.display_name {
+            Some(display_name) => println!("{} ({}), since {}", display_name, user.username, since),
+            None => println!("{}, since {}", user.username, since),
+        }
+    }
+}
+
+fn print_profile(profile: blog_client::blog_grpc::UserProfile) {
+    println!("username: {}", profile.username);
+    if let Some(display_name) = profile.display_name {
+        println!("name: {}", display_name);
+    }
+    if let Some(bio) = profile.bio {
+        println!("bio: {}", bio);
+    }
+    if let Some(avatar_url) = profile.avatar_url {
+        println!("avatar: {}", avatar_url);
+    }
+    for link in profile.links {
+        println!("link: {}", link);
+    }
+}
+
+fn print_personal_token(token: blog_client::blog_grpc::PersonalAccessToken) {
+    let format_time = |t: Option<i64>, default: &str| {
+        t.and_then(|t| chrono::DateTime::from_timestamp(t, 0))
+            .map(|t| t.to_rfc3339())
+            .unwrap_or_else(|| default.to_string())
+    };
+    println!(
+        "id: {}, \nname: {}, \nscopes: {}, \nexpires: {}, \nlast used: {}",
+        token.id,
+        token.name,
+        token.scopes.join(" "),
+        format_time(token.expires_at, "never"),
+        format_time(token.last_used_at, "-")
+    );
+}
+
+fn print_revision(revision: blog_client::blog_grpc::PostRevision) {
+    let created_at = chrono::DateTime::from_timestamp(revision.created_at, 0)
+        .map(|t| t.to_rfc3339())
+        .unwrap_or_default();
+    println!(
+        "revision: {}, \ncreated: {}, \neditor: {}, \ntitle: {}, \ncontent: {}",
+        revision.revision,
+        created_at,
+        revision.editor_id.unwrap_or_else(|| "-".to_string()),
+        revision.title,
+        revision.content
+    );
+}
+
+/// Фрагмент из поиска для терминала: подсветка в `[]`, без HTML-сущностей
+fn plain_snippet(snippet: &str) -> String {
+    snippet
+        .replace("<mark>", "[")
+        .replace("</mark>", "]")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&amp;", "&")
 }
@@ -5,6 +5,7 @@ use std::{
 };
 
 static PATH_TOKEN: &str = ".blog_token";
+static PATH_REFRESH_TOKEN: &str = ".blog_refresh_token";
 
 pub fn get_token_path() -> PathBuf {
     PathBuf::from(PATH_TOKEN)
@@ -24,16 +25,27 @@ pub fn read_token() -> io::Result<String> {
     fs::read_to_string(&token_path)
 }
 
-#[allow(dead_code)]
 pub fn delete_token() -> io::Result<()> {
     let token_path = get_token_path();
     if token_path.exists() {
         fs::remove_file(&token_path)?;
         println!("Токен удален");
     }
+    let refresh_path = PathBuf::from(PATH_REFRESH_TOKEN);
+    if refresh_path.exists() {
+        fs::remove_file(&refresh_path)?;
+    }
     Ok(())
 }
 
+pub fn save_refresh_token(token: &str) -> io::Result<()> {
+    fs::write(PATH_REFRESH_TOKEN, token)
+}
+
+pub fn read_refresh_token() -> Option<String> {
+    fs::read_to_string(PATH_REFRESH_TOKEN).ok()
+}
+
 pub fn has_token() -> bool {
     get_token_path().exists()
 }
@@ -1,14 +1,23 @@
 use crate::{
     BlogCommands,
     blog_grpc::{
-        self, AuthResponse, DeletePostResponse, ListPostsResponse, PostResponse,
-        blog_service_client::BlogServiceClient,
+        self, AttachmentResponse, AuthResponse, BookmarkResponse,
+        CreatePersonalAccessTokenResponse, DeleteAccountResponse, DeletePostResponse,
+        ExportAccountResponse, FollowStatus, ListFollowsResponse, ListPersonalAccessTokensResponse,
+        ListPostsResponse, ListRevisionsResponse, ListUsersResponse, LogoutResponse,
+        PasswordResetResponse, PostReactions, PostResponse, RevokePersonalAccessTokenResponse,
+        SearchPostsResponse, TwoFactorEnrollment, TwoFactorResponse, UpdateProfileRequest,
+        UserProfileResponse, UserResponse, VerifyEmailResponse,
+        blog_service_client::BlogServiceClient, upload_attachment_request,
     },
     error::AppError,
 };
 use async_trait::async_trait;
 use tonic::Request;
 
+/// Размер части файла в потоке загрузки
+const UPLOAD_CHUNK_SIZE: usize = 64 * 1024;
+
 pub struct GrpcClient {
     connection: BlogServiceClient<tonic::transport::Channel>,
 }
@@ -51,15 +60,191 @@ impl BlogCommands for GrpcClient {
 
         Ok(token.into_inner())
     }
+    async fn login_two_factor(
+        &mut self,
+        challenge: &str,
+        code: &str,
+    ) -> Result<AuthResponse, AppError> {
+        let token = self
+            .connection
+            .login_two_factor(blog_grpc::LoginTwoFactorRequest {
+                challenge: challenge.to_string(),
+                code: code.to_string(),
+            })
+            .await?;
+
+        Ok(token.into_inner())
+    }
+
+    async fn enroll_two_factor(&mut self, token: &str) -> Result<TwoFactorEnrollment, AppError> {
+        let mut request = Request::new(blog_grpc::EnrollTwoFactorRequest {});
+        request
+            .metadata_mut()
+            .insert("authorization", format!("Bearer {}", token).parse()?);
+
+        let response = self.connection.enroll_two_factor(request).await?;
+
+        Ok(response.into_inner())
+    }
+
+    async fn confirm_two_factor(
+        &mut self,
+        token: &str,
+        code: &str,
+    ) -> Result<TwoFactorResponse, AppError> {
+        let mut request = Request::new(blog_grpc::TwoFactorCodeRequest {
+            code: code.to_string(),
+        });
+        request
+            .metadata_mut()
+            .insert("authorization", format!("Bearer {}", token).parse()?);
+
+        let response = self.connection.confirm_two_factor(request).await?;
+
+        Ok(response.into_inner())
+    }
+
+    async fn disable_two_factor(
+        &mut self,
+        token: &str,
+        code: &str,
+    ) -> Result<TwoFactorResponse, AppError> {
+        let mut request = Request::new(blog_grpc::TwoFactorCodeRequest {
+            code: code.to_string(),
+        });
+        request
+            .metadata_mut()
+            .insert("authorization", format!("Bearer {}", token).parse()?);
+
+        let response = self.connection.disable_two_factor(request).await?;
+
+        Ok(response.into_inner())
+    }
+
+    async fn refresh(&mut self, refresh_token: &str) -> Result<AuthResponse, AppError> {
+        let token = self
+            .connection
+            .refresh(blog_grpc::RefreshRequest {
+                refresh_token: refresh_token.to_string(),
+            })
+            .await?;
+
+        Ok(token.into_inner())
+    }
+
+    async fn logout(
+        &mut self,
+        token: &str,
+        refresh_token: &str,
+    ) -> Result<LogoutResponse, AppError> {
+        let mut request = Request::new(blog_grpc::LogoutRequest {
+            refresh_token: refresh_token.to_string(),
+        });
+        request
+            .metadata_mut()
+            .insert("authorization", format!("Bearer {}", token).parse()?);
+
+        let response = self.connection.logout(request).await?;
+
+        Ok(response.into_inner())
+    }
+
+    async fn logout_all(&mut self, token: &str) -> Result<LogoutResponse, AppError> {
+        let mut request = Request::new(blog_grpc::LogoutAllRequest {});
+        request
+            .metadata_mut()
+            .insert("authorization", format!("Bearer {}", token).parse()?);
+
+        let response = self.connection.logout_all(request).await?;
+
+        Ok(response.into_inner())
+    }
+
+    async fn change_password(
+        &mut self,
+        token: &str,
+        current_password: &str,
+        new_password: &str,
+    ) -> Result<AuthResponse, AppError> {
+        let mut request = Request::new(blog_grpc::ChangePasswordRequest {
+            current_password: current_password.to_string(),
+            new_password: new_password.to_string(),
+        });
+        request
+            .metadata_mut()
+            .insert("authorization", format!("Bearer {}", token).parse()?);
+
+        let response = self.connection.change_password(request).await?;
+
+        Ok(response.into_inner())
+    }
+
+    async fn request_password_reset(
+        &mut self,
+        email: &str,
+    ) -> Result<PasswordResetResponse, AppError> {
+        let response = self
+            .connection
+            .request_password_reset(blog_grpc::PasswordResetRequest {
+                email: email.to_string(),
+            })
+            .await?;
+
+        Ok(response.into_inner())
+    }
+
+    async fn confirm_password_reset(
+        &mut self,
+        reset_token: &str,
+        new_password: &str,
+    ) -> Result<PasswordResetResponse, AppError> {
+        let response = self
+            .connection
+            .confirm_password_reset(blog_grpc::ConfirmPasswordResetRequest {
+                token: reset_token.to_string(),
+                new_password: new_password.to_string(),
+            })
+            .await?;
+
+        Ok(response.into_inner())
+    }
+
+    async fn verify_email(&mut self, verify_token: &str) -> Result<VerifyEmailResponse, AppError> {
+        let response = self
+            .connection
+            .verify_email(blog_grpc::VerifyEmailRequest {
+                token: verify_token.to_string(),
+            })
+            .await?;
+
+        Ok(response.into_inner())
+    }
+
+    async fn resend_verification(&mut self, token: &str) -> Result<VerifyEmailResponse, AppError> {
+        let mut request = Request::new(blog_grpc::ResendVerificationRequest {});
+        request
+            .metadata_mut()
+            .insert("authorization", format!("Bearer {}", token).parse()?);
+
+        let response = self.connection.resend_verification(request).await?;
+
+        Ok(response.into_inner())
+    }
+
     async fn create_post(
         &mut self,
         token: &str,
         title: &str,
         content: &str,
+        publish_at: Option<i64>,
     ) -> Result<PostResponse, AppError> {
         let mut request = Request::new(blog_grpc::CreatePostRequest {
             title: title.to_string(),
             content: content.to_string(),
+            tags: Vec::new(),
+            draft: false,
+            publish_at,
+            content_format: None,
         });
 
         request
@@ -77,11 +262,16 @@ impl BlogCommands for GrpcClient {
         post_id: i64,
         title: Option<String>,
         content: Option<String>,
+        publish_at: Option<i64>,
     ) -> Result<PostResponse, AppError> {
         let mut request = Request::new(blog_grpc::UpdatePostRequest {
             id: post_id,
             title,
             content,
+            tags: None,
+            status: None,
+            publish_at,
+            content_format: None,
         });
 
         request
@@ -118,12 +308,390 @@ impl BlogCommands for GrpcClient {
         Ok(post.into_inner())
     }
 
-    async fn list_posts(&mut self, limit: i32, offset: i32) -> Result<ListPostsResponse, AppError> {
+    async fn list_posts(
+        &mut self,
+        limit: i32,
+        offset: i32,
+        cursor: Option<&str>,
+        order: Option<&str>,
+    ) -> Result<ListPostsResponse, AppError> {
         let list_posts = self
             .connection
-            .list_post(blog_grpc::ListPostsRequest { offset, limit })
+            .list_post(blog_grpc::ListPostsRequest {
+                offset,
+                limit,
+                tags: Vec::new(),
+                cursor: cursor.map(str::to_string),
+                order: order.map(str::to_string),
+            })
             .await?;
 
         Ok(list_posts.into_inner())
     }
+
+    async fn search_posts(
+        &mut self,
+        query: &str,
+        limit: i32,
+        offset: i32,
+    ) -> Result<SearchPostsResponse, AppError> {
+        let hits = self
+            .connection
+            .search_posts(blog_grpc::SearchPostsRequest {
+                query: query.to_string(),
+                limit,
+                offset,
+            })
+            .await?;
+
+        Ok(hits.into_inner())
+    }
+
+    async fn list_revisions(&mut self, post_id: i64) -> Result<ListRevisionsResponse, AppError> {
+        let revisions = self
+            .connection
+            .list_revisions(blog_grpc::ListRevisionsRequest { post_id })
+            .await?;
+
+        Ok(revisions.into_inner())
+    }
+
+    async fn restore_revision(
+        &mut self,
+        token: &str,
+        post_id: i64,
+        revision: i32,
+    ) -> Result<PostResponse, AppError> {
+        let mut request = Request::new(blog_grpc::RestoreRevisionRequest { post_id, revision });
+
+        request
+            .metadata_mut()
+            .insert("authorization", format!("Bearer {}", token).parse()?);
+
+        let post = self.connection.restore_revision(request).await?;
+
+        Ok(post.into_inner())
+    }
+
+    async fn upload_attachment(
+        &mut self,
+        token: &str,
+        file_name: &str,
+        data: Vec<u8>,
+        post_id: Option<i64>,
+    ) -> Result<AttachmentResponse, AppError> {
+        let mut messages = vec![blog_grpc::UploadAttachmentRequest {
+            data: Some(upload_attachment_request::Data::Meta(
+                blog_grpc::AttachmentMeta {
+                    file_name: file_name.to_string(),
+                    post_id,
+                },
+            )),
+        }];
+        messages.extend(data.chunks(UPLOAD_CHUNK_SIZE).map(|chunk| {
+            blog_grpc::UploadAttachmentRequest {
+                data: Some(upload_attachment_request::Data::Chunk(chunk.to_vec())),
+            }
+        }));
+
+        let mut request = Request::new(futures_util::stream::iter(messages));
+        request
+            .metadata_mut()
+            .insert("authorization", format!("Bearer {}", token).parse()?);
+
+        let attachment = self.connection.upload_attachment(request).await?;
+
+        Ok(attachment.into_inner())
+    }
+
+    async fn list_users(
+        &mut self,
+        token: &str,
+        limit: i32,
+        offset: i32,
+    ) -> Result<ListUsersResponse, AppError> {
+        let mut request = Request::new(blog_grpc::ListUsersRequest { limit, offset });
+        request
+            .metadata_mut()
+            .insert("authorization", format!("Bearer {}", token).parse()?);
+
+        let users = self.connection.list_users(request).await?;
+
+        Ok(users.into_inner())
+    }
+
+    async fn set_user_role(
+        &mut self,
+        token: &str,
+        user_id: &str,
+        role: &str,
+    ) -> Result<UserResponse, AppError> {
+        let mut request = Request::new(blog_grpc::SetUserRoleRequest {
+            user_id: user_id.to_string(),
+            role: role.to_string(),
+        });
+        request
+            .metadata_mut()
+            .insert("authorization", format!("Bearer {}", token).parse()?);
+
+        let user = self.connection.set_user_role(request).await?;
+
+        Ok(user.into_inner())
+    }
+
+    async fn create_personal_token(
+        &mut self,
+        token: &str,
+        name: &str,
+        scopes: &[String],
+        expires_in_days: Option<i64>,
+    ) -> Result<CreatePersonalAccessTokenResponse, AppError> {
+        let mut request = Request::new(blog_grpc::CreatePersonalAccessTokenRequest {
+            name: name.to_string(),
+            scopes: scopes.to_vec(),
+            expires_in_days,
+        });
+        request
+            .metadata_mut()
+            .insert("authorization", format!("Bearer {}", token).parse()?);
+
+        let response = self
+            .connection
+            .create_personal_access_token(request)
+            .await?;
+
+        Ok(response.into_inner())
+    }
+
+    async fn list_personal_tokens(
+        &mut self,
+        token: &str,
+    ) -> Result<ListPersonalAccessTokensResponse, AppError> {
+        let mut request = Request::new(blog_grpc::ListPersonalAccessTokensRequest {});
+        request
+            .metadata_mut()
+            .insert("authorization", format!("Bearer {}", token).parse()?);
+
+        let response = self.connection.list_personal_access_tokens(request).await?;
+
+        Ok(response.into_inner())
+    }
+
+    async fn revoke_personal_token(
+        &mut self,
+        token: &str,
+        token_id: &str,
+    ) -> Result<RevokePersonalAccessTokenResponse, AppError> {
+        let mut request = Request::new(blog_grpc::RevokePersonalAccessTokenRequest {
+            id: token_id.to_string(),
+        });
+        request
+            .metadata_mut()
+            .insert("authorization", format!("Bearer {}", token).parse()?);
+
+        let response = self
+            .connection
+            .revoke_personal_access_token(request)
+            .await?;
+
+        Ok(response.into_inner())
+    }
+
+    async fn get_user_profile(&mut self, username: &str) -> Result<UserProfileResponse, AppError> {
+        let profile = self
+            .connection
+            .get_user_profile(blog_grpc::GetUserProfileRequest {
+                username: username.to_string(),
+            })
+            .await?;
+
+        Ok(profile.into_inner())
+    }
+
+    async fn list_user_posts(
+        &mut self,
+        username: &str,
+        limit: i32,
+        offset: i32,
+    ) -> Result<ListPostsResponse, AppError> {
+        let posts = self
+            .connection
+            .list_user_posts(blog_grpc::ListUserPostsRequest {
+                username: username.to_string(),
+                limit,
+                offset,
+            })
+            .await?;
+
+        Ok(posts.into_inner())
+    }
+
+    async fn update_profile(
+        &mut self,
+        token: &str,
+        profile: UpdateProfileRequest,
+    ) -> Result<UserProfileResponse, AppError> {
+        let mut request = Request::new(profile);
+        request
+            .metadata_mut()
+            .insert("authorization", format!("Bearer {}", token).parse()?);
+
+        let profile = self.connection.update_profile(request).await?;
+
+        Ok(profile.into_inner())
+    }
+
+    async fn export_account(&mut self, token: &str) -> Result<ExportAccountResponse, AppError> {
+        let mut request = Request::new(blog_grpc::ExportAccountRequest {});
+        request
+            .metadata_mut()
+            .insert("authorization", format!("Bearer {}", token).parse()?);
+
+        let response = self.connection.export_account(request).await?;
+
+        Ok(response.into_inner())
+    }
+
+    async fn delete_account(
+        &mut self,
+        token: &str,
+        password: &str,
+        posts: &str,
+    ) -> Result<DeleteAccountResponse, AppError> {
+        let mut request = Request::new(blog_grpc::DeleteAccountRequest {
+            password: password.to_string(),
+            posts: posts.to_string(),
+        });
+        request
+            .metadata_mut()
+            .insert("authorization", format!("Bearer {}", token).parse()?);
+
+        let response = self.connection.delete_account(request).await?;
+
+        Ok(response.into_inner())
+    }
+
+    async fn react(
+        &mut self,
+        token: &str,
+        post_id: i64,
+        kind: &str,
+        remove: bool,
+    ) -> Result<PostReactions, AppError> {
+        let mut request = Request::new(blog_grpc::ReactionRequest {
+            post_id,
+            kind: kind.to_string(),
+        });
+        request
+            .metadata_mut()
+            .insert("authorization", format!("Bearer {}", token).parse()?);
+
+        let response = if remove {
+            self.connection.unreact(request).await?
+        } else {
+            self.connection.react(request).await?
+        };
+
+        Ok(response.into_inner())
+    }
+
+    async fn follow(
+        &mut self,
+        token: &str,
+        username: &str,
+        remove: bool,
+    ) -> Result<FollowStatus, AppError> {
+        let mut request = Request::new(blog_grpc::FollowRequest {
+            username: username.to_string(),
+        });
+        request
+            .metadata_mut()
+            .insert("authorization", format!("Bearer {}", token).parse()?);
+
+        let response = if remove {
+            self.connection.unfollow(request).await?
+        } else {
+            self.connection.follow(request).await?
+        };
+
+        Ok(response.into_inner())
+    }
+
+    async fn list_follows(
+        &mut self,
+        username: &str,
+        following: bool,
+        limit: i32,
+        offset: i32,
+    ) -> Result<ListFollowsResponse, AppError> {
+        let request = blog_grpc::ListFollowsRequest {
+            username: username.to_string(),
+            limit,
+            offset,
+        };
+        let response = if following {
+            self.connection.list_following(request).await?
+        } else {
+            self.connection.list_followers(request).await?
+        };
+
+        Ok(response.into_inner())
+    }
+
+    async fn get_feed(
+        &mut self,
+        token: &str,
+        limit: i32,
+        offset: i32,
+        cursor: Option<&str>,
+    ) -> Result<ListPostsResponse, AppError> {
+        let mut request = Request::new(blog_grpc::FeedRequest {
+            limit,
+            offset,
+            cursor: cursor.map(str::to_string),
+        });
+        request
+            .metadata_mut()
+            .insert("authorization", format!("Bearer {}", token).parse()?);
+
+        let response = self.connection.get_feed(request).await?;
+
+        Ok(response.into_inner())
+    }
+
+    async fn bookmark(
+        &mut self,
+        token: &str,
+        post_id: i64,
+        remove: bool,
+    ) -> Result<BookmarkResponse, AppError> {
+        let mut request = Request::new(blog_grpc::BookmarkRequest { post_id });
+        request
+            .metadata_mut()
+            .insert("authorization", format!("Bearer {}", token).parse()?);
+
+        let response = if remove {
+            self.connection.remove_bookmark(request).await?
+        } else {
+            self.connection.add_bookmark(request).await?
+        };
+
+        Ok(response.into_inner())
+    }
+
+    async fn list_bookmarks(
+        &mut self,
+        token: &str,
+        limit: i32,
+        offset: i32,
+    ) -> Result<ListPostsResponse, AppError> {
+        let mut request = Request::new(blog_grpc::ListBookmarksRequest { limit, offset });
+        request
+            .metadata_mut()
+            .insert("authorization", format!("Bearer {}", token).parse()?);
+
+        let response = self.connection.list_bookmarks(request).await?;
+
+        Ok(response.into_inner())
+    }
 }
@@ -2,7 +2,16 @@ use async_trait::async_trait;
 
 use crate::{
     BlogCommands,
-    blog_grpc::{self, AuthResponse, DeletePostResponse, ListPostsResponse, Post, PostResponse},
+    blog_grpc::{
+        self, AttachmentResponse, AuthResponse, BookmarkResponse,
+        CreatePersonalAccessTokenResponse, DeleteAccountResponse, DeletePostResponse,
+        ExportAccountResponse, FollowStatus, ListFollowsResponse, ListPersonalAccessTokensResponse,
+        ListPostsResponse, ListRevisionsResponse, ListUsersResponse, LogoutResponse,
+        PasswordResetResponse, Post, PostReactions, PostResponse,
+        RevokePersonalAccessTokenResponse, SearchPostsResponse, TwoFactorEnrollment,
+        TwoFactorResponse, UpdateProfileRequest, UserProfileResponse, UserResponse,
+        VerifyEmailResponse,
+    },
     error::AppError,
 };
 
@@ -18,6 +27,32 @@ impl HttpClient {
             connection: reqwest::Client::new(),
         }
     }
+
+    async fn post_two_factor_code(
+        &mut self,
+        token: &str,
+        action: &str,
+        code: &str,
+    ) -> Result<TwoFactorResponse, AppError> {
+        let request_path = format!("{}/protect/account/2fa/{}", self.addr, action);
+        let request_body = blog_grpc::TwoFactorCodeRequest {
+            code: code.to_string(),
+        };
+        let responce = self
+            .connection
+            .post(request_path)
+            .header(reqwest::header::AUTHORIZATION, get_auth_header(token))
+            .json(&request_body)
+            .send()
+            .await?;
+
+        let status = responce.status();
+        if status.is_success() {
+            return Ok(TwoFactorResponse { success: true });
+        }
+
+        Err(AppError::Internal(format!("Server responce: {}", status)))
+    }
 }
 
 #[async_trait]
@@ -65,6 +100,25 @@ impl BlogCommands for HttpClient {
             .json(&request_body)
             .send()
             .await?;
+        // 202: пароль верный, нужен код второго фактора
+        if responce.status() == reqwest::StatusCode::ACCEPTED {
+            #[derive(serde::Deserialize)]
+            struct Challenge {
+                two_factor_challenge: String,
+                expires_in: i64,
+            }
+
+            let challenge = responce
+                .json::<Challenge>()
+                .await
+                .map_err(|e| AppError::Internal(e.to_string()))?;
+            return Ok(AuthResponse {
+                token: String::new(),
+                refresh_token: String::new(),
+                expires_in: challenge.expires_in,
+                two_factor_challenge: Some(challenge.two_factor_challenge),
+            });
+        }
         if responce.status().is_success() {
             if let Ok(token) = responce.json::<AuthResponse>().await {
                 return Ok(token);
@@ -78,16 +132,268 @@ impl BlogCommands for HttpClient {
         }
     }
 
+    async fn login_two_factor(
+        &mut self,
+        challenge: &str,
+        code: &str,
+    ) -> Result<AuthResponse, AppError> {
+        let request_path = format!("{}/api/auth/login/two-factor", self.addr);
+        let request_body = blog_grpc::LoginTwoFactorRequest {
+            challenge: challenge.to_string(),
+            code: code.to_string(),
+        };
+        let responce = self
+            .connection
+            .post(request_path)
+            .json(&request_body)
+            .send()
+            .await?;
+
+        let status = responce.status();
+        if status.is_success() {
+            return responce
+                .json::<AuthResponse>()
+                .await
+                .map_err(|e| AppError::Internal(e.to_string()));
+        }
+
+        Err(AppError::Internal(format!("Server responce: {}", status)))
+    }
+
+    async fn enroll_two_factor(&mut self, token: &str) -> Result<TwoFactorEnrollment, AppError> {
+        let request_path = format!("{}/protect/account/2fa/enroll", self.addr);
+        let responce = self
+            .connection
+            .post(request_path)
+            .header(reqwest::header::AUTHORIZATION, get_auth_header(token))
+            .send()
+            .await?;
+
+        let status = responce.status();
+        if status.is_success() {
+            return responce
+                .json::<TwoFactorEnrollment>()
+                .await
+                .map_err(|e| AppError::Internal(e.to_string()));
+        }
+
+        Err(AppError::Internal(format!("Server responce: {}", status)))
+    }
+
+    async fn confirm_two_factor(
+        &mut self,
+        token: &str,
+        code: &str,
+    ) -> Result<TwoFactorResponse, AppError> {
+        self.post_two_factor_code(token, "confirm", code).await
+    }
+
+    async fn disable_two_factor(
+        &mut self,
+        token: &str,
+        code: &str,
+    ) -> Result<TwoFactorResponse, AppError> {
+        self.post_two_factor_code(token, "disable", code).await
+    }
+
+    async fn refresh(&mut self, refresh_token: &str) -> Result<AuthResponse, AppError> {
+        let request_path = format!("{}/api/auth/refresh", self.addr);
+        let request_body = blog_grpc::RefreshRequest {
+            refresh_token: refresh_token.to_string(),
+        };
+        let responce = self
+            .connection
+            .post(request_path)
+            .json(&request_body)
+            .send()
+            .await?;
+
+        let status = responce.status();
+        if status == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(AppError::Unauthorized);
+        }
+        if status.is_success() {
+            return responce
+                .json::<AuthResponse>()
+                .await
+                .map_err(|e| AppError::Internal(e.to_string()));
+        }
+
+        Err(AppError::Internal(format!("Server responce: {}", status)))
+    }
+
+    async fn logout(
+        &mut self,
+        token: &str,
+        refresh_token: &str,
+    ) -> Result<LogoutResponse, AppError> {
+        let request_path = format!("{}/protect/logout", self.addr);
+        let request_body = blog_grpc::LogoutRequest {
+            refresh_token: refresh_token.to_string(),
+        };
+        let responce = self
+            .connection
+            .post(request_path)
+            .header(reqwest::header::AUTHORIZATION, get_auth_header(token))
+            .json(&request_body)
+            .send()
+            .await?;
+
+        let status = responce.status();
+        if status.is_success() {
+            return Ok(LogoutResponse { success: true });
+        }
+
+        Err(AppError::Internal(format!("Server responce: {}", status)))
+    }
+
+    async fn logout_all(&mut self, token: &str) -> Result<LogoutResponse, AppError> {
+        let request_path = format!("{}/protect/logout-all", self.addr);
+        let responce = self
+            .connection
+            .post(request_path)
+            .header(reqwest::header::AUTHORIZATION, get_auth_header(token))
+            .send()
+            .await?;
+
+        let status = responce.status();
+        if status.is_success() {
+            return Ok(LogoutResponse { success: true });
+        }
+
+        Err(AppError::Internal(format!("Server responce: {}", status)))
+    }
+
+    async fn change_password(
+        &mut self,
+        token: &str,
+        current_password: &str,
+        new_password: &str,
+    ) -> Result<AuthResponse, AppError> {
+        let request_path = format!("{}/protect/account/password", self.addr);
+        let request_body = blog_grpc::ChangePasswordRequest {
+            current_password: current_password.to_string(),
+            new_password: new_password.to_string(),
+        };
+        let responce = self
+            .connection
+            .post(request_path)
+            .header(reqwest::header::AUTHORIZATION, get_auth_header(token))
+            .json(&request_body)
+            .send()
+            .await?;
+
+        let status = responce.status();
+        if status.is_success() {
+            return responce
+                .json::<AuthResponse>()
+                .await
+                .map_err(|e| AppError::Internal(e.to_string()));
+        }
+
+        Err(AppError::Internal(format!("Server responce: {}", status)))
+    }
+
+    async fn request_password_reset(
+        &mut self,
+        email: &str,
+    ) -> Result<PasswordResetResponse, AppError> {
+        let request_path = format!("{}/api/auth/password-reset", self.addr);
+        let request_body = blog_grpc::PasswordResetRequest {
+            email: email.to_string(),
+        };
+        let responce = self
+            .connection
+            .post(request_path)
+            .json(&request_body)
+            .send()
+            .await?;
+
+        let status = responce.status();
+        if status.is_success() {
+            return Ok(PasswordResetResponse { success: true });
+        }
+
+        Err(AppError::Internal(format!("Server responce: {}", status)))
+    }
+
+    async fn confirm_password_reset(
+        &mut self,
+        reset_token: &str,
+        new_password: &str,
+    ) -> Result<PasswordResetResponse, AppError> {
+        let request_path = format!("{}/api/auth/password-reset/confirm", self.addr);
+        let request_body = blog_grpc::ConfirmPasswordResetRequest {
+            token: reset_token.to_string(),
+            new_password: new_password.to_string(),
+        };
+        let responce = self
+            .connection
+            .post(request_path)
+            .json(&request_body)
+            .send()
+            .await?;
+
+        let status = responce.status();
+        if status.is_success() {
+            return Ok(PasswordResetResponse { success: true });
+        }
+
+        Err(AppError::Internal(format!("Server responce: {}", status)))
+    }
+
+    async fn verify_email(&mut self, verify_token: &str) -> Result<VerifyEmailResponse, AppError> {
+        let request_path = format!("{}/api/auth/verify-email", self.addr);
+        let request_body = blog_grpc::VerifyEmailRequest {
+            token: verify_token.to_string(),
+        };
+        let responce = self
+            .connection
+            .post(request_path)
+            .json(&request_body)
+            .send()
+            .await?;
+
+        let status = responce.status();
+        if status.is_success() {
+            return Ok(VerifyEmailResponse { success: true });
+        }
+
+        Err(AppError::Internal(format!("Server responce: {}", status)))
+    }
+
+    async fn resend_verification(&mut self, token: &str) -> Result<VerifyEmailResponse, AppError> {
+        let request_path = format!("{}/protect/account/verify-email/resend", self.addr);
+        let responce = self
+            .connection
+            .post(request_path)
+            .header(reqwest::header::AUTHORIZATION, get_auth_header(token))
+            .send()
+            .await?;
+
+        let status = responce.status();
+        if status.is_success() {
+            return Ok(VerifyEmailResponse { success: true });
+        }
+
+        Err(AppError::Internal(format!("Server responce: {}", status)))
+    }
+
     async fn create_post(
         &mut self,
         token: &str,
         title: &str,
         content: &str,
+        publish_at: Option<i64>,
     ) -> Result<PostResponse, AppError> {
         let request_path = format!("{}/protect/post", self.addr);
         let request_body = blog_grpc::CreatePostRequest {
             title: title.to_string(),
             content: content.to_string(),
+            tags: Vec::new(),
+            draft: false,
+            publish_at,
+            content_format: None,
         };
 
         let responce = self
@@ -115,12 +421,17 @@ impl BlogCommands for HttpClient {
         post_id: i64,
         title: Option<String>,
         content: Option<String>,
+        publish_at: Option<i64>,
     ) -> Result<PostResponse, AppError> {
         let request_path = format!("{}/protect/post/{}", self.addr, post_id);
         let request_body = blog_grpc::UpdatePostRequest {
             id: post_id,
             title,
             content,
+            tags: None,
+            status: None,
+            publish_at,
+            content_format: None,
         };
 
         let responce = self
@@ -179,8 +490,22 @@ impl BlogCommands for HttpClient {
         &mut self,
         limmit: i32,
         offset: i32,
+        cursor: Option<&str>,
+        order: Option<&str>,
     ) -> Result<ListPostsResponse, AppError> {
-        let request_path = format!("{}/api/posts?limit={}&offset={}", self.addr, limmit, offset);
+        let mut params = vec![
+            ("limit", limmit.to_string()),
+            ("offset", offset.to_string()),
+        ];
+        if let Some(cursor) = cursor {
+            params.push(("cursor", cursor.to_string()));
+        }
+        if let Some(order) = order {
+            params.push(("order", order.to_string()));
+        }
+        let request_path =
+            reqwest::Url::parse_with_params(&format!("{}/api/posts", self.addr), &params)
+                .map_err(|e| AppError::Internal(e.to_string()))?;
         let responce = self.connection.get(request_path).send().await?;
         if responce.status().is_success() {
             return responce
@@ -191,6 +516,541 @@ impl BlogCommands for HttpClient {
 
         Err(AppError::NotFound)
     }
+
+    async fn search_posts(
+        &mut self,
+        query: &str,
+        limit: i32,
+        offset: i32,
+    ) -> Result<SearchPostsResponse, AppError> {
+        let request_path = reqwest::Url::parse_with_params(
+            &format!("{}/api/search", self.addr),
+            &[
+                ("q", query.to_string()),
+                ("limit", limit.to_string()),
+                ("offset", offset.to_string()),
+            ],
+        )
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+        let responce = self.connection.get(request_path).send().await?;
+        let status = responce.status();
+        if status.is_success() {
+            return responce
+                .json::<SearchPostsResponse>()
+                .await
+                .map_err(|e| AppError::Internal(e.to_string()));
+        }
+
+        Err(AppError::Internal(format!("Server responce: {}", status)))
+    }
+
+    async fn list_revisions(&mut self, post_id: i64) -> Result<ListRevisionsResponse, AppError> {
+        let request_path = format!("{}/api/posts/{}/revisions", self.addr, post_id);
+        let responce = self.connection.get(request_path).send().await?;
+        if responce.status().is_success() {
+            return responce
+                .json::<ListRevisionsResponse>()
+                .await
+                .map_err(|e| AppError::Internal(e.to_string()));
+        }
+
+        Err(AppError::NotFound)
+    }
+
+    async fn restore_revision(
+        &mut self,
+        token: &str,
+        post_id: i64,
+        revision: i32,
+    ) -> Result<PostResponse, AppError> {
+        let request_path = format!(
+            "{}/protect/post/{}/revisions/{}/restore",
+            self.addr, post_id, revision
+        );
+
+        let responce = self
+            .connection
+            .post(request_path)
+            .header(reqwest::header::AUTHORIZATION, get_auth_header(token))
+            .send()
+            .await?;
+
+        let status = responce.status();
+        if status.is_success()
+            && let Ok(res) = responce.json::<Post>().await
+        {
+            return Ok(PostResponse { post: Some(res) });
+        }
+
+        Err(AppError::Internal(format!("Server responce: {}", status)))
+    }
+
+    async fn upload_attachment(
+        &mut self,
+        token: &str,
+        file_name: &str,
+        data: Vec<u8>,
+        post_id: Option<i64>,
+    ) -> Result<AttachmentResponse, AppError> {
+        let mut request_path = reqwest::Url::parse(&format!("{}/protect/attachments", self.addr))
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+        if let Some(post_id) = post_id {
+            request_path
+                .query_pairs_mut()
+                .append_pair("post_id", &post_id.to_string());
+        }
+        let form = reqwest::multipart::Form::new().part(
+            "file",
+            reqwest::multipart::Part::bytes(data).file_name(file_name.to_string()),
+        );
+
+        let responce = self
+            .connection
+            .post(request_path)
+            .header(reqwest::header::AUTHORIZATION, get_auth_header(token))
+            .multipart(form)
+            .send()
+            .await?;
+
+        let status = responce.status();
+        if status.is_success() {
+            return responce
+                .json::<AttachmentResponse>()
+                .await
+                .map_err(|e| AppError::Internal(e.to_string()));
+        }
+
+        Err(AppError::Internal(format!("Server responce: {}", status)))
+    }
+
+    async fn list_users(
+        &mut self,
+        token: &str,
+        limit: i32,
+        offset: i32,
+    ) -> Result<ListUsersResponse, AppError> {
+        let request_path = reqwest::Url::parse_with_params(
+            &format!("{}/protect/admin/users", self.addr),
+            &[("limit", limit.to_string()), ("offset", offset.to_string())],
+        )
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        let responce = self
+            .connection
+            .get(request_path)
+            .header(reqwest::header::AUTHORIZATION, get_auth_header(token))
+            .send()
+            .await?;
+
+        let status = responce.status();
+        if status.is_success() {
+            return responce
+                .json::<ListUsersResponse>()
+                .await
+                .map_err(|e| AppError::Internal(e.to_string()));
+        }
+
+        Err(AppError::Internal(format!("Server responce: {}", status)))
+    }
+
+    async fn set_user_role(
+        &mut self,
+        token: &str,
+        user_id: &str,
+        role: &str,
+    ) -> Result<UserResponse, AppError> {
+        let request_path = format!("{}/protect/admin/users/{}/role", self.addr, user_id);
+        let request_body = blog_grpc::SetUserRoleRequest {
+            user_id: user_id.to_string(),
+            role: role.to_string(),
+        };
+
+        let responce = self
+            .connection
+            .put(request_path)
+            .header(reqwest::header::AUTHORIZATION, get_auth_header(token))
+            .json(&request_body)
+            .send()
+            .await?;
+
+        let status = responce.status();
+        if status.is_success() {
+            return responce
+                .json::<UserResponse>()
+                .await
+                .map_err(|e| AppError::Internal(e.to_string()));
+        }
+
+        Err(AppError::Internal(format!("Server responce: {}", status)))
+    }
+
+    async fn create_personal_token(
+        &mut self,
+        token: &str,
+        name: &str,
+        scopes: &[String],
+        expires_in_days: Option<i64>,
+    ) -> Result<CreatePersonalAccessTokenResponse, AppError> {
+        let request_path = format!("{}/protect/tokens", self.addr);
+        let request_body = blog_grpc::CreatePersonalAccessTokenRequest {
+            name: name.to_string(),
+            scopes: scopes.to_vec(),
+            expires_in_days,
+        };
+
+        let responce = self
+            .connection
+            .post(request_path)
+            .header(reqwest::header::AUTHORIZATION, get_auth_header(token))
+            .json(&request_body)
+            .send()
+            .await?;
+
+        let status = responce.status();
+        if status.is_success() {
+            return responce
+                .json::<CreatePersonalAccessTokenResponse>()
+                .await
+                .map_err(|e| AppError::Internal(e.to_string()));
+        }
+
+        Err(AppError::Internal(format!("Server responce: {}", status)))
+    }
+
+    async fn list_personal_tokens(
+        &mut self,
+        token: &str,
+    ) -> Result<ListPersonalAccessTokensResponse, AppError> {
+        let request_path = format!("{}/protect/tokens", self.addr);
+        let responce = self
+            .connection
+            .get(request_path)
+            .header(reqwest::header::AUTHORIZATION, get_auth_header(token))
+            .send()
+            .await?;
+
+        let status = responce.status();
+        if status.is_success() {
+            return responce
+                .json::<ListPersonalAccessTokensResponse>()
+                .await
+                .map_err(|e| AppError::Internal(e.to_string()));
+        }
+
+        Err(AppError::Internal(format!("Server responce: {}", status)))
+    }
+
+    async fn revoke_personal_token(
+        &mut self,
+        token: &str,
+        token_id: &str,
+    ) -> Result<RevokePersonalAccessTokenResponse, AppError> {
+        let request_path = format!("{}/protect/tokens/{}", self.addr, token_id);
+        let responce = self
+            .connection
+            .delete(request_path)
+            .header(reqwest::header::AUTHORIZATION, get_auth_header(token))
+            .send()
+            .await?;
+
+        let status = responce.status();
+        if status.is_success() {
+            return Ok(RevokePersonalAccessTokenResponse { success: true });
+        }
+
+        Err(AppError::Internal(format!("Server responce: {}", status)))
+    }
+
+    async fn get_user_profile(&mut self, username: &str) -> Result<UserProfileResponse, AppError> {
+        let request_path = format!("{}/api/users/{}", self.addr, username);
+        let responce = self.connection.get(request_path).send().await?;
+        if responce.status().is_success() {
+            return responce
+                .json::<UserProfileResponse>()
+                .await
+                .map_err(|e| AppError::Internal(e.to_string()));
+        }
+
+        Err(AppError::NotFound)
+    }
+
+    async fn list_user_posts(
+        &mut self,
+        username: &str,
+        limit: i32,
+        offset: i32,
+    ) -> Result<ListPostsResponse, AppError> {
+        let request_path = reqwest::Url::parse_with_params(
+            &format!("{}/api/users/{}/posts", self.addr, username),
+            &[("limit", limit.to_string()), ("offset", offset.to_string())],
+        )
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+        let responce = self.connection.get(request_path).send().await?;
+        if responce.status().is_success() {
+            return responce
+                .json::<ListPostsResponse>()
+                .await
+                .map_err(|e| AppError::Internal(e.to_string()));
+        }
+
+        Err(AppError::NotFound)
+    }
+
+    async fn update_profile(
+        &mut self,
+        token: &str,
+        profile: UpdateProfileRequest,
+    ) -> Result<UserProfileResponse, AppError> {
+        let request_path = format!("{}/protect/account/profile", self.addr);
+
+        let responce = self
+            .connection
+            .put(request_path)
+            .header(reqwest::header::AUTHORIZATION, get_auth_header(token))
+            .json(&profile)
+            .send()
+            .await?;
+
+        let status = responce.status();
+        if status.is_success() {
+            return responce
+                .json::<UserProfileResponse>()
+                .await
+                .map_err(|e| AppError::Internal(e.to_string()));
+        }
+
+        Err(AppError::Internal(format!("Server responce: {}", status)))
+    }
+
+    async fn export_account(&mut self, token: &str) -> Result<ExportAccountResponse, AppError> {
+        let request_path = format!("{}/protect/account/export", self.addr);
+        let responce = self
+            .connection
+            .get(request_path)
+            .header(reqwest::header::AUTHORIZATION, get_auth_header(token))
+            .send()
+            .await?;
+
+        let status = responce.status();
+        if status.is_success() {
+            let file_name = responce
+                .headers()
+                .get(reqwest::header::CONTENT_DISPOSITION)
+                .and_then(|value| value.to_str().ok())
+                .and_then(file_name_from_disposition)
+                .unwrap_or_else(|| "blog-export.json".to_string());
+            let data = responce.bytes().await?.to_vec();
+            return Ok(ExportAccountResponse { file_name, data });
+        }
+
+        Err(AppError::Internal(format!("Server responce: {}", status)))
+    }
+
+    async fn delete_account(
+        &mut self,
+        token: &str,
+        password: &str,
+        posts: &str,
+    ) -> Result<DeleteAccountResponse, AppError> {
+        let request_path = format!("{}/protect/account/delete", self.addr);
+        let request_body = blog_grpc::DeleteAccountRequest {
+            password: password.to_string(),
+            posts: posts.to_string(),
+        };
+        let responce = self
+            .connection
+            .post(request_path)
+            .header(reqwest::header::AUTHORIZATION, get_auth_header(token))
+            .json(&request_body)
+            .send()
+            .await?;
+
+        let status = responce.status();
+        if status.is_success() {
+            return Ok(DeleteAccountResponse { success: true });
+        }
+
+        Err(AppError::Internal(format!("Server responce: {}", status)))
+    }
+
+    async fn react(
+        &mut self,
+        token: &str,
+        post_id: i64,
+        kind: &str,
+        remove: bool,
+    ) -> Result<PostReactions, AppError> {
+        let request_path = format!("{}/protect/post/{}/reaction/{}", self.addr, post_id, kind);
+        let request = if remove {
+            self.connection.delete(request_path)
+        } else {
+            self.connection.put(request_path)
+        };
+        let responce = request
+            .header(reqwest::header::AUTHORIZATION, get_auth_header(token))
+            .send()
+            .await?;
+
+        let status = responce.status();
+        if status.is_success() {
+            return responce
+                .json::<PostReactions>()
+                .await
+                .map_err(|e| AppError::Internal(e.to_string()));
+        }
+
+        Err(AppError::Internal(format!("Server responce: {}", status)))
+    }
+
+    async fn follow(
+        &mut self,
+        token: &str,
+        username: &str,
+        remove: bool,
+    ) -> Result<FollowStatus, AppError> {
+        let request_path = format!("{}/protect/users/{}/follow", self.addr, username);
+        let request = if remove {
+            self.connection.delete(request_path)
+        } else {
+            self.connection.put(request_path)
+        };
+        let responce = request
+            .header(reqwest::header::AUTHORIZATION, get_auth_header(token))
+            .send()
+            .await?;
+
+        let status = responce.status();
+        if status.is_success() {
+            return responce
+                .json::<FollowStatus>()
+                .await
+                .map_err(|e| AppError::Internal(e.to_string()));
+        }
+
+        Err(AppError::Internal(format!("Server responce: {}", status)))
+    }
+
+    async fn list_follows(
+        &mut self,
+        username: &str,
+        following: bool,
+        limit: i32,
+        offset: i32,
+    ) -> Result<ListFollowsResponse, AppError> {
+        let list = if following { "following" } else { "followers" };
+        let request_path = reqwest::Url::parse_with_params(
+            &format!("{}/api/users/{}/{}", self.addr, username, list),
+            &[("limit", limit.to_string()), ("offset", offset.to_string())],
+        )
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+        let responce = self.connection.get(request_path).send().await?;
+        if responce.status().is_success() {
+            return responce
+                .json::<ListFollowsResponse>()
+                .await
+                .map_err(|e| AppError::Internal(e.to_string()));
+        }
+
+        Err(AppError::NotFound)
+    }
+
+    async fn get_feed(
+        &mut self,
+        token: &str,
+        limit: i32,
+        offset: i32,
+        cursor: Option<&str>,
+    ) -> Result<ListPostsResponse, AppError> {
+        let mut params = vec![("limit", limit.to_string()), ("offset", offset.to_string())];
+        if let Some(cursor) = cursor {
+            params.push(("cursor", cursor.to_string()));
+        }
+        let request_path =
+            reqwest::Url::parse_with_params(&format!("{}/protect/feed", self.addr), &params)
+                .map_err(|e| AppError::Internal(e.to_string()))?;
+        let responce = self
+            .connection
+            .get(request_path)
+            .header(reqwest::header::AUTHORIZATION, get_auth_header(token))
+            .send()
+            .await?;
+
+        let status = responce.status();
+        if status.is_success() {
+            return responce
+                .json::<ListPostsResponse>()
+                .await
+                .map_err(|e| AppError::Internal(e.to_string()));
+        }
+
+        Err(AppError::Internal(format!("Server responce: {}", status)))
+    }
+
+    async fn bookmark(
+        &mut self,
+        token: &str,
+        post_id: i64,
+        remove: bool,
+    ) -> Result<BookmarkResponse, AppError> {
+        let request_path = format!("{}/protect/post/{}/bookmark", self.addr, post_id);
+        let request = if remove {
+            self.connection.delete(request_path)
+        } else {
+            self.connection.put(request_path)
+        };
+        let responce = request
+            .header(reqwest::header::AUTHORIZATION, get_auth_header(token))
+            .send()
+            .await?;
+
+        let status = responce.status();
+        if status.is_success() {
+            return responce
+                .json::<BookmarkResponse>()
+                .await
+                .map_err(|e| AppError::Internal(e.to_string()));
+        }
+
+        Err(AppError::Internal(format!("Server responce: {}", status)))
+    }
+
+    async fn list_bookmarks(
+        &mut self,
+        token: &str,
+        limit: i32,
+        offset: i32,
+    ) -> Result<ListPostsResponse, AppError> {
+        let request_path = reqwest::Url::parse_with_params(
+            &format!("{}/protect/bookmarks", self.addr),
+            &[("limit", limit.to_string()), ("offset", offset.to_string())],
+        )
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+        let responce = self
+            .connection
+            .get(request_path)
+            .header(reqwest::header::AUTHORIZATION, get_auth_header(token))
+            .send()
+            .await?;
+
+        let status = responce.status();
+        if status.is_success() {
+            return responce
+                .json::<ListPostsResponse>()
+                .await
+                .map_err(|e| AppError::Internal(e.to_string()));
+        }
+
+        Err(AppError::Internal(format!("Server responce: {}", status)))
+    }
+}
+
+/// Имя файла из `Content-Disposition: attachment; filename="..."`
+fn file_name_from_disposition(value: &str) -> Option<String> {
+    value
+        .split(';')
+        .filter_map(|part| part.trim().strip_prefix("filename="))
+        .map(|name| name.trim_matches('"').to_string())
+        .find(|name| !name.is_empty())
 }
 
 fn get_auth_header(token: &str) -> String {
@@ -1,7 +1,16 @@
 use async_trait::async_trait;
+use base64::Engine;
 
 use crate::{
-    blog_grpc::{AuthResponse, DeletePostResponse, ListPostsResponse, PostResponse},
+    blog_grpc::{
+        AttachmentResponse, AuthResponse, BookmarkResponse, CreatePersonalAccessTokenResponse,
+        DeleteAccountResponse, DeletePostResponse, ExportAccountResponse, FollowStatus,
+        ListFollowsResponse, ListPersonalAccessTokensResponse, ListPostsResponse,
+        ListRevisionsResponse, ListUsersResponse, LogoutResponse, PasswordResetResponse,
+        PostReactions, PostResponse, RevokePersonalAccessTokenResponse, SearchPostsResponse,
+        TwoFactorEnrollment, TwoFactorResponse, UpdateProfileRequest, UserProfileResponse,
+        UserResponse, VerifyEmailResponse,
+    },
     error::AppError,
     grpc_client::GrpcClient,
     http_client::HttpClient,
@@ -15,6 +24,9 @@ mod error;
 mod grpc_client;
 mod http_client;
 
+/// За сколько секунд до истечения access токен считается устаревшим
+const TOKEN_REFRESH_LEEWAY: i64 = 30;
+
 #[derive(Clone)]
 pub enum Transport {
     Http(String),
@@ -26,6 +38,7 @@ pub struct BlogClient {
     http_client: Option<HttpClient>,
     grpc_client: Option<GrpcClient>,
     token: Option<String>,
+    refresh_token: Option<String>,
 }
 
 #[async_trait]
@@ -37,6 +50,46 @@ trait BlogCommands {
         password: &str,
     ) -> Result<AuthResponse, AppError>;
     async fn login(&mut self, username: &str, password: &str) -> Result<AuthResponse, AppError>;
+    async fn refresh(&mut self, refresh_token: &str) -> Result<AuthResponse, AppError>;
+    async fn logout(
+        &mut self,
+        token: &str,
+        refresh_token: &str,
+    ) -> Result<LogoutResponse, AppError>;
+    async fn logout_all(&mut self, token: &str) -> Result<LogoutResponse, AppError>;
+    async fn change_password(
+        &mut self,
+        token: &str,
+        current_password: &str,
+        new_password: &str,
+    ) -> Result<AuthResponse, AppError>;
+    async fn request_password_reset(
+        &mut self,
+        email: &str,
+    ) -> Result<PasswordResetResponse, AppError>;
+    async fn confirm_password_reset(
+        &mut self,
+        reset_token: &str,
+        new_password: &str,
+    ) -> Result<PasswordResetResponse, AppError>;
+    async fn verify_email(&mut self, verify_token: &str) -> Result<VerifyEmailResponse, AppError>;
+    async fn resend_verification(&mut self, token: &str) -> Result<VerifyEmailResponse, AppError>;
+    async fn login_two_factor(
+        &mut self,
+        challenge: &str,
+        code: &str,
+    ) -> Result<AuthResponse, AppError>;
+    async fn enroll_two_factor(&mut self, token: &str) -> Result<TwoFactorEnrollment, AppError>;
+    async fn confirm_two_factor(
+        &mut self,
+        token: &str,
+        code: &str,
+    ) -> Result<TwoFactorResponse, AppError>;
+    async fn disable_two_factor(
+        &mut self,
+        token: &str,
+        code: &str,
+    ) -> Result<TwoFactorResponse, AppError>;
     async fn get_post(&mut self, post_id: i64) -> Result<PostResponse, AppError>;
     async fn update_post(
         &mut self,
@@ -44,6 +97,7 @@ trait BlogCommands {
         post_id: i64,
         title: Option<String>,
         content: Option<String>,
+        publish_at: Option<i64>,
     ) -> Result<PostResponse, AppError>;
     async fn delete_post(
         &mut self,
@@ -55,8 +109,122 @@ trait BlogCommands {
         token: &str,
         title: &str,
         content: &str,
+        publish_at: Option<i64>,
     ) -> Result<PostResponse, AppError>;
-    async fn list_posts(&mut self, limit: i32, offset: i32) -> Result<ListPostsResponse, AppError>;
+    async fn list_posts(
+        &mut self,
+        limit: i32,
+        offset: i32,
+        cursor: Option<&str>,
+        order: Option<&str>,
+    ) -> Result<ListPostsResponse, AppError>;
+    async fn search_posts(
+        &mut self,
+        query: &str,
+        limit: i32,
+        offset: i32,
+    ) -> Result<SearchPostsResponse, AppError>;
+    async fn list_revisions(&mut self, post_id: i64) -> Result<ListRevisionsResponse, AppError>;
+    async fn restore_revision(
+        &mut self,
+        token: &str,
+        post_id: i64,
+        revision: i32,
+    ) -> Result<PostResponse, AppError>;
+    async fn upload_attachment(
+        &mut self,
+        token: &str,
+        file_name: &str,
+        data: Vec<u8>,
+        post_id: Option<i64>,
+    ) -> Result<AttachmentResponse, AppError>;
+    async fn list_users(
+        &mut self,
+        token: &str,
+        limit: i32,
+        offset: i32,
+    ) -> Result<ListUsersResponse, AppError>;
+    async fn set_user_role(
+        &mut self,
+        token: &str,
+        user_id: &str,
+        role: &str,
+    ) -> Result<UserResponse, AppError>;
+    async fn create_personal_token(
+        &mut self,
+        token: &str,
+        name: &str,
+        scopes: &[String],
+        expires_in_days: Option<i64>,
+    ) -> Result<CreatePersonalAccessTokenResponse, AppError>;
+    async fn list_personal_tokens(
+        &mut self,
+        token: &str,
+    ) -> Result<ListPersonalAccessTokensResponse, AppError>;
+    async fn revoke_personal_token(
+        &mut self,
+        token: &str,
+        token_id: &str,
+    ) -> Result<RevokePersonalAccessTokenResponse, AppError>;
+    async fn get_user_profile(&mut self, username: &str) -> Result<UserProfileResponse, AppError>;
+    async fn list_user_posts(
+        &mut self,
+        username: &str,
+        limit: i32,
+        offset: i32,
+    ) -> Result<ListPostsResponse, AppError>;
+    async fn update_profile(
+        &mut self,
+        token: &str,
+        profile: UpdateProfileRequest,
+    ) -> Result<UserProfileResponse, AppError>;
+    async fn export_account(&mut self, token: &str) -> Result<ExportAccountResponse, AppError>;
+    async fn delete_account(
+        &mut self,
+        token: &str,
+        password: &str,
+        posts: &str,
+    ) -> Result<DeleteAccountResponse, AppError>;
+    async fn react(
+        &mut self,
+        token: &str,
+        post_id: i64,
+        kind: &str,
+        remove: bool,
+    ) -> Result<PostReactions, AppError>;
+    async fn follow(
+        &mut self,
+        token: &str,
+        username: &str,
+        remove: bool,
+    ) -> Result<FollowStatus, AppError>;
+    /// `following` - авторы, на которых подписан пользователь, иначе его подписчики
+    async fn list_follows(
+        &mut self,
+        username: &str,
+        following: bool,
+        limit: i32,
+        offset: i32,
+    ) -> Result<ListFollowsResponse, AppError>;
+    async fn get_feed(
+        &mut self,
+        token: &str,
+        limit: i32,
+        offset: i32,
+        cursor: Option<&str>,
+    ) -> Result<ListPostsResponse, AppError>;
+    async fn bookmark(
+        &mut self,
+        token: &str,
+        post_id: i64,
+        remove: bool,
+    ) -> Result<BookmarkResponse, AppError>;
+    async fn list_bookmarks(
+        &mut self,
+        token: &str,
+        limit: i32,
+        offset: i32,
+    ) -> Result<ListPostsResponse, AppError>;
 }
 
 impl BlogClient {
@@ -71,6 +239,7 @@ impl BlogClient {
                     http_client: Some(client),
                     grpc_client: None,
                     token: None,
+                    refresh_token: None,
                 })
             }
 
@@ -82,6 +251,7 @@ impl BlogClient {
                     http_client: None,
                     grpc_client: Some(client),
                     token: None,
+                    refresh_token: None,
                 })
             }
         }
@@ -95,6 +265,92 @@ impl BlogClient {
         self.token.as_ref()
     }
 
+    pub fn set_refresh_token(&mut self, refresh_token: String) {
+        self.refresh_token = Some(refresh_token);
+    }
+
+    pub fn get_refresh_token(&self) -> Option<&String> {
+        self.refresh_token.as_ref()
+    }
+
+    /// Access токен для защищённого запроса. Истекающий токен
+    /// заранее обменивается на новый, если есть refresh токен
+    async fn access_token(&mut self) -> Result<String, AppError> {
+        let token = self.token.clone().ok_or(AppError::Unauthorized)?;
+        if self.refresh_token.is_some() && token_expires_soon(&token) {
+            let response = self.refresh().await?;
+            return Ok(response.token);
+        }
+        Ok(token)
+    }
+
+    fn save_tokens(&mut self, response: &AuthResponse) {
+        self.token = Some(response.token.clone());
+        if !response.refresh_token.is_empty() {
+            self.refresh_token = Some(response.refresh_token.clone());
+        }
+    }
+
+    /// Обменивает refresh токен на новую пару и запоминает её
+    pub async fn refresh(&mut self) -> Result<AuthResponse, AppError> {
+        let refresh_token = self.refresh_token.clone().ok_or(AppError::Unauthorized)?;
+
+        let response = match self.transport {
+            Transport::Http(_) => match &mut self.http_client {
+                Some(client) => client.refresh(&refresh_token).await,
+                None => Err(AppError::Internal("Http client not set".to_string())),
+            },
+            Transport::Grpc(_) => match &mut self.grpc_client {
+                Some(client) => client.refresh(&refresh_token).await,
+                None => Err(AppError::Internal("Grpc client not set".to_string())),
+            },
+        }?;
+
+        self.save_tokens(&response);
+        Ok(response)
+    }
+
+    /// Завершает сессию на сервере и забывает токены
+    pub async fn logout(&mut self) -> Result<LogoutResponse, AppError> {
+        let token = self.access_token().await?;
+        let refresh_token = self.refresh_token.clone().ok_or(AppError::Unauthorized)?;
+
+        let response = match self.transport {
+            Transport::Http(_) => match &mut self.http_client {
+                Some(client) => client.logout(&token, &refresh_token).await,
+                None => Err(AppError::Internal("Http client not set".to_string())),
+            },
+            Transport::Grpc(_) => match &mut self.grpc_client {
+                Some(client) => client.logout(&token, &refresh_token).await,
+                None => Err(AppError::Internal("Grpc client not set".to_string())),
+            },
+        }?;
+
+        self.token = None;
+        self.refresh_token = None;
+        Ok(response)
+    }
+
+    /// Завершает все сессии пользователя, включая текущую
+    pub async fn logout_all(&mut self) -> Result<LogoutResponse, AppError> {
+        let token = self.access_token().await?;
+
+        let response = match self.transport {
+            Transport::Http(_) => match &mut self.http_client {
+                Some(client) => client.logout_all(&token).await,
+                None => Err(AppError::Internal("Http client not set".to_string())),
+            },
+            Transport::Grpc(_) => match &mut self.grpc_client {
+                Some(client) => client.logout_all(&token).await,
+                None => Err(AppError::Internal("Grpc client not set".to_string())),
+            },
+        }?;
+
+        self.token = None;
+        self.refresh_token = None;
+        Ok(response)
+    }
+
     pub async fn register(
         &mut self,
         username: &str,
@@ -104,14 +360,18 @@ impl BlogClient {
         match self.transport {
             Transport::Http(_) => {
                 if let Some(client) = &mut self.http_client {
-                    return client.register(username, email, password).await;
+                    let response = client.register(username, email, password).await?;
+                    self.save_tokens(&response);
+                    return Ok(response);
                 }
 
                 Err(AppError::Internal("Http client not set".to_string()))
             }
             Transport::Grpc(_) => {
                 if let Some(client) = &mut self.grpc_client {
-                    return client.register(username, email, password).await;
+                    let response = client.register(username, email, password).await?;
+                    self.save_tokens(&response);
+                    return Ok(response);
                 }
 
                 Err(AppError::Internal("Grpc client not set".to_string()))
@@ -119,50 +379,224 @@ impl BlogClient {
         }
     }
 
+    /// При включённой 2FA в ответе вместо токенов `two_factor_challenge`,
+    /// вход завершается через `login_two_factor`
     pub async fn login(
         &mut self,
         username: &str,
         password: &str,
     ) -> Result<AuthResponse, AppError> {
+        let response = match self.transport {
+            Transport::Http(_) => match &mut self.http_client {
+                Some(client) => client.login(username, password).await,
+                None => Err(AppError::Internal("Http client not set".to_string())),
+            },
+            Transport::Grpc(_) => match &mut self.grpc_client {
+                Some(client) => client.login(username, password).await,
+                None => Err(AppError::Internal("Grpc client not set".to_string())),
+            },
+        }?;
+
+        if response.two_factor_challenge.is_none() {
+            self.save_tokens(&response);
+        }
+        Ok(response)
+    }
+
+    /// Второй шаг входа: код из приложения или код восстановления
+    pub async fn login_two_factor(
+        &mut self,
+        challenge: &str,
+        code: &str,
+    ) -> Result<AuthResponse, AppError> {
+        let response = match self.transport {
+            Transport::Http(_) => match &mut self.http_client {
+                Some(client) => client.login_two_factor(challenge, code).await,
+                None => Err(AppError::Internal("Http client not set".to_string())),
+            },
+            Transport::Grpc(_) => match &mut self.grpc_client {
+                Some(client) => client.login_two_factor(challenge, code).await,
+                None => Err(AppError::Internal("Grpc client not set".to_string())),
+            },
+        }?;
+
+        self.save_tokens(&response);
+        Ok(response)
+    }
+
+    /// Новый секрет TOTP и коды восстановления. 2FA включается после `confirm_two_factor`
+    pub async fn enroll_two_factor(&mut self) -> Result<TwoFactorEnrollment, AppError> {
+        let token = self.access_token().await?;
+
         match self.transport {
-            Transport::Http(_) => {
-                if let Some(client) = &mut self.http_client {
-                    return client.login(username, password).await;
+            Transport::Http(_) => match &mut self.http_client {
+                Some(client) => client.enroll_two_factor(&token).await,
+                None => Err(AppError::Internal("Http client not set".to_string())),
+            },
+            Transport::Grpc(_) => match &mut self.grpc_client {
+                Some(client) => client.enroll_two_factor(&token).await,
+                None => Err(AppError::Internal("Grpc client not set".to_string())),
+            },
+        }
+    }
+
+    pub async fn confirm_two_factor(&mut self, code: &str) -> Result<TwoFactorResponse, AppError> {
+        let token = self.access_token().await?;
+
+        match self.transport {
+            Transport::Http(_) => match &mut self.http_client {
+                Some(client) => client.confirm_two_factor(&token, code).await,
+                None => Err(AppError::Internal("Http client not set".to_string())),
+            },
+            Transport::Grpc(_) => match &mut self.grpc_client {
+                Some(client) => client.confirm_two_factor(&token, code).await,
+                None => Err(AppError::Internal("Grpc client not set".to_string())),
+            },
+        }
+    }
+
+    pub async fn disable_two_factor(&mut self, code: &str) -> Result<TwoFactorResponse, AppError> {
+        let token = self.access_token().await?;
+
+        match self.transport {
+            Transport::Http(_) => match &mut self.http_client {
+                Some(client) => client.disable_two_factor(&token, code).await,
+                None => Err(AppError::Internal("Http client not set".to_string())),
+            },
+            Transport::Grpc(_) => match &mut self.grpc_client {
+                Some(client) => client.disable_two_factor(&token, code).await,
+                None => Err(AppError::Internal("Grpc client not set".to_string())),
+            },
+        }
+    }
+
+    /// Смена пароля. Другие сессии завершаются, клиент получает новую пару токенов
+    pub async fn change_password(
+        &mut self,
+        current_password: &str,
+        new_password: &str,
+    ) -> Result<AuthResponse, AppError> {
+        let token = self.access_token().await?;
+
+        let response = match self.transport {
+            Transport::Http(_) => match &mut self.http_client {
+                Some(client) => {
+                    client
+                        .change_password(&token, current_password, new_password)
+                        .await
                 }
+                None => Err(AppError::Internal("Http client not set".to_string())),
+            },
+            Transport::Grpc(_) => match &mut self.grpc_client {
+                Some(client) => {
+                    client
+                        .change_password(&token, current_password, new_password)
+                        .await
+                }
+                None => Err(AppError::Internal("Grpc client not set".to_string())),
+            },
+        }?;
 
-                Err(AppError::Internal("Http client not set".to_string()))
-            }
-            Transport::Grpc(_) => {
-                if let Some(client) = &mut self.grpc_client {
-                    return client.login(username, password).await;
+        self.save_tokens(&response);
+        Ok(response)
+    }
+
+    /// Письмо со ссылкой для сброса пароля уходит на `email`, если такой аккаунт есть
+    pub async fn request_password_reset(
+        &mut self,
+        email: &str,
+    ) -> Result<PasswordResetResponse, AppError> {
+        match self.transport {
+            Transport::Http(_) => match &mut self.http_client {
+                Some(client) => client.request_password_reset(email).await,
+                None => Err(AppError::Internal("Http client not set".to_string())),
+            },
+            Transport::Grpc(_) => match &mut self.grpc_client {
+                Some(client) => client.request_password_reset(email).await,
+                None => Err(AppError::Internal("Grpc client not set".to_string())),
+            },
+        }
+    }
+
+    /// `reset_token` - токен из письма
+    pub async fn confirm_password_reset(
+        &mut self,
+        reset_token: &str,
+        new_password: &str,
+    ) -> Result<PasswordResetResponse, AppError> {
+        match self.transport {
+            Transport::Http(_) => match &mut self.http_client {
+                Some(client) => {
+                    client
+                        .confirm_password_reset(reset_token, new_password)
+                        .await
+                }
+                None => Err(AppError::Internal("Http client not set".to_string())),
+            },
+            Transport::Grpc(_) => match &mut self.grpc_client {
+                Some(client) => {
+                    client
+                        .confirm_password_reset(reset_token, new_password)
+                        .await
                 }
+                None => Err(AppError::Internal("Grpc client not set".to_string())),
+            },
+        }
+    }
 
-                Err(AppError::Internal("Grpc client not set".to_string()))
-            }
+    /// `verify_token` - токен из письма
+    pub async fn verify_email(
+        &mut self,
+        verify_token: &str,
+    ) -> Result<VerifyEmailResponse, AppError> {
+        match self.transport {
+            Transport::Http(_) => match &mut self.http_client {
+                Some(client) => client.verify_email(verify_token).await,
+                None => Err(AppError::Internal("Http client not set".to_string())),
+            },
+            Transport::Grpc(_) => match &mut self.grpc_client {
+                Some(client) => client.verify_email(verify_token).await,
+                None => Err(AppError::Internal("Grpc client not set".to_string())),
+            },
+        }
+    }
+
+    pub async fn resend_verification(&mut self) -> Result<VerifyEmailResponse, AppError> {
+        let token = self.access_token().await?;
+
+        match self.transport {
+            Transport::Http(_) => match &mut self.http_client {
+                Some(client) => client.resend_verification(&token).await,
+                None => Err(AppError::Internal("Http client not set".to_string())),
+            },
+            Transport::Grpc(_) => match &mut self.grpc_client {
+                Some(client) => client.resend_verification(&token).await,
+                None => Err(AppError::Internal("Grpc client not set".to_string())),
+            },
         }
     }
 
+    /// `publish_at` (unix time) сохраняет пост черновиком
+    /// и публикует его автоматически в указанное время
     pub async fn create_post(
         &mut self,
         title: &str,
         content: &str,
+        publish_at: Option<i64>,
     ) -> Result<PostResponse, AppError> {
-        if self.token.is_none() {
-            return Err(AppError::Unauthorized);
-        }
-        let token = self.token.clone().unwrap();
+        let token = self.access_token().await?;
 
         match self.transport {
             Transport::Http(_) => {
                 if let Some(client) = &mut self.http_client {
-                    return client.create_post(&token, title, content).await;
+                    return client.create_post(&token, title, content, publish_at).await;
                 }
 
                 Err(AppError::Internal("Http client not set".to_string()))
             }
             Transport::Grpc(_) => {
                 if let Some(client) = &mut self.grpc_client {
-                    return client.create_post(&token, title, content).await;
+                    return client.create_post(&token, title, content, publish_at).await;
                 }
 
                 Err(AppError::Internal("Grpc client not set".to_string()))
@@ -175,23 +609,25 @@ impl BlogClient {
         post_id: i64,
         title: Option<String>,
         content: Option<String>,
+        publish_at: Option<i64>,
     ) -> Result<PostResponse, AppError> {
-        if self.token.is_none() {
-            return Err(AppError::Unauthorized);
-        }
-        let token = self.token.clone().unwrap();
+        let token = self.access_token().await?;
 
         match self.transport {
             Transport::Http(_) => {
                 if let Some(client) = &mut self.http_client {
-                    return client.update_post(&token, post_id, title, content).await;
+                    return client
+                        .update_post(&token, post_id, title, content, publish_at)
+                        .await;
                 }
 
                 Err(AppError::Internal("Http client not set".to_string()))
             }
             Transport::Grpc(_) => {
                 if let Some(client) = &mut self.grpc_client {
-                    return client.update_post(&token, post_id, title, content).await;
+                    return client
+                        .update_post(&token, post_id, title, content, publish_at)
+                        .await;
                 }
 
                 Err(AppError::Internal("Grpc client not set".to_string()))
@@ -200,10 +636,7 @@ impl BlogClient {
     }
 
     pub async fn delete_post(&mut self, post_id: i64) -> Result<DeletePostResponse, AppError> {
-        if self.token.is_none() {
-            return Err(AppError::Unauthorized);
-        }
-        let token = self.token.clone().unwrap();
+        let token = self.access_token().await?;
 
         match self.transport {
             Transport::Http(_) => {
@@ -242,26 +675,478 @@ impl BlogClient {
         }
     }
 
+    /// `cursor` - значение `next_cursor` из предыдущего ответа, имеет приоритет над `offset`.
+    /// `order` - `latest` (по умолчанию) или `liked`, второй листается только по смещению
     pub async fn list_posts(
         &mut self,
         limit: i32,
         offset: i32,
+        cursor: Option<&str>,
+        order: Option<&str>,
     ) -> Result<ListPostsResponse, AppError> {
         match self.transport {
             Transport::Http(_) => {
                 if let Some(client) = &mut self.http_client {
-                    return client.list_posts(limit, offset).await;
+                    return client.list_posts(limit, offset, cursor, order).await;
+                }
+
+                Err(AppError::Internal("Http client not set".to_string()))
+            }
+            Transport::Grpc(_) => {
+                if let Some(client) = &mut self.grpc_client {
+                    return client.list_posts(limit, offset, cursor, order).await;
+                }
+
+                Err(AppError::Internal("Grpc client not set".to_string()))
+            }
+        }
+    }
+
+    pub async fn search_posts(
+        &mut self,
+        query: &str,
+        limit: i32,
+        offset: i32,
+    ) -> Result<SearchPostsResponse, AppError> {
+        match self.transport {
+            Transport::Http(_) => {
+                if let Some(client) = &mut self.http_client {
+                    return client.search_posts(query, limit, offset).await;
                 }
 
                 Err(AppError::Internal("Http client not set".to_string()))
             }
             Transport::Grpc(_) => {
                 if let Some(client) = &mut self.grpc_client {
-                    return client.list_posts(limit, offset).await;
+                    return client.search_posts(query, limit, offset).await;
                 }
 
                 Err(AppError::Internal("Grpc client not set".to_string()))
             }
         }
     }
+
+    pub async fn list_revisions(
+        &mut self,
+        post_id: i64,
+    ) -> Result<ListRevisionsResponse, AppError> {
+        match self.transport {
+            Transport::Http(_) => {
+                if let Some(client) = &mut self.http_client {
+                    return client.list_revisions(post_id).await;
+                }
+
+                Err(AppError::Internal("Http client not set".to_string()))
+            }
+            Transport::Grpc(_) => {
+                if let Some(client) = &mut self.grpc_client {
+                    return client.list_revisions(post_id).await;
+                }
+
+                Err(AppError::Internal("Grpc client not set".to_string()))
+            }
+        }
+    }
+
+    pub async fn restore_revision(
+        &mut self,
+        post_id: i64,
+        revision: i32,
+    ) -> Result<PostResponse, AppError> {
+        let token = self.access_token().await?;
+
+        match self.transport {
+            Transport::Http(_) => {
+                if let Some(client) = &mut self.http_client {
+                    return client.restore_revision(&token, post_id, revision).await;
+                }
+
+                Err(AppError::Internal("Http client not set".to_string()))
+            }
+            Transport::Grpc(_) => {
+                if let Some(client) = &mut self.grpc_client {
+                    return client.restore_revision(&token, post_id, revision).await;
+                }
+
+                Err(AppError::Internal("Grpc client not set".to_string()))
+            }
+        }
+    }
+
+    /// Загрузка файла. `post_id` привязывает файл к посту автора
+    pub async fn upload_attachment(
+        &mut self,
+        file_name: &str,
+        data: Vec<u8>,
+        post_id: Option<i64>,
+    ) -> Result<AttachmentResponse, AppError> {
+        let token = self.access_token().await?;
+
+        match self.transport {
+            Transport::Http(_) => {
+                if let Some(client) = &mut self.http_client {
+                    return client
+                        .upload_attachment(&token, file_name, data, post_id)
+                        .await;
+                }
+
+                Err(AppError::Internal("Http client not set".to_string()))
+            }
+            Transport::Grpc(_) => {
+                if let Some(client) = &mut self.grpc_client {
+                    return client
+                        .upload_attachment(&token, file_name, data, post_id)
+                        .await;
+                }
+
+                Err(AppError::Internal("Grpc client not set".to_string()))
+            }
+        }
+    }
+
+    /// Список пользователей, только для администратора
+    pub async fn list_users(
+        &mut self,
+        limit: i32,
+        offset: i32,
+    ) -> Result<ListUsersResponse, AppError> {
+        let token = self.access_token().await?;
+
+        match self.transport {
+            Transport::Http(_) => match &mut self.http_client {
+                Some(client) => client.list_users(&token, limit, offset).await,
+                None => Err(AppError::Internal("Http client not set".to_string())),
+            },
+            Transport::Grpc(_) => match &mut self.grpc_client {
+                Some(client) => client.list_users(&token, limit, offset).await,
+                None => Err(AppError::Internal("Grpc client not set".to_string())),
+            },
+        }
+    }
+
+    /// Смена роли пользователя (reader, author, moderator, admin)
+    pub async fn set_user_role(
+        &mut self,
+        user_id: &str,
+        role: &str,
+    ) -> Result<UserResponse, AppError> {
+        let token = self.access_token().await?;
+
+        match self.transport {
+            Transport::Http(_) => match &mut self.http_client {
+                Some(client) => client.set_user_role(&token, user_id, role).await,
+                None => Err(AppError::Internal("Http client not set".to_string())),
+            },
+            Transport::Grpc(_) => match &mut self.grpc_client {
+                Some(client) => client.set_user_role(&token, user_id, role).await,
+                None => Err(AppError::Internal("Grpc client not set".to_string())),
+            },
+        }
+    }
+
+    /// Токен доступа для автоматизации. Значение токена есть только в ответе
+    pub async fn create_personal_token(
+        &mut self,
+        name: &str,
+        scopes: &[String],
+        expires_in_days: Option<i64>,
+    ) -> Result<CreatePersonalAccessTokenResponse, AppError> {
+        let token = self.access_token().await?;
+
+        match self.transport {
+            Transport::Http(_) => match &mut self.http_client {
+                Some(client) => {
+                    client
+                        .create_personal_token(&token, name, scopes, expires_in_days)
+                        .await
+                }
+                None => Err(AppError::Internal("Http client not set".to_string())),
+            },
+            Transport::Grpc(_) => match &mut self.grpc_client {
+                Some(client) => {
+                    client
+                        .create_personal_token(&token, name, scopes, expires_in_days)
+                        .await
+                }
+                None => Err(AppError::Internal("Grpc client not set".to_string())),
+            },
+        }
+    }
+
+    pub async fn list_personal_tokens(
+        &mut self,
+    ) -> Result<ListPersonalAccessTokensResponse, AppError> {
+        let token = self.access_token().await?;
+
+        match self.transport {
+            Transport::Http(_) => match &mut self.http_client {
+                Some(client) => client.list_personal_tokens(&token).await,
+                None => Err(AppError::Internal("Http client not set".to_string())),
+            },
+            Transport::Grpc(_) => match &mut self.grpc_client {
+                Some(client) => client.list_personal_tokens(&token).await,
+                None => Err(AppError::Internal("Grpc client not set".to_string())),
+            },
+        }
+    }
+
+    pub async fn revoke_personal_token(
+        &mut self,
+        token_id: &str,
+    ) -> Result<RevokePersonalAccessTokenResponse, AppError> {
+        let token = self.access_token().await?;
+
+        match self.transport {
+            Transport::Http(_) => match &mut self.http_client {
+                Some(client) => client.revoke_personal_token(&token, token_id).await,
+                None => Err(AppError::Internal("Http client not set".to_string())),
+            },
+            Transport::Grpc(_) => match &mut self.grpc_client {
+                Some(client) => client.revoke_personal_token(&token, token_id).await,
+                None => Err(AppError::Internal("Grpc client not set".to_string())),
+            },
+        }
+    }
+
+    /// Публичный профиль пользователя
+    pub async fn get_user_profile(
+        &mut self,
+        username: &str,
+    ) -> Result<UserProfileResponse, AppError> {
+        match self.transport {
+            Transport::Http(_) => match &mut self.http_client {
+                Some(client) => client.get_user_profile(username).await,
+                None => Err(AppError::Internal("Http client not set".to_string())),
+            },
+            Transport::Grpc(_) => match &mut self.grpc_client {
+                Some(client) => client.get_user_profile(username).await,
+                None => Err(AppError::Internal("Grpc client not set".to_string())),
+            },
+        }
+    }
+
+    /// Опубликованные посты автора, от новых к старым
+    pub async fn list_user_posts(
+        &mut self,
+        username: &str,
+        limit: i32,
+        offset: i32,
+    ) -> Result<ListPostsResponse, AppError> {
+        match self.transport {
+            Transport::Http(_) => match &mut self.http_client {
+                Some(client) => client.list_user_posts(username, limit, offset).await,
+                None => Err(AppError::Internal("Http client not set".to_string())),
+            },
+            Transport::Grpc(_) => match &mut self.grpc_client {
+                Some(client) => client.list_user_posts(username, limit, offset).await,
+                None => Err(AppError::Internal("Grpc client not set".to_string())),
+            },
+        }
+    }
+
+    /// Профиль заменяется целиком, не заполненные поля очищаются
+    pub async fn update_profile(
+        &mut self,
+        profile: UpdateProfileRequest,
+    ) -> Result<UserProfileResponse, AppError> {
+        let token = self.access_token().await?;
+
+        match self.transport {
+            Transport::Http(_) => match &mut self.http_client {
+                Some(client) => client.update_profile(&token, profile).await,
+                None => Err(AppError::Internal("Http client not set".to_string())),
+            },
+            Transport::Grpc(_) => match &mut self.grpc_client {
+                Some(client) => client.update_profile(&token, profile).await,
+                None => Err(AppError::Internal("Grpc client not set".to_string())),
+            },
+        }
+    }
+
+    /// Все данные пользователя одним JSON файлом
+    pub async fn export_account(&mut self) -> Result<ExportAccountResponse, AppError> {
+        let token = self.access_token().await?;
+
+        match self.transport {
+            Transport::Http(_) => match &mut self.http_client {
+                Some(client) => client.export_account(&token).await,
+                None => Err(AppError::Internal("Http client not set".to_string())),
+            },
+            Transport::Grpc(_) => match &mut self.grpc_client {
+                Some(client) => client.export_account(&token).await,
+                None => Err(AppError::Internal("Grpc client not set".to_string())),
+            },
+        }
+    }
+
+    /// Удаляет аккаунт. `posts`: "delete" или "anonymize".
+    /// Токены после удаления недействительны и забываются
+    pub async fn delete_account(
+        &mut self,
+        password: &str,
+        posts: &str,
+    ) -> Result<DeleteAccountResponse, AppError> {
+        let token = self.access_token().await?;
+
+        let response = match self.transport {
+            Transport::Http(_) => match &mut self.http_client {
+                Some(client) => client.delete_account(&token, password, posts).await,
+                None => Err(AppError::Internal("Http client not set".to_string())),
+            },
+            Transport::Grpc(_) => match &mut self.grpc_client {
+                Some(client) => client.delete_account(&token, password, posts).await,
+                None => Err(AppError::Internal("Grpc client not set".to_string())),
+            },
+        }?;
+
+        self.token = None;
+        self.refresh_token = None;
+        Ok(response)
+    }
+
+    /// Ставит реакцию `kind` на пост или снимает её при `remove`
+    pub async fn react(
+        &mut self,
+        post_id: i64,
+        kind: &str,
+        remove: bool,
+    ) -> Result<PostReactions, AppError> {
+        let token = self.access_token().await?;
+
+        match self.transport {
+            Transport::Http(_) => match &mut self.http_client {
+                Some(client) => client.react(&token, post_id, kind, remove).await,
+                None => Err(AppError::Internal("Http client not set".to_string())),
+            },
+            Transport::Grpc(_) => match &mut self.grpc_client {
+                Some(client) => client.react(&token, post_id, kind, remove).await,
+                None => Err(AppError::Internal("Grpc client not set".to_string())),
+            },
+        }
+    }
+
+    /// Подписывается на автора или отписывается при `remove`
+    pub async fn follow(&mut self, username: &str, remove: bool) -> Result<FollowStatus, AppError> {
+        let token = self.access_token().await?;
+
+        match self.transport {
+            Transport::Http(_) => match &mut self.http_client {
+                Some(client) => client.follow(&token, username, remove).await,
+                None => Err(AppError::Internal("Http client not set".to_string())),
+            },
+            Transport::Grpc(_) => match &mut self.grpc_client {
+                Some(client) => client.follow(&token, username, remove).await,
+                None => Err(AppError::Internal("Grpc client not set".to_string())),
+            },
+        }
+    }
+
+    /// Подписчики автора или, при `following`, авторы, на которых он подписан
+    pub async fn list_follows(
+        &mut self,
+        username: &str,
+        following: bool,
+        limit: i32,
+        offset: i32,
+    ) -> Result<ListFollowsResponse, AppError> {
+        match self.transport {
+            Transport::Http(_) => match &mut self.http_client {
+                Some(client) => {
+                    client
+                        .list_follows(username, following, limit, offset)
+                        .await
+                }
+                None => Err(AppError::Internal("Http client not set".to_string())),
+            },
+            Transport::Grpc(_) => match &mut self.grpc_client {
+                Some(client) => {
+                    client
+                        .list_follows(username, following, limit, offset)
+                        .await
+                }
+                None => Err(AppError::Internal("Grpc client not set".to_string())),
+            },
+        }
+    }
+
+    /// Лента подписок, листается как `list_posts`
+    pub async fn get_feed(
+        &mut self,
+        limit: i32,
+        offset: i32,
+        cursor: Option<&str>,
+    ) -> Result<ListPostsResponse, AppError> {
+        let token = self.access_token().await?;
+
+        match self.transport {
+            Transport::Http(_) => match &mut self.http_client {
+                Some(client) => client.get_feed(&token, limit, offset, cursor).await,
+                None => Err(AppError::Internal("Http client not set".to_string())),
+            },
+            Transport::Grpc(_) => match &mut self.grpc_client {
+                Some(client) => client.get_feed(&token, limit, offset, cursor).await,
+                None => Err(AppError::Internal("Grpc client not set".to_string())),
+            },
+        }
+    }
+
+    /// Добавляет пост в закладки или убирает его оттуда при `remove`
+    pub async fn bookmark(
+        &mut self,
+        post_id: i64,
+        remove: bool,
+    ) -> Result<BookmarkResponse, AppError> {
+        let token = self.access_token().await?;
+
+        match self.transport {
+            Transport::Http(_) => match &mut self.http_client {
+                Some(client) => client.bookmark(&token, post_id, remove).await,
+                None => Err(AppError::Internal("Http client not set".to_string())),
+            },
+            Transport::Grpc(_) => match &mut self.grpc_client {
+                Some(client) => client.bookmark(&token, post_id, remove).await,
+                None => Err(AppError::Internal("Grpc client not set".to_string())),
+            },
+        }
+    }
+
+    /// Сохранённые посты, от новых закладок к старым
+    pub async fn list_bookmarks(
+        &mut self,
+        limit: i32,
+        offset: i32,
+    ) -> Result<ListPostsResponse, AppError> {
+        let token = self.access_token().await?;
+
+        match self.transport {
+            Transport::Http(_) => match &mut self.http_client {
+                Some(client) => client.list_bookmarks(&token, limit, offset).await,
+                None => Err(AppError::Internal("Http client not set".to_string())),
+            },
+            Transport::Grpc(_) => match &mut self.grpc_client {
+                Some(client) => client.list_bookmarks(&token, limit, offset).await,
+                None => Err(AppError::Internal("Grpc client not set".to_string())),
+            },
+        }
+    }
+}
+
+/// Срок действия берётся из `exp` в JWT без проверки подписи:
+/// это лишь подсказка, когда пора обновиться, проверяет токен сервер
+fn token_expires_soon(token: &str) -> bool {
+    #[derive(serde::Deserialize)]
+    struct Claims {
+        exp: i64,
+    }
+
+    token
+        .split('.')
+        .nth(1)
+        .and_then(|payload| {
+            base64::engine::general_purpose::URL_SAFE_NO_PAD
+                .decode(payload)
+                .ok()
+        })
+        .and_then(|payload| serde_json::from_slice::<Claims>(&payload).ok())
+        .is_some_and(|claims| claims.exp - TOKEN_REFRESH_LEEWAY <= chrono::Utc::now().timestamp())
 }
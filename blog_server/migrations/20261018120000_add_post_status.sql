@@ -0,0 +1,10 @@
+-- Add migration script here
+CREATE TYPE post_status AS ENUM ('draft', 'published', 'archived');
+
+ALTER TABLE posts
+    ADD COLUMN IF NOT EXISTS status post_status NOT NULL DEFAULT 'published',
+    ADD COLUMN IF NOT EXISTS published_at TIMESTAMPTZ;
+
+UPDATE posts SET published_at = created_at WHERE status = 'published' AND published_at IS NULL;
+
+CREATE INDEX IF NOT EXISTS posts_status_created_at_idx ON posts(status, created_at);
@@ -0,0 +1,4 @@
+-- Add migration script here
+ALTER TABLE posts ADD COLUMN IF NOT EXISTS publish_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS posts_scheduled_idx ON posts(publish_at) WHERE status = 'draft' AND publish_at IS NOT NULL;
@@ -0,0 +1,20 @@
+-- Add migration script here
+ALTER TABLE posts ADD COLUMN IF NOT EXISTS slug VARCHAR(100);
+
+-- Существующие посты получают технический slug `post-<id>`: транслитерация и
+-- подбор свободного суффикса (slugify, pick_free_slug) есть только в коде сервера.
+-- Slug из заголовка такой пост получит при первой смене заголовка, а `post-<id>`
+-- после этого продолжит вести на него через post_slug_redirects
+UPDATE posts SET slug = 'post-' || id WHERE slug IS NULL;
+
+ALTER TABLE posts ALTER COLUMN slug SET NOT NULL;
+ALTER TABLE posts ADD CONSTRAINT posts_slug_key UNIQUE (slug);
+
+-- Прежние slug постов, переименованных после публикации
+CREATE TABLE IF NOT EXISTS post_slug_redirects (
+    slug VARCHAR(100) PRIMARY KEY,
+    post_id BIGINT NOT NULL REFERENCES posts(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS post_slug_redirects_post_id_idx ON post_slug_redirects(post_id);
@@ -0,0 +1,14 @@
+-- Add migration script here
+CREATE TYPE content_format AS ENUM ('plain', 'markdown');
+
+-- Существующие посты написаны обычным текстом, новые по умолчанию в Markdown
+ALTER TABLE posts
+    ADD COLUMN IF NOT EXISTS content_format content_format NOT NULL DEFAULT 'plain',
+    ADD COLUMN IF NOT EXISTS content_html TEXT NOT NULL DEFAULT '';
+
+ALTER TABLE posts ALTER COLUMN content_format SET DEFAULT 'markdown';
+
+UPDATE posts SET content_html = '<p>' || replace(
+    replace(replace(replace(COALESCE(content, ''), '&', '&amp;'), '<', '&lt;'), '>', '&gt;'),
+    E'\n', '<br>'
+) || '</p>';
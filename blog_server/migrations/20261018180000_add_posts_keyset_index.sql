@@ -0,0 +1,3 @@
+-- Add migration script here
+CREATE INDEX IF NOT EXISTS posts_published_keyset_idx
+    ON posts(created_at DESC, id DESC) WHERE status = 'published';
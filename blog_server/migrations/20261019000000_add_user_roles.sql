@@ -0,0 +1,6 @@
+-- Add migration script here
+CREATE TYPE user_role AS ENUM ('reader', 'author', 'moderator', 'admin');
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS role user_role NOT NULL DEFAULT 'author';
+
+CREATE INDEX IF NOT EXISTS users_admin_idx ON users(role) WHERE role = 'admin';
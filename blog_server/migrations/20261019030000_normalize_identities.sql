@@ -0,0 +1,34 @@
+-- Add migration script here
+-- Email хранится в нижнем регистре, имя пользователя уникально без учёта регистра.
+-- Дубликаты, отличающиеся только регистром, автоматически не сливаются: миграция
+-- останавливается и перечисляет их, такие аккаунты нужно объединить или переименовать вручную
+DO $$
+DECLARE
+    duplicates TEXT;
+BEGIN
+    SELECT string_agg(format('%s: %s', key, ids), '; ')
+    INTO duplicates
+    FROM (
+        SELECT 'email ' || lower(trim(email)) AS key,
+            string_agg(id::text, ', ' ORDER BY created_at) AS ids
+        FROM users
+        GROUP BY lower(trim(email))
+        HAVING count(*) > 1
+        UNION ALL
+        SELECT 'username ' || lower(username), string_agg(id::text, ', ' ORDER BY created_at)
+        FROM users
+        GROUP BY lower(username)
+        HAVING count(*) > 1
+    ) conflicts;
+
+    IF duplicates IS NOT NULL THEN
+        RAISE EXCEPTION 'users differ only by letter case, resolve them before this migration: %',
+            duplicates;
+    END IF;
+END $$;
+
+UPDATE users SET email = lower(trim(email)) WHERE email <> lower(trim(email));
+
+ALTER TABLE users ADD CONSTRAINT users_email_lowercase CHECK (email = lower(email));
+
+CREATE UNIQUE INDEX IF NOT EXISTS users_username_lower_key ON users (lower(username));
@@ -0,0 +1,7 @@
+-- Add migration script here
+-- Публичный профиль автора. Все поля необязательные и редактируются самим пользователем
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS display_name TEXT,
+    ADD COLUMN IF NOT EXISTS bio TEXT,
+    ADD COLUMN IF NOT EXISTS avatar_url TEXT,
+    ADD COLUMN IF NOT EXISTS links TEXT[] NOT NULL DEFAULT '{}';
@@ -0,0 +1,12 @@
+-- Add migration script here
+-- Системный пользователь, которому передаются обезличенные посты и комментарии
+-- удалённых аккаунтов. Имя не проходит проверку при регистрации, а по такому
+-- паролю войти нельзя
+INSERT INTO users (id, username, email, password_hash, role)
+VALUES ('00000000-0000-0000-0000-000000000000', '[deleted]', 'deleted@invalid', '!', 'reader')
+ON CONFLICT (id) DO NOTHING;
+
+-- У системного пользователя могут оказаться одинаковые файлы разных авторов
+ALTER TABLE attachments DROP CONSTRAINT attachments_owner_id_sha256_key;
+CREATE UNIQUE INDEX attachments_owner_id_sha256_key ON attachments (owner_id, sha256)
+    WHERE owner_id <> '00000000-0000-0000-0000-000000000000';
@@ -0,0 +1,14 @@
+-- Add migration script here
+CREATE TYPE reaction_kind AS ENUM ('like', 'love', 'laugh', 'wow');
+
+-- Один пользователь ставит каждую реакцию на пост не больше одного раза.
+-- Порядок ключа позволяет считать реакции поста по видам по индексу
+CREATE TABLE IF NOT EXISTS post_reactions (
+    post_id BIGINT NOT NULL REFERENCES posts(id) ON DELETE CASCADE,
+    kind reaction_kind NOT NULL,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (post_id, kind, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS post_reactions_user_id_idx ON post_reactions(user_id);
@@ -0,0 +1,11 @@
+-- Add migration script here
+-- Закладки пользователя. Первичный ключ обслуживает список закладок,
+-- индекс по посту - удаление поста
+CREATE TABLE IF NOT EXISTS bookmarks (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    post_id BIGINT NOT NULL REFERENCES posts(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, post_id)
+);
+
+CREATE INDEX IF NOT EXISTS bookmarks_post_id_idx ON bookmarks(post_id);
@@ -0,0 +1,7 @@
+-- Add migration script here
+-- Одинаковый файл, загруженный к разным постам, получает свою запись для каждого поста.
+-- Содержимое по-прежнему хранится один раз под ключом sha256
+DROP INDEX IF EXISTS attachments_owner_id_sha256_key;
+CREATE UNIQUE INDEX IF NOT EXISTS attachments_owner_post_sha256_key
+    ON attachments (owner_id, COALESCE(post_id, 0), sha256)
+    WHERE owner_id <> '00000000-0000-0000-0000-000000000000';
@@ -0,0 +1,33 @@
+-- Add migration script here
+-- Пост вместе с автором, счётчиками реакций и тегами: общая проекция запросов,
+-- которые возвращают Post. Фильтры и сортировка остаются в самих запросах
+CREATE OR REPLACE VIEW posts_view AS
+SELECT
+    posts.id,
+    posts.slug,
+    posts.title,
+    posts.content,
+    posts.content_format,
+    posts.content_html,
+    posts.author_id,
+    (
+        SELECT json_build_object('username', u.username, 'display_name', u.display_name)
+        FROM users u WHERE u.id = posts.author_id
+    ) AS author,
+    (
+        SELECT COALESCE(json_object_agg(r.kind, r.count), '{}')
+        FROM (
+            SELECT kind, count(*) AS count FROM post_reactions
+            WHERE post_id = posts.id GROUP BY kind
+        ) r
+    ) AS reactions,
+    posts.created_at,
+    posts.updated_at,
+    ARRAY(
+        SELECT t.name FROM post_tags pt JOIN tags t ON t.id = pt.tag_id
+        WHERE pt.post_id = posts.id ORDER BY t.name
+    ) AS tags,
+    posts.status,
+    posts.published_at,
+    posts.publish_at
+FROM posts;
@@ -0,0 +1,171 @@
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+use crate::{
+    data::{
+        attachment_repository::AttachmentRepository, comment_repository::CommentRepository,
+        posr_repository::PostRepository, storage::BlobStorage, user_repository::UserRepository,
+    },
+    domain::{
+        account::{AccountExport, AccountInfo, DeleteAccount},
+        error::BlogError,
+        post::PostStatus,
+    },
+    infrastructure::password_verify,
+};
+
+/// Сколько постов читать за один запрос при выгрузке
+const EXPORT_BATCH_SIZE: i64 = 100;
+
+/// Выгрузка и удаление аккаунта затрагивают данные всех остальных сервисов
+#[derive(Clone)]
+pub struct AccountService<
+    U: UserRepository,
+    P: PostRepository,
+    C: CommentRepository,
+    A: AttachmentRepository,
+    S: BlobStorage,
+> {
+    users: Arc<U>,
+    posts: Arc<P>,
+    comments: Arc<C>,
+    attachments: Arc<A>,
+    storage: Arc<S>,
+}
+
+impl<U, P, C, A, S> AccountService<U, P, C, A, S>
+where
+    U: UserRepository,
+    P: PostRepository,
+    C: CommentRepository,
+    A: AttachmentRepository,
+    S: BlobStorage,
+{
+    pub fn new(
+        users: Arc<U>,
+        posts: Arc<P>,
+        comments: Arc<C>,
+        attachments: Arc<A>,
+        storage: Arc<S>,
+    ) -> Self {
+        Self {
+            users,
+            posts,
+            comments,
+            attachments,
+            storage,
+        }
+    }
+
+    pub async fn export(&self, user_id: Uuid) -> Result<AccountExport, BlogError> {
+        let user = self.users.get_user_by_id(user_id).await?;
+        let profile = self.users.get_profile(&user.username).await?;
+        let personal_tokens = self.users.list_personal_tokens(user_id).await?;
+
+        let mut posts = Vec::new();
+        for status in [
+            PostStatus::Draft,
+            PostStatus::Published,
+            PostStatus::Archived,
+        ] {
+            let mut offset = 0;
+            loop {
+                let batch = self
+                    .posts
+                    .get_posts_by_status(user_id, status, EXPORT_BATCH_SIZE, offset)
+                    .await
+                    .map_err(|e| BlogError::Internal(e.to_string()))?;
+                let done = (batch.len() as i64) < EXPORT_BATCH_SIZE;
+                offset += batch.len() as i64;
+                posts.extend(batch);
+                if done {
+                    break;
+                }
+            }
+        }
+        posts.sort_by_key(|post| (post.created_at, post.id));
+
+        let comments = self
+            .comments
+            .get_comments_by_author(user_id)
+            .await
+            .map_err(|e| BlogError::Internal(e.to_string()))?;
+        let attachments = self
+            .attachments
+            .get_attachments_by_owner(user_id)
+            .await
+            .map_err(|e| BlogError::Internal(e.to_string()))?;
+
+        Ok(AccountExport {
+            exported_at: chrono::Utc::now(),
+            account: AccountInfo {
+                id: user.id,
+                username: user.username,
+                email: user.email,
+                role: user.role,
+                created_at: user.created_at,
+                email_verified_at: user.email_verified_at,
+                two_factor_enabled: user.totp_enabled_at.is_some(),
+            },
+            profile,
+            posts,
+            comments,
+            attachments,
+            personal_tokens,
+        })
+    }
+
+    /// Удаляет аккаунт после проверки пароля. Refresh токены и токены доступа
+    /// удаляются вместе с пользователем, выданные access токены перестают
+    /// проходить проверку, так как пользователя больше нет
+    pub async fn delete_account(
+        &self,
+        user_id: Uuid,
+        data: DeleteAccount,
+    ) -> Result<(), BlogError> {
+        let user = self.users.get_user_by_id(user_id).await?;
+        let valid = password_verify(&data.password, &user.password_hash)
+            .map_err(|err| BlogError::Internal(err.to_string()))?;
+        if !valid {
+            return Err(BlogError::Forbidden);
+        }
+
+        let hashes: Vec<String> = self
+            .attachments
+            .get_attachments_by_owner(user_id)
+            .await
+            .map_err(|e| BlogError::Internal(e.to_string()))?
+            .into_iter()
+            .map(|attachment| attachment.sha256)
+            .collect();
+
+        self.users.delete_user(user_id, data.posts).await?;
+
+        // Аккаунт уже удалён, поэтому ошибки очистки хранилища только логируются
+        if let Err(err) = self.remove_unreferenced_blobs(&hashes).await {
+            tracing::error!(user_id = %user_id, error = %err, "Failed to remove attachment files");
+        }
+        Ok(())
+    }
+
+    /// Содержимое хранится один раз для всех владельцев,
+    /// поэтому удаляется только то, на что больше нет ссылок
+    async fn remove_unreferenced_blobs(&self, hashes: &[String]) -> Result<(), BlogError> {
+        if hashes.is_empty() {
+            return Ok(());
+        }
+        let unreferenced = self
+            .attachments
+            .get_unreferenced_hashes(hashes)
+            .await
+            .map_err(|e| BlogError::Internal(e.to_string()))?;
+        for hash in unreferenced {
+            self.storage
+                .delete(&hash)
+                .await
+                .map_err(|e| BlogError::Internal(e.to_string()))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,155 @@
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+use crate::{
+    data::{
+        attachment_repository::{AttachmentRepository, AttachmentRepositoryError},
+        posr_repository::{PostRepository, PostRepositoryError},
+        storage::{BlobStorage, StorageError},
+    },
+    domain::{
+        attachment::{
+            Attachment, CreateAttachment, UploadAttachment, content_hash, sanitize_file_name,
+            sniff_content_type,
+        },
+        auth::AccessSession,
+        error::BlogError,
+        post::PostStatus,
+    },
+};
+
+#[derive(Clone)]
+pub struct AttachmentService<A: AttachmentRepository, S: BlobStorage, P: PostRepository> {
+    data: Arc<A>,
+    storage: Arc<S>,
+    posts: Arc<P>,
+    /// Максимальный размер файла в байтах
+    max_size: usize,
+}
+
+impl<A: AttachmentRepository, S: BlobStorage, P: PostRepository> AttachmentService<A, S, P> {
+    pub fn new(data: Arc<A>, storage: Arc<S>, posts: Arc<P>, max_size: usize) -> Self {
+        Self {
+            data,
+            storage,
+            posts,
+            max_size,
+        }
+    }
+
+    /// Проверка размера во время приёма потока, до того как файл прочитан целиком
+    pub fn check_size(&self, size: usize) -> Result<(), BlogError> {
+        if size > self.max_size {
+            return Err(BlogError::Validation(format!(
+                "file is larger than {} bytes",
+                self.max_size
+            )));
+        }
+        Ok(())
+    }
+
+    pub async fn upload(
+        &self,
+        owner_id: Uuid,
+        upload: UploadAttachment,
+    ) -> Result<Attachment, BlogError> {
+        if upload.data.is_empty() {
+            return Err(BlogError::Validation("file is empty".to_string()));
+        }
+        self.check_size(upload.data.len())?;
+
+        let file_name = sanitize_file_name(&upload.file_name)?;
+        let content_type = sniff_content_type(&upload.data)
+            .ok_or_else(|| BlogError::Validation("unsupported file type".to_string()))?;
+
+        if let Some(post_id) = upload.post_id {
+            let post = match self.posts.get_post(post_id).await {
+                Ok(p) => p,
+                Err(PostRepositoryError::NotFound) => {
+                    return Err(BlogError::NotFound("Posts not found".to_string()));
+                }
+                Err(e) => return Err(BlogError::Internal(e.to_string())),
+            };
+            if post.author_id != owner_id {
+                return Err(BlogError::Forbidden);
+            }
+        }
+
+        let sha256 = content_hash(&upload.data);
+        self.storage
+            .put(&sha256, &upload.data)
+            .await
+            .map_err(|e| BlogError::Internal(e.to_string()))?;
+
+        self.data
+            .create_attachment(
+                owner_id,
+                CreateAttachment {
+                    post_id: upload.post_id,
+                    file_name,
+                    content_type: content_type.to_string(),
+                    size: upload.data.len() as i64,
+                    sha256,
+                },
+            )
+            .await
+            .map_err(|e| BlogError::Internal(e.to_string()))
+    }
+
+    pub async fn get_attachment(&self, id: i64) -> Result<Attachment, BlogError> {
+        match self.data.get_attachment(id).await {
+            Ok(a) => Ok(a),
+            Err(AttachmentRepositoryError::NotFound) => {
+                Err(BlogError::NotFound("Attachment not found".to_string()))
+            }
+            Err(e) => Err(BlogError::Internal(e.to_string())),
+        }
+    }
+
+    /// Вложение для скачивания. Вложения неопубликованного поста видны только тем,
+    /// кому виден сам пост, для остальных их нет. Второе значение - можно ли
+    /// кэшировать содержимое публично, это верно только для опубликованных постов
+    pub async fn get_visible_attachment(
+        &self,
+        id: i64,
+        session: Option<&AccessSession>,
+    ) -> Result<(Attachment, bool), BlogError> {
+        let attachment = self.get_attachment(id).await?;
+        let Some(post_id) = attachment.post_id else {
+            return Ok((attachment, false));
+        };
+
+        let post = match self.posts.get_post(post_id).await {
+            Ok(p) => p,
+            Err(PostRepositoryError::NotFound) => {
+                return Err(BlogError::NotFound("Attachment not found".to_string()));
+            }
+            Err(e) => return Err(BlogError::Internal(e.to_string())),
+        };
+        if post.status == PostStatus::Published {
+            return Ok((attachment, true));
+        }
+        if !post.is_visible_to(session) {
+            return Err(BlogError::NotFound("Attachment not found".to_string()));
+        }
+        Ok((attachment, false))
+    }
+
+    pub async fn read_content(&self, attachment: &Attachment) -> Result<Vec<u8>, BlogError> {
+        match self.storage.get(&attachment.sha256).await {
+            Ok(data) => Ok(data),
+            Err(StorageError::NotFound) => {
+                Err(BlogError::NotFound("Attachment not found".to_string()))
+            }
+            Err(e) => Err(BlogError::Internal(e.to_string())),
+        }
+    }
+
+    pub async fn get_post_attachments(&self, post_id: i64) -> Result<Vec<Attachment>, BlogError> {
+        self.data
+            .get_attachments_by_post(post_id)
+            .await
+            .map_err(|e| BlogError::Internal(e.to_string()))
+    }
+}
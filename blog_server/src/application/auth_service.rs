@@ -1,62 +1,864 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
+
+use uuid::Uuid;
 
 use crate::{
-    data::user_repository::UserRepository,
+    data::{
+        mailer::{Mail, Mailer},
+        user_repository::UserRepository,
+    },
     domain::{
-        auth::{Auth, AuthAnswer, User},
-        error::BlogError,
+        account::DELETED_USER_ID,
+        auth::{
+            AccessSession, Auth, AuthAnswer, ChangePassword, LoginOutcome, LoginTwoFactor,
+            OneTimeToken, PasswordResetConfirm, RefreshToken, TwoFactorChallenge,
+            TwoFactorEnrollment, User, normalize_email, normalize_username, validate_email,
+            validate_password, validate_registration,
+        },
+        error::{BlogError, DomainError},
+        follow::{FollowPage, FollowStatus},
+        personal_token::{CreatePersonalToken, PERSONAL_TOKEN_PREFIX, PersonalAccessToken},
+        profile::{UpdateProfile, UserProfile},
+        role::Role,
+    },
+    infrastructure::{
+        DUMMY_PASSWORD_HASH, JwtService, RateLimitConfig, RateLimiter, RevocationCache,
+        generate_opaque_token, generate_recovery_codes, generate_totp_secret, hash_opaque_token,
+        normalize_recovery_code, password_hash, password_verify, totp_uri, verify_totp,
     },
-    infrastructure::{JwtService, password_hash, password_verify},
 };
 
+/// Сколько помнить, что токен не отозван
+const REVOCATION_CACHE_TTL: Duration = Duration::from_secs(30);
+/// Время жизни ссылки для сброса пароля, в минутах
+const PASSWORD_RESET_TTL_MINUTES: i64 = 60;
+/// Время жизни ссылки для подтверждения email, в часах
+const EMAIL_VERIFICATION_TTL_HOURS: i64 = 48;
+/// Время на ввод кода второго фактора, в минутах
+const TWO_FACTOR_CHALLENGE_TTL_MINUTES: i64 = 5;
+/// Сколько неверных кодов допускается для одного запроса входа
+const TWO_FACTOR_MAX_ATTEMPTS: i32 = 5;
+const RECOVERY_CODES_COUNT: usize = 10;
+
 #[derive(Clone)]
 pub struct AuthService<R: UserRepository> {
     repo: Arc<R>,
     keys: JwtService,
+    /// Время жизни refresh токена
+    refresh_ttl: chrono::Duration,
+    revocations: Arc<RevocationCache>,
+    mailer: Arc<dyn Mailer>,
+    /// Адрес сайта для ссылок в письмах
+    public_url: String,
+    /// Публиковать посты можно только с подтверждённым email
+    require_verified_email: bool,
+    /// Пользователь, который становится администратором, пока другого нет
+    bootstrap_admin: Option<String>,
+    rate_limiter: Arc<RateLimiter>,
 }
 
 impl<R: UserRepository> AuthService<R> {
-    pub fn new(repo: Arc<R>, keys: JwtService) -> Self {
-        Self { repo, keys }
+    pub fn new(
+        repo: Arc<R>,
+        keys: JwtService,
+        refresh_ttl: chrono::Duration,
+        mailer: Arc<dyn Mailer>,
+        public_url: String,
+        require_verified_email: bool,
+        bootstrap_admin: Option<String>,
+    ) -> Self {
+        Self {
+            repo,
+            keys,
+            refresh_ttl,
+            revocations: Arc::new(RevocationCache::new(REVOCATION_CACHE_TTL)),
+            mailer,
+            public_url,
+            require_verified_email,
+            bootstrap_admin,
+            rate_limiter: Arc::new(RateLimiter::in_memory(RateLimitConfig::default())),
+        }
+    }
+
+    /// Подменяет ограничения входа, например общим хранилищем для нескольких экземпляров
+    pub fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Arc::new(rate_limiter);
+        self
+    }
+
+    pub fn rate_limiter(&self) -> Arc<RateLimiter> {
+        Arc::clone(&self.rate_limiter)
+    }
+
+    /// Открытые ключи проверки access токенов
+    pub fn jwks(&self) -> &jsonwebtoken::jwk::JwkSet {
+        self.keys.jwks()
+    }
+
+    /// Проверяет access токен: подпись, срок, водяной знак пользователя и отзыв по `jti`.
+    /// Токены доступа с префиксом проверяются по базе
+    pub async fn authenticate(&self, token: &str) -> Result<AccessSession, BlogError> {
+        if token.starts_with(PERSONAL_TOKEN_PREFIX) {
+            return self.authenticate_personal(token).await;
+        }
+
+        let claims = self
+            .keys
+            .verify_token(token)
+            .map_err(|_| BlogError::Unautorized)?;
+
+        let user_id = Uuid::parse_str(&claims.sub).map_err(|_| BlogError::Unautorized)?;
+        let jti = Uuid::parse_str(&claims.jti).map_err(|_| BlogError::Unautorized)?;
+
+        let user = self
+            .repo
+            .get_user_by_id(user_id)
+            .await
+            .map_err(|_| BlogError::Unautorized)?;
+
+        if let Some(valid_after) = user.tokens_valid_after
+            && issued_before(claims.iat, valid_after)
+        {
+            return Err(BlogError::Unautorized);
+        }
+
+        if self.is_revoked(jti).await? {
+            return Err(BlogError::Unautorized);
+        }
+
+        Ok(AccessSession {
+            user_id,
+            jti,
+            expires_at: chrono::DateTime::from_timestamp(claims.exp as i64, 0)
+                .ok_or(BlogError::Unautorized)?,
+            email_verified: user.email_verified_at.is_some(),
+            role: claims.role,
+            scopes: None,
+        })
+    }
+
+    /// Водяной знак `tokens_valid_after` здесь не проверяется. Роль берётся из базы,
+    /// поэтому её смена сразу действует и на токены доступа, а при смене или сбросе
+    /// пароля они отзываются в `set_password`
+    async fn authenticate_personal(&self, token: &str) -> Result<AccessSession, BlogError> {
+        let token = self
+            .repo
+            .use_personal_token(&hash_opaque_token(token))
+            .await?
+            .ok_or(BlogError::Unautorized)?;
+
+        let user = self
+            .repo
+            .get_user_by_id(token.user_id)
+            .await
+            .map_err(|_| BlogError::Unautorized)?;
+
+        Ok(AccessSession {
+            user_id: user.id,
+            jti: token.id,
+            expires_at: token
+                .expires_at
+                .unwrap_or(chrono::DateTime::<chrono::Utc>::MAX_UTC),
+            email_verified: user.email_verified_at.is_some(),
+            role: user.role,
+            scopes: Some(token.scopes),
+        })
     }
 
-    pub fn keys(&self) -> &JwtService {
-        &self.keys
+    /// Создаёт токен доступа. Сам токен возвращается только здесь
+    pub async fn create_personal_token(
+        &self,
+        session: &AccessSession,
+        data: CreatePersonalToken,
+    ) -> Result<(PersonalAccessToken, String), BlogError> {
+        session.require_interactive()?;
+        data.validate()?;
+
+        let secret = format!("{}{}", PERSONAL_TOKEN_PREFIX, generate_opaque_token());
+        let token = PersonalAccessToken::new(session.user_id, data, hash_opaque_token(&secret));
+        self.repo.create_personal_token(&token).await?;
+        Ok((token, secret))
     }
 
-    pub async fn get_user(&self, id: uuid::Uuid) -> Result<User, BlogError> {
-        self.repo.get_user_by_id(id).await.map_err(BlogError::from)
+    pub async fn list_personal_tokens(
+        &self,
+        session: &AccessSession,
+    ) -> Result<Vec<PersonalAccessToken>, BlogError> {
+        session.require_interactive()?;
+        Ok(self.repo.list_personal_tokens(session.user_id).await?)
     }
 
-    pub async fn login_by_username(
+    pub async fn revoke_personal_token(
         &self,
-        username: &str,
+        session: &AccessSession,
+        id: Uuid,
+    ) -> Result<(), BlogError> {
+        session.require_interactive()?;
+        if !self.repo.revoke_personal_token(session.user_id, id).await? {
+            return Err(BlogError::NotFound("Token not found".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Публикация (сразу или по расписанию) требует подтверждённого email,
+    /// если это включено в настройках
+    pub fn ensure_can_publish(&self, session: &AccessSession) -> Result<(), BlogError> {
+        if self.require_verified_email && !session.email_verified {
+            return Err(BlogError::Forbidden);
+        }
+        Ok(())
+    }
+
+    async fn is_revoked(&self, jti: Uuid) -> Result<bool, BlogError> {
+        if let Some(revoked) = self.revocations.get(jti) {
+            return Ok(revoked);
+        }
+
+        let revoked = self.repo.is_access_token_revoked(jti).await?;
+        if revoked {
+            // Срок жизни токена не известен, но он не больше времени жизни access токена
+            self.revocations.insert_revoked(
+                jti,
+                Duration::from_secs(self.keys.expiration_seconds() as u64),
+            );
+        } else {
+            self.revocations.insert_active(jti);
+        }
+        Ok(revoked)
+    }
+
+    /// Отзывает access токен до истечения его срока
+    pub async fn revoke_access_token(&self, session: &AccessSession) -> Result<(), BlogError> {
+        self.repo
+            .revoke_access_token(session.jti, session.user_id, session.expires_at)
+            .await?;
+
+        let lifetime = (session.expires_at - chrono::Utc::now())
+            .to_std()
+            .unwrap_or_default();
+        self.revocations.insert_revoked(session.jti, lifetime);
+        Ok(())
+    }
+
+    /// Вход по имени пользователя или email и паролю. При включённой 2FA вместо токенов выдаётся
+    /// короткоживущий запрос, который подтверждается кодом в `complete_two_factor_login`
+    pub async fn login_by_password(
+        &self,
+        login: &str,
         password: &str,
-    ) -> Result<AuthAnswer, BlogError> {
-        let user = self.repo.get_user_by_username(username).await?;
+    ) -> Result<LoginOutcome, BlogError> {
+        // Неизвестный пользователь неотличим от неверного пароля, в том числе
+        // по времени ответа: пароль всё равно проверяется, но с фиктивным хешем
+        let Some(user) = self.find_user_by_login(login).await? else {
+            let _ = password_verify(password, DUMMY_PASSWORD_HASH);
+            return Err(BlogError::Unautorized);
+        };
+        let username = user.username.as_str();
+
+        self.rate_limiter
+            .check_login(username)
+            .map_err(BlogError::too_many_requests)?;
 
         let valid =
             password_verify(password, &user.password_hash).map_err(|_| BlogError::Unautorized)?;
 
         if !valid {
+            if let Some(locked_for) = self.rate_limiter.login_failed(username) {
+                tracing::warn!(
+                    user_id = %user.id,
+                    locked_secs = locked_for.as_secs(),
+                    "Account locked after failed logins"
+                );
+            }
+            return Err(BlogError::Unautorized);
+        }
+        self.rate_limiter.login_succeeded(username);
+
+        if user.totp_enabled_at.is_some() {
+            let challenge = generate_opaque_token();
+            let ttl = chrono::Duration::minutes(TWO_FACTOR_CHALLENGE_TTL_MINUTES);
+            self.repo
+                .create_two_factor_challenge(&OneTimeToken::new(
+                    user.id,
+                    hash_opaque_token(&challenge),
+                    ttl,
+                ))
+                .await?;
+            return Ok(LoginOutcome::TwoFactorRequired(TwoFactorChallenge {
+                challenge,
+                expires_in: ttl.num_seconds(),
+            }));
+        }
+
+        Ok(LoginOutcome::Authenticated(
+            self.issue_tokens(&user, Uuid::new_v4()).await?,
+        ))
+    }
+
+    /// Вход по имени пользователя или по email
+    async fn find_user_by_login(&self, login: &str) -> Result<Option<User>, BlogError> {
+        let found = if login.contains('@') {
+            self.repo.get_user_by_email(&normalize_email(login)).await
+        } else {
+            self.repo
+                .get_user_by_username(&normalize_username(login))
+                .await
+        };
+
+        match found {
+            Ok(user) => Ok(Some(user)),
+            Err(DomainError::UserNotFound) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Второй шаг входа: код из приложения или код восстановления
+    pub async fn complete_two_factor_login(
+        &self,
+        data: LoginTwoFactor,
+    ) -> Result<AuthAnswer, BlogError> {
+        let challenge = self
+            .repo
+            .get_two_factor_challenge(&hash_opaque_token(&data.challenge), TWO_FACTOR_MAX_ATTEMPTS)
+            .await?
+            .ok_or(BlogError::Unautorized)?;
+        let user = self.repo.get_user_by_id(challenge.user_id).await?;
+
+        if !self.check_second_factor(&user, &data.code).await? {
+            self.repo.fail_two_factor_challenge(challenge.id).await?;
+            tracing::warn!(user_id = %user.id, "Invalid two-factor code");
+            return Err(BlogError::Unautorized);
+        }
+        if !self.repo.take_two_factor_challenge(challenge.id).await? {
+            return Err(BlogError::Unautorized);
+        }
+
+        self.issue_tokens(&user, Uuid::new_v4()).await
+    }
+
+    /// Начинает подключение 2FA. Пока код не подтверждён, вход работает без него.
+    /// Повторный вызов выдаёт новый секрет и новые коды восстановления
+    pub async fn enroll_two_factor(
+        &self,
+        session: &AccessSession,
+    ) -> Result<TwoFactorEnrollment, BlogError> {
+        session.require_interactive()?;
+        let user = self.repo.get_user_by_id(session.user_id).await?;
+        if user.totp_enabled_at.is_some() {
+            return Err(BlogError::Validation(
+                "two-factor authentication already enabled".to_string(),
+            ));
+        }
+
+        let secret = generate_totp_secret();
+        let otpauth_uri = totp_uri(&secret, &user.username)
+            .map_err(|err| BlogError::Internal(err.to_string()))?;
+        let recovery_codes = generate_recovery_codes(RECOVERY_CODES_COUNT);
+        let hashes: Vec<String> = recovery_codes
+            .iter()
+            .map(|code| hash_opaque_token(&normalize_recovery_code(code)))
+            .collect();
+
+        self.repo.set_totp_secret(user.id, Some(&secret)).await?;
+        self.repo.replace_recovery_codes(user.id, &hashes).await?;
+
+        Ok(TwoFactorEnrollment {
+            secret,
+            otpauth_uri,
+            recovery_codes,
+        })
+    }
+
+    /// Включает 2FA после проверки первого кода из приложения
+    pub async fn confirm_two_factor(
+        &self,
+        session: &AccessSession,
+        code: &str,
+    ) -> Result<(), BlogError> {
+        session.require_interactive()?;
+        let user = self.repo.get_user_by_id(session.user_id).await?;
+        if user.totp_enabled_at.is_some() {
+            return Err(BlogError::Validation(
+                "two-factor authentication already enabled".to_string(),
+            ));
+        }
+        if user.totp_secret.is_none() {
+            return Err(BlogError::Validation(
+                "two-factor enrollment not started".to_string(),
+            ));
+        }
+
+        if !self.check_totp(&user, code).await? {
+            return Err(BlogError::Validation("invalid code".to_string()));
+        }
+        self.repo.enable_totp(user.id).await?;
+        Ok(())
+    }
+
+    /// Отключение 2FA тоже требует кода
+    pub async fn disable_two_factor(
+        &self,
+        session: &AccessSession,
+        code: &str,
+    ) -> Result<(), BlogError> {
+        session.require_interactive()?;
+        let user = self.repo.get_user_by_id(session.user_id).await?;
+        if user.totp_enabled_at.is_none() {
+            return Err(BlogError::Validation(
+                "two-factor authentication not enabled".to_string(),
+            ));
+        }
+
+        if !self.check_second_factor(&user, code).await? {
+            return Err(BlogError::Forbidden);
+        }
+        self.repo.set_totp_secret(user.id, None).await?;
+        self.repo.replace_recovery_codes(user.id, &[]).await?;
+        Ok(())
+    }
+
+    /// Шестизначный код проверяется как TOTP, остальное как код восстановления
+    async fn check_second_factor(&self, user: &User, code: &str) -> Result<bool, BlogError> {
+        let code = code.trim();
+        if code.len() == 6 && code.chars().all(|c| c.is_ascii_digit()) {
+            return self.check_totp(user, code).await;
+        }
+
+        let hash = hash_opaque_token(&normalize_recovery_code(code));
+        Ok(self.repo.use_recovery_code(user.id, &hash).await?)
+    }
+
+    async fn check_totp(&self, user: &User, code: &str) -> Result<bool, BlogError> {
+        let Some(secret) = &user.totp_secret else {
+            return Ok(false);
+        };
+        let now = chrono::Utc::now().timestamp() as u64;
+        match verify_totp(secret, code.trim(), now) {
+            Some(step) => Ok(self.repo.record_totp_step(user.id, step as i64).await?),
+            None => Ok(false),
+        }
+    }
+
+    /// Обменивает refresh токен на новую пару токенов.
+    /// Повторное предъявление уже использованного токена означает утечку,
+    /// поэтому всё семейство отзывается
+    pub async fn refresh(&self, refresh_token: &str) -> Result<AuthAnswer, BlogError> {
+        let token = self
+            .repo
+            .get_refresh_token(&hash_opaque_token(refresh_token))
+            .await?
+            .ok_or(BlogError::Unautorized)?;
+
+        if token.revoked_at.is_some() || token.expires_at <= chrono::Utc::now() {
+            return Err(BlogError::Unautorized);
+        }
+
+        if token.used_at.is_some() || !self.repo.mark_refresh_token_used(token.id).await? {
+            self.repo.revoke_token_family(token.family_id).await?;
+            tracing::warn!(
+                user_id = %token.user_id,
+                family_id = %token.family_id,
+                "Refresh token reuse detected, token family revoked",
+            );
             return Err(BlogError::Unautorized);
         }
 
+        let user = self.repo.get_user_by_id(token.user_id).await?;
+        self.issue_tokens(&user, token.family_id).await
+    }
+
+    /// Завершает сессию: отзывает текущий access токен
+    /// и семейство, к которому относится refresh токен пользователя
+    pub async fn logout(
+        &self,
+        session: &AccessSession,
+        refresh_token: &str,
+    ) -> Result<(), BlogError> {
+        let token = self
+            .repo
+            .get_refresh_token(&hash_opaque_token(refresh_token))
+            .await?
+            .filter(|t| t.user_id == session.user_id)
+            .ok_or(BlogError::Unautorized)?;
+
+        self.repo.revoke_token_family(token.family_id).await?;
+        self.revoke_access_token(session).await
+    }
+
+    /// Выход со всех устройств: все выданные ранее токены пользователя
+    /// становятся недействительными
+    pub async fn logout_everywhere(&self, user_id: Uuid) -> Result<(), BlogError> {
+        self.invalidate_issued_tokens(user_id).await?;
+        self.repo.revoke_user_refresh_tokens(user_id).await?;
+        Ok(())
+    }
+
+    /// Сдвигает водяной знак пользователя. Токены, выпущенные в ту же секунду, считаются
+    /// выпущенными до него, поэтому возврат ждёт следующей секунды: токены, выданные
+    /// после вызова, уже действуют
+    async fn invalidate_issued_tokens(&self, user_id: Uuid) -> Result<(), BlogError> {
+        let now = chrono::Utc::now();
+        self.repo.set_tokens_valid_after(user_id, now).await?;
+
+        let elapsed = Duration::from_nanos(now.timestamp_subsec_nanos().into());
+        tokio::time::sleep(Duration::from_secs(1).saturating_sub(elapsed)).await;
+        Ok(())
+    }
+
+    /// Смена пароля с проверкой текущего. Все сессии завершаются,
+    /// текущему клиенту выдаётся новая пара токенов
+    pub async fn change_password(
+        &self,
+        user_id: Uuid,
+        data: ChangePassword,
+    ) -> Result<AuthAnswer, BlogError> {
+        let user = self.repo.get_user_by_id(user_id).await?;
+
+        let valid = password_verify(&data.current_password, &user.password_hash)
+            .map_err(|err| BlogError::Internal(err.to_string()))?;
+        if !valid {
+            return Err(BlogError::Forbidden);
+        }
+
+        validate_password(&data.new_password, &[&user.username, &user.email])?;
+        self.set_password(user.id, &data.new_password).await?;
+
+        self.issue_tokens(&user, Uuid::new_v4()).await
+    }
+
+    /// Отправляет письмо со ссылкой для сброса пароля.
+    /// Неизвестный email не считается ошибкой, чтобы не раскрывать наличие аккаунта
+    pub async fn request_password_reset(&self, email: &str) -> Result<(), BlogError> {
+        let email = normalize_email(email);
+        validate_email(&email)?;
+
+        let user = match self.repo.get_user_by_email(&email).await {
+            Ok(user) => user,
+            Err(DomainError::UserNotFound) => return Ok(()),
+            Err(err) => return Err(err.into()),
+        };
+
+        let token = generate_opaque_token();
+        self.repo
+            .create_password_reset(&OneTimeToken::new(
+                user.id,
+                hash_opaque_token(&token),
+                chrono::Duration::minutes(PASSWORD_RESET_TTL_MINUTES),
+            ))
+            .await?;
+
+        let mail = Mail {
+            to: user.email,
+            subject: "Сброс пароля".to_string(),
+            body: format!(
+                "Чтобы задать новый пароль, перейдите по ссылке:\n{}/reset-password/{}\n\n\
+                 Ссылка действует {} минут. После смены пароля все сессии и токены доступа \
+                 будут отозваны. Если вы не запрашивали сброс, проигнорируйте это письмо.",
+                self.public_url.trim_end_matches('/'),
+                token,
+                PASSWORD_RESET_TTL_MINUTES,
+            ),
+        };
+        if let Err(err) = self.mailer.send(&mail).await {
+            tracing::error!(user_id = %user.id, error = %err, "Failed to send password reset mail");
+        }
+
+        Ok(())
+    }
+
+    /// Задаёт новый пароль по токену из письма. Токен одноразовый
+    pub async fn confirm_password_reset(
+        &self,
+        data: PasswordResetConfirm,
+    ) -> Result<(), BlogError> {
+        let token_hash = hash_opaque_token(&data.token);
+        let invalid_token = || BlogError::Validation("invalid or expired reset token".to_string());
+
+        // Токен гасится только после проверки пароля, чтобы при ошибке его можно было повторить
+        let token = self
+            .repo
+            .get_password_reset(&token_hash)
+            .await?
+            .ok_or_else(invalid_token)?;
+        let user = self.repo.get_user_by_id(token.user_id).await?;
+        validate_password(&data.new_password, &[&user.username, &user.email])?;
+
+        let token = self
+            .repo
+            .take_password_reset(&token_hash)
+            .await?
+            .ok_or_else(invalid_token)?;
+
+        self.set_password(token.user_id, &data.new_password).await
+    }
+
+    /// Новый пароль делает недействительными все выданные ранее токены,
+    /// включая токены доступа: к ним водяной знак не применяется, поэтому они отзываются
+    async fn set_password(&self, user_id: Uuid, password: &str) -> Result<(), BlogError> {
+        let hash = password_hash(password).map_err(|err| BlogError::Internal(err.to_string()))?;
+        self.repo.update_password(user_id, &hash).await?;
+        self.repo.revoke_user_personal_tokens(user_id).await?;
+        self.logout_everywhere(user_id).await
+    }
+
+    async fn issue_tokens(&self, user: &User, family_id: Uuid) -> Result<AuthAnswer, BlogError> {
         let token = self
             .keys
-            .generate_token(user.id)
+            .generate_token(user.id, user.role)
             .map_err(|err| BlogError::Internal(err.to_string()))?;
 
+        let refresh_token = generate_opaque_token();
+        self.repo
+            .create_refresh_token(&RefreshToken::new(
+                user.id,
+                family_id,
+                hash_opaque_token(&refresh_token),
+                self.refresh_ttl,
+            ))
+            .await?;
+
         Ok(AuthAnswer {
             token,
+            refresh_token,
+            expires_in: self.keys.expiration_seconds(),
             uuid: user.id,
         })
     }
 
+    /// После регистрации на email уходит ссылка для подтверждения
     pub async fn register(&self, user: Auth) -> Result<User, BlogError> {
+        let username = normalize_username(&user.username);
+        let email = normalize_email(&user.email);
+        validate_registration(&username, &email, &user.password)?;
+
         let hash =
             password_hash(&user.password).map_err(|err| BlogError::Internal(err.to_string()))?;
-        let user = User::new(user.username, user.email, hash);
-        self.repo.create_user(user).await.map_err(BlogError::from)
+        let user = User::new(username, email, hash);
+        let user = self.repo.create_user(user).await?;
+
+        self.send_verification(&user).await?;
+        Ok(user)
+    }
+
+    /// Назначает администратора из настроек при старте, если администратора ещё нет.
+    /// Роль выдаётся только уже зарегистрированному пользователю, регистрация
+    /// под этим именем сама по себе прав не даёт
+    pub async fn bootstrap_admin(&self) -> Result<(), BlogError> {
+        let Some(username) = self.bootstrap_admin.as_deref() else {
+            return Ok(());
+        };
+        if self.repo.has_admin().await? {
+            return Ok(());
+        }
+
+        match self.repo.get_user_by_username(username).await {
+            Ok(user) => {
+                self.promote_bootstrap_admin(user.id).await?;
+                Ok(())
+            }
+            Err(DomainError::UserNotFound) => {
+                tracing::warn!(
+                    username = %username,
+                    "Bootstrap admin is not registered, restart the server after registration"
+                );
+                Ok(())
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    async fn promote_bootstrap_admin(&self, user_id: Uuid) -> Result<User, BlogError> {
+        let user = self.repo.set_user_role(user_id, Role::Admin).await?;
+        tracing::warn!(user_id = %user.id, username = %user.username, "Bootstrap admin assigned");
+        Ok(user)
+    }
+
+    pub async fn list_users(
+        &self,
+        admin: &AccessSession,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<User>, BlogError> {
+        admin.require_interactive()?;
+        admin.require_role(Role::Admin)?;
+        Ok(self.repo.list_users(limit, offset).await?)
+    }
+
+    /// Меняет роль пользователя. Токены со старой ролью перестают действовать,
+    /// refresh токен выдаёт новые уже с новой ролью.
+    /// Свою роль администратор менять не может, чтобы не остаться без администратора
+    pub async fn set_user_role(
+        &self,
+        admin: &AccessSession,
+        user_id: Uuid,
+        role: Role,
+    ) -> Result<User, BlogError> {
+        admin.require_interactive()?;
+        admin.require_role(Role::Admin)?;
+        if admin.user_id == user_id {
+            return Err(BlogError::Validation("cannot change own role".to_string()));
+        }
+
+        let user = self.repo.set_user_role(user_id, role).await?;
+        self.invalidate_issued_tokens(user.id).await?;
+        Ok(user)
+    }
+
+    pub async fn get_profile(&self, username: &str) -> Result<UserProfile, BlogError> {
+        Ok(self.repo.get_profile(username.trim()).await?)
+    }
+
+    pub async fn update_profile(
+        &self,
+        user_id: Uuid,
+        profile: UpdateProfile,
+    ) -> Result<UserProfile, BlogError> {
+        let profile = profile.normalize();
+        profile.validate()?;
+        Ok(self.repo.update_profile(user_id, &profile).await?)
+    }
+
+    /// Подписка на автора. На себя и на `[deleted]` подписаться нельзя
+    pub async fn follow(&self, user_id: Uuid, username: &str) -> Result<FollowStatus, BlogError> {
+        let profile = self.get_profile(username).await?;
+        if profile.id == DELETED_USER_ID {
+            return Err(DomainError::UserNotFound.into());
+        }
+        if profile.id == user_id {
+            return Err(BlogError::Validation("cannot follow yourself".to_string()));
+        }
+
+        self.repo.follow(user_id, profile.id).await?;
+        self.follow_status(user_id, profile).await
+    }
+
+    pub async fn unfollow(&self, user_id: Uuid, username: &str) -> Result<FollowStatus, BlogError> {
+        let profile = self.get_profile(username).await?;
+        self.repo.unfollow(user_id, profile.id).await?;
+        self.follow_status(user_id, profile).await
+    }
+
+    pub async fn get_follow_status(
+        &self,
+        user_id: Uuid,
+        username: &str,
+    ) -> Result<FollowStatus, BlogError> {
+        let profile = self.get_profile(username).await?;
+        self.follow_status(user_id, profile).await
+    }
+
+    pub async fn get_followers(
+        &self,
+        username: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<FollowPage, BlogError> {
+        let profile = self.get_profile(username).await?;
+        let users = self
+            .repo
+            .list_followers(profile.id, limit.max(0), offset.max(0))
+            .await?;
+        let total = self.repo.count_followers(profile.id).await?;
+        Ok(FollowPage { users, total })
+    }
+
+    pub async fn get_following(
+        &self,
+        username: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<FollowPage, BlogError> {
+        let profile = self.get_profile(username).await?;
+        let users = self
+            .repo
+            .list_following(profile.id, limit.max(0), offset.max(0))
+            .await?;
+        let total = self.repo.count_following(profile.id).await?;
+        Ok(FollowPage { users, total })
+    }
+
+    async fn follow_status(
+        &self,
+        user_id: Uuid,
+        profile: UserProfile,
+    ) -> Result<FollowStatus, BlogError> {
+        Ok(FollowStatus {
+            following: self.repo.is_following(user_id, profile.id).await?,
+            followers: self.repo.count_followers(profile.id).await?,
+            username: profile.username,
+        })
+    }
+
+    /// Подтверждает email по токену из письма. Токен одноразовый
+    pub async fn verify_email(&self, token: &str) -> Result<(), BlogError> {
+        let token = self
+            .repo
+            .take_email_verification(&hash_opaque_token(token))
+            .await?
+            .ok_or_else(|| {
+                BlogError::Validation("invalid or expired verification token".to_string())
+            })?;
+
+        self.repo.mark_email_verified(token.user_id).await?;
+        Ok(())
+    }
+
+    /// Повторно отправляет ссылку для подтверждения
+    pub async fn resend_verification(&self, user_id: Uuid) -> Result<(), BlogError> {
+        let user = self.repo.get_user_by_id(user_id).await?;
+        if user.email_verified_at.is_some() {
+            return Err(BlogError::Validation("email already verified".to_string()));
+        }
+
+        self.send_verification(&user).await
+    }
+
+    /// Ошибка отправки письма не мешает регистрации, ссылку можно запросить повторно
+    async fn send_verification(&self, user: &User) -> Result<(), BlogError> {
+        let token = generate_opaque_token();
+        self.repo
+            .create_email_verification(&OneTimeToken::new(
+                user.id,
+                hash_opaque_token(&token),
+                chrono::Duration::hours(EMAIL_VERIFICATION_TTL_HOURS),
+            ))
+            .await?;
+
+        let mail = Mail {
+            to: user.email.clone(),
+            subject: "Подтверждение email".to_string(),
+            body: format!(
+                "Чтобы подтвердить адрес, перейдите по ссылке:\n{}/verify-email/{}\n\n\
+                 Ссылка действует {} часов.",
+                self.public_url.trim_end_matches('/'),
+                token,
+                EMAIL_VERIFICATION_TTL_HOURS,
+            ),
+        };
+        if let Err(err) = self.mailer.send(&mail).await {
+            tracing::error!(user_id = %user.id, error = %err, "Failed to send verification mail");
+        }
+
+        Ok(())
+    }
+}
+
+/// `iat` хранится с точностью до секунды, и токен из той же секунды, что и водяной знак,
+/// мог быть выпущен раньше него. Такие токены не принимаются
+fn issued_before(iat: usize, valid_after: chrono::DateTime<chrono::Utc>) -> bool {
+    (iat as i64) <= valid_after.timestamp()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_from_watermark_second_is_rejected() {
+        let valid_after = chrono::DateTime::from_timestamp(1_000, 700_000_000).unwrap();
+
+        assert!(issued_before(999, valid_after));
+        assert!(issued_before(1_000, valid_after));
+        assert!(!issued_before(1_001, valid_after));
     }
 }
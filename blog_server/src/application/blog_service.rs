@@ -5,11 +5,22 @@ use uuid::Uuid;
 use crate::{
     data::posr_repository::{PostRepository, PostRepositoryError},
     domain::{
+        bookmark::Bookmark,
+        content::render_html,
         error::BlogError,
-        post::{CreatePost, Post, UpdatePost},
+        pagination::{PageRequest, PostCursor, PostPage},
+        post::{
+            CreatePost, Post, PostStatus, TagCount, UpdatePost, normalize_tags, validate_publish_at,
+        },
+        reaction::{PostOrder, PostReactions, ReactionKind},
+        revision::{PostRevision, RevisionDiff, diff_lines},
+        search::{SearchResults, normalize_query},
     },
 };
 
+/// Сколько запланированных постов публикуется за один проход
+const PUBLISH_BATCH_SIZE: i64 = 100;
+
 #[derive(Clone)]
 pub struct BlogService<R: PostRepository> {
     data: Arc<R>,
@@ -20,28 +31,286 @@ impl<R: PostRepository> BlogService<R> {
         Self { data }
     }
 
-    pub async fn get_next_posts(&self, offset: i64, count: i64) -> Result<Vec<Post>, BlogError> {
-        match self.data.get_last_posts(count, Some(offset)).await {
+    /// Запрашивает на один пост больше, чтобы понять, есть ли следующая страница.
+    /// Курсор выдаётся только для порядка от новых к старым
+    pub async fn get_next_posts(
+        &self,
+        page: PageRequest,
+        count: i64,
+        tags: Vec<String>,
+        order: PostOrder,
+    ) -> Result<PostPage, BlogError> {
+        if order != PostOrder::Latest && matches!(page, PageRequest::After(_)) {
+            return Err(BlogError::Validation(format!(
+                "cursor is not supported for order {}",
+                order.as_str()
+            )));
+        }
+        let tags = normalize_tags(tags)?;
+        let count = count.max(0);
+        let mut posts = self
+            .data
+            .get_last_posts(count + 1, page, &tags, order)
+            .await
+            .map_err(|e| BlogError::Internal(e.to_string()))?;
+        let total = self
+            .data
+            .count_published_posts(&tags)
+            .await
+            .map_err(|e| BlogError::Internal(e.to_string()))?;
+
+        let next_cursor = if posts.len() as i64 > count {
+            posts.truncate(count as usize);
+            posts
+                .last()
+                .filter(|_| order == PostOrder::Latest)
+                .map(|p| PostCursor::from_post(p).encode())
+        } else {
+            None
+        };
+
+        Ok(PostPage {
+            posts,
+            total,
+            next_cursor,
+        })
+    }
+
+    /// Лента подписок листается так же, как общий список постов
+    pub async fn get_feed(
+        &self,
+        user_id: Uuid,
+        page: PageRequest,
+        count: i64,
+    ) -> Result<PostPage, BlogError> {
+        let count = count.max(0);
+        let mut posts = self
+            .data
+            .get_feed_posts(user_id, count + 1, page)
+            .await
+            .map_err(|e| BlogError::Internal(e.to_string()))?;
+        let total = self
+            .data
+            .count_feed_posts(user_id)
+            .await
+            .map_err(|e| BlogError::Internal(e.to_string()))?;
+
+        let next_cursor = if posts.len() as i64 > count {
+            posts.truncate(count as usize);
+            posts.last().map(|p| PostCursor::from_post(p).encode())
+        } else {
+            None
+        };
+
+        Ok(PostPage {
+            posts,
+            total,
+            next_cursor,
+        })
+    }
+
+    /// Опубликованные посты автора для его страницы
+    pub async fn get_posts_by_user(
+        &self,
+        user_id: Uuid,
+        offset: i64,
+        count: i64,
+    ) -> Result<PostPage, BlogError> {
+        let posts = self
+            .data
+            .get_posts_by_author(user_id, count.max(0), offset.max(0))
+            .await
+            .map_err(|e| BlogError::Internal(e.to_string()))?;
+        let total = self
+            .data
+            .count_posts_by_status(user_id, PostStatus::Published)
+            .await
+            .map_err(|e| BlogError::Internal(e.to_string()))?;
+
+        Ok(PostPage {
+            posts,
+            total,
+            next_cursor: None,
+        })
+    }
+
+    pub async fn get_post_by_id(&self, id: i64) -> Result<Post, BlogError> {
+        match self.data.get_post(id).await {
             Ok(p) => Ok(p),
+            Err(PostRepositoryError::NotFound) => {
+                Err(BlogError::NotFound("Posts not found".to_string()))
+            }
             Err(e) => Err(BlogError::Internal(e.to_string())),
         }
     }
-    #[allow(dead_code)]
-    pub async fn get_posts_by_user(&self, user_id: Uuid) -> Result<Vec<Post>, BlogError> {
-        match self.data.get_posts_by_author(user_id).await {
+
+    /// Пост, доступный без авторизации: только опубликованный
+    pub async fn get_published_post(&self, id: i64) -> Result<Post, BlogError> {
+        let post = self.get_post_by_id(id).await?;
+        if post.status != PostStatus::Published {
+            return Err(BlogError::NotFound("Posts not found".to_string()));
+        }
+        Ok(post)
+    }
+
+    /// Реакции ставятся только на опубликованные посты
+    pub async fn react(
+        &self,
+        post_id: i64,
+        user_id: Uuid,
+        kind: ReactionKind,
+    ) -> Result<PostReactions, BlogError> {
+        self.get_published_post(post_id).await?;
+        self.data
+            .add_reaction(post_id, user_id, kind)
+            .await
+            .map_err(|e| BlogError::Internal(e.to_string()))?;
+        self.get_reactions(post_id, user_id).await
+    }
+
+    pub async fn unreact(
+        &self,
+        post_id: i64,
+        user_id: Uuid,
+        kind: ReactionKind,
+    ) -> Result<PostReactions, BlogError> {
+        self.get_published_post(post_id).await?;
+        self.data
+            .remove_reaction(post_id, user_id, kind)
+            .await
+            .map_err(|e| BlogError::Internal(e.to_string()))?;
+        self.get_reactions(post_id, user_id).await
+    }
+
+    async fn get_reactions(&self, post_id: i64, user_id: Uuid) -> Result<PostReactions, BlogError> {
+        self.data
+            .get_reactions(post_id, user_id)
+            .await
+            .map_err(|e| BlogError::Internal(e.to_string()))
+    }
+
+    /// Пост по текущему или прежнему slug, в любом статусе
+    pub async fn get_post_by_slug(&self, slug: &str) -> Result<Post, BlogError> {
+        match self.data.get_post_by_slug(slug).await {
             Ok(p) => Ok(p),
             Err(PostRepositoryError::NotFound) => {
                 Err(BlogError::NotFound("Posts not found".to_string()))
             }
-            Err(PostRepositoryError::AutorNotFound) => {
-                Err(BlogError::NotFound(format!("Not found Uuid: {}", user_id)))
-            }
             Err(e) => Err(BlogError::Internal(e.to_string())),
         }
     }
 
-    pub async fn get_post_by_id(&self, id: i64) -> Result<Post, BlogError> {
-        match self.data.get_post(id).await {
+    pub async fn get_published_post_by_slug(&self, slug: &str) -> Result<Post, BlogError> {
+        let post = self.get_post_by_slug(slug).await?;
+        if post.status != PostStatus::Published {
+            return Err(BlogError::NotFound("Posts not found".to_string()));
+        }
+        Ok(post)
+    }
+
+    /// В закладки добавляются только опубликованные посты
+    pub async fn add_bookmark(&self, user_id: Uuid, post_id: i64) -> Result<Bookmark, BlogError> {
+        self.get_published_post(post_id).await?;
+        self.data
+            .add_bookmark(user_id, post_id)
+            .await
+            .map_err(|e| BlogError::Internal(e.to_string()))?;
+        Ok(Bookmark {
+            post_id,
+            bookmarked: true,
+        })
+    }
+
+    pub async fn remove_bookmark(
+        &self,
+        user_id: Uuid,
+        post_id: i64,
+    ) -> Result<Bookmark, BlogError> {
+        self.data
+            .remove_bookmark(user_id, post_id)
+            .await
+            .map_err(|e| BlogError::Internal(e.to_string()))?;
+        Ok(Bookmark {
+            post_id,
+            bookmarked: false,
+        })
+    }
+
+    /// Закладки на снятые с публикации посты сохраняются, но в список не попадают
+    pub async fn get_bookmarks(
+        &self,
+        user_id: Uuid,
+        offset: i64,
+        count: i64,
+    ) -> Result<PostPage, BlogError> {
+        let posts = self
+            .data
+            .get_bookmarked_posts(user_id, count.max(0), offset.max(0))
+            .await
+            .map_err(|e| BlogError::Internal(e.to_string()))?;
+        let total = self
+            .data
+            .count_bookmarks(user_id)
+            .await
+            .map_err(|e| BlogError::Internal(e.to_string()))?;
+
+        Ok(PostPage {
+            posts,
+            total,
+            next_cursor: None,
+        })
+    }
+
+    /// Заполняет `bookmarked` для авторизованного пользователя одним запросом на страницу
+    pub async fn mark_bookmarked(
+        &self,
+        user_id: Option<Uuid>,
+        posts: &mut [Post],
+    ) -> Result<(), BlogError> {
+        let Some(user_id) = user_id else {
+            return Ok(());
+        };
+        if posts.is_empty() {
+            return Ok(());
+        }
+        let ids: Vec<i64> = posts.iter().map(|post| post.id).collect();
+        let bookmarked = self
+            .data
+            .get_bookmarked_ids(user_id, &ids)
+            .await
+            .map_err(|e| BlogError::Internal(e.to_string()))?;
+        for post in posts {
+            post.bookmarked = Some(bookmarked.contains(&post.id));
+        }
+        Ok(())
+    }
+
+    pub async fn get_drafts(
+        &self,
+        author_id: Uuid,
+        offset: i64,
+        count: i64,
+    ) -> Result<PostPage, BlogError> {
+        let posts = self
+            .data
+            .get_posts_by_status(author_id, PostStatus::Draft, count.max(0), offset.max(0))
+            .await
+            .map_err(|e| BlogError::Internal(e.to_string()))?;
+        let total = self
+            .data
+            .count_posts_by_status(author_id, PostStatus::Draft)
+            .await
+            .map_err(|e| BlogError::Internal(e.to_string()))?;
+
+        Ok(PostPage {
+            posts,
+            total,
+            next_cursor: None,
+        })
+    }
+
+    pub async fn change_status(&self, post: &Post, status: PostStatus) -> Result<Post, BlogError> {
+        match self.data.set_status(post.id, status).await {
             Ok(p) => Ok(p),
             Err(PostRepositoryError::NotFound) => {
                 Err(BlogError::NotFound("Posts not found".to_string()))
@@ -50,23 +319,63 @@ impl<R: PostRepository> BlogService<R> {
         }
     }
 
-    pub async fn create_post(&self, post: CreatePost, author_id: Uuid) -> Result<Post, BlogError> {
-        match self.data.create_post(post, author_id).await {
+    pub async fn create_post(
+        &self,
+        mut post: CreatePost,
+        author_id: Uuid,
+    ) -> Result<Post, BlogError> {
+        post.tags = normalize_tags(post.tags)?;
+        validate_publish_at(post.publish_at)?;
+        // Запланированный пост остаётся черновиком до наступления срока
+        if post.publish_at.is_some() {
+            post.draft = true;
+        }
+        let content_html = render_html(&post.content, post.content_format);
+        match self.data.create_post(post, author_id, &content_html).await {
             Ok(p) => Ok(p),
             Err(PostRepositoryError::AutorNotFound) => Err(BlogError::NotFound(format!(
                 "Not found Uuid: {}",
                 author_id
             ))),
+            Err(PostRepositoryError::SlugTaken) => Err(BlogError::Conflict(
+                "post slug is taken, retry the request".to_string(),
+            )),
             Err(e) => Err(BlogError::Internal(e.to_string())),
         }
     }
 
-    pub async fn update_post(&self, post: &Post, update: UpdatePost) -> Result<Post, BlogError> {
-        match self.data.update_post(post.id, update).await {
+    pub async fn update_post(
+        &self,
+        post: &Post,
+        mut update: UpdatePost,
+        editor_id: Uuid,
+    ) -> Result<Post, BlogError> {
+        update.tags = update.tags.map(normalize_tags).transpose()?;
+        validate_publish_at(update.publish_at)?;
+        if update.publish_at.is_some() {
+            match update.status {
+                None | Some(PostStatus::Draft) => update.status = Some(PostStatus::Draft),
+                Some(_) => {
+                    return Err(BlogError::Validation(
+                        "only drafts can be scheduled".to_string(),
+                    ));
+                }
+            }
+        }
+        let content_format = update.content_format.unwrap_or(post.content_format);
+        let content_html = render_html(&update.content, content_format);
+        match self
+            .data
+            .update_post(post.id, update, editor_id, &content_html)
+            .await
+        {
             Ok(p) => Ok(p),
             Err(PostRepositoryError::NotFound) => {
                 Err(BlogError::NotFound("Posts not found".to_string()))
             }
+            Err(PostRepositoryError::SlugTaken) => Err(BlogError::Conflict(
+                "post slug is taken, retry the request".to_string(),
+            )),
             Err(e) => Err(BlogError::Internal(e.to_string())),
         }
     }
@@ -77,4 +386,98 @@ impl<R: PostRepository> BlogService<R> {
             .await
             .map_err(|e| BlogError::Internal(e.to_string()))
     }
+
+    pub async fn get_tags(&self) -> Result<Vec<TagCount>, BlogError> {
+        self.data
+            .get_tags()
+            .await
+            .map_err(|e| BlogError::Internal(e.to_string()))
+    }
+
+    /// Публикует посты, срок публикации которых наступил
+    pub async fn publish_due_posts(&self) -> Result<Vec<i64>, BlogError> {
+        self.data
+            .publish_due_posts(PUBLISH_BATCH_SIZE)
+            .await
+            .map_err(|e| BlogError::Internal(e.to_string()))
+    }
+
+    pub async fn get_revisions(&self, post_id: i64) -> Result<Vec<PostRevision>, BlogError> {
+        self.data
+            .get_revisions(post_id)
+            .await
+            .map_err(|e| BlogError::Internal(e.to_string()))
+    }
+
+    pub async fn get_revision(
+        &self,
+        post_id: i64,
+        revision: i32,
+    ) -> Result<PostRevision, BlogError> {
+        match self.data.get_revision(post_id, revision).await {
+            Ok(r) => Ok(r),
+            Err(PostRepositoryError::NotFound) => {
+                Err(BlogError::NotFound("Revision not found".to_string()))
+            }
+            Err(e) => Err(BlogError::Internal(e.to_string())),
+        }
+    }
+
+    /// Сравнивает ревизию `from` с ревизией `to` или с текущей версией поста
+    pub async fn diff_revisions(
+        &self,
+        post: &Post,
+        from: i32,
+        to: Option<i32>,
+    ) -> Result<RevisionDiff, BlogError> {
+        let old = self.get_revision(post.id, from).await?;
+        let (title, content) = match to {
+            Some(to) => {
+                let new = self.get_revision(post.id, to).await?;
+                (new.title, new.content)
+            }
+            None => (post.title.clone(), post.content.clone()),
+        };
+
+        Ok(RevisionDiff {
+            post_id: post.id,
+            from,
+            to,
+            title: diff_lines(&old.title, &title),
+            content: diff_lines(&old.content, &content),
+        })
+    }
+
+    /// Возвращает заголовок и текст ревизии. Текущая версия при этом
+    /// сохраняется как новая ревизия, так что восстановление обратимо
+    pub async fn restore_revision(
+        &self,
+        post: &Post,
+        revision: i32,
+        editor_id: Uuid,
+    ) -> Result<Post, BlogError> {
+        let revision = self.get_revision(post.id, revision).await?;
+        let update = UpdatePost {
+            title: revision.title,
+            content: revision.content,
+            tags: None,
+            status: None,
+            publish_at: None,
+            content_format: None,
+        };
+        self.update_post(post, update, editor_id).await
+    }
+
+    pub async fn search_posts(
+        &self,
+        query: &str,
+        offset: i64,
+        count: i64,
+    ) -> Result<SearchResults, BlogError> {
+        let query = normalize_query(query)?;
+        self.data
+            .search_posts(&query, count.max(0), offset.max(0))
+            .await
+            .map_err(|e| BlogError::Internal(e.to_string()))
+    }
 }
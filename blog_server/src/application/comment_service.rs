@@ -0,0 +1,150 @@
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+use crate::{
+    data::{
+        comment_repository::{CommentRepository, CommentRepositoryError},
+        posr_repository::{PostRepository, PostRepositoryError},
+    },
+    domain::{
+        auth::AccessSession,
+        comment::{Comment, CreateComment, UpdateComment},
+        error::BlogError,
+        post::Post,
+    },
+};
+
+#[derive(Clone)]
+pub struct CommentService<C: CommentRepository, P: PostRepository> {
+    data: Arc<C>,
+    posts: Arc<P>,
+}
+
+impl<C: CommentRepository, P: PostRepository> CommentService<C, P> {
+    pub fn new(data: Arc<C>, posts: Arc<P>) -> Self {
+        Self { data, posts }
+    }
+
+    /// Комментарии неопубликованного поста видны только тем, кому виден сам пост
+    pub async fn get_comments(
+        &self,
+        post_id: i64,
+        session: Option<&AccessSession>,
+    ) -> Result<Vec<Comment>, BlogError> {
+        self.ensure_post_visible(post_id, session).await?;
+        self.data
+            .get_comments_by_post(post_id)
+            .await
+            .map_err(|e| BlogError::Internal(e.to_string()))
+    }
+
+    pub async fn get_comment_by_id(&self, id: i64) -> Result<Comment, BlogError> {
+        match self.data.get_comment(id).await {
+            Ok(c) => Ok(c),
+            Err(CommentRepositoryError::NotFound) => {
+                Err(BlogError::NotFound("Comment not found".to_string()))
+            }
+            Err(e) => Err(BlogError::Internal(e.to_string())),
+        }
+    }
+
+    pub async fn create_comment(
+        &self,
+        post_id: i64,
+        comment: CreateComment,
+        session: &AccessSession,
+    ) -> Result<Comment, BlogError> {
+        validate_content(&comment.content)?;
+        self.ensure_post_visible(post_id, Some(session)).await?;
+
+        if let Some(parent_id) = comment.parent_id {
+            let parent = self.get_comment_by_id(parent_id).await?;
+            if parent.post_id != post_id {
+                return Err(BlogError::Validation(
+                    "parent comment belongs to another post".to_string(),
+                ));
+            }
+        }
+
+        self.data
+            .create_comment(post_id, comment, session.user_id)
+            .await
+            .map_err(|e| BlogError::Internal(e.to_string()))
+    }
+
+    /// Редактировать комментарий может только его автор
+    pub async fn update_comment(
+        &self,
+        id: i64,
+        update: UpdateComment,
+        user_id: Uuid,
+    ) -> Result<Comment, BlogError> {
+        validate_content(&update.content)?;
+        let comment = self.get_comment_by_id(id).await?;
+        if comment.author_id != user_id {
+            return Err(BlogError::Forbidden);
+        }
+
+        match self.data.update_comment(id, update).await {
+            Ok(c) => Ok(c),
+            Err(CommentRepositoryError::NotFound) => {
+                Err(BlogError::NotFound("Comment not found".to_string()))
+            }
+            Err(e) => Err(BlogError::Internal(e.to_string())),
+        }
+    }
+
+    /// Удалить комментарий может его автор, автор поста или модератор
+    pub async fn delete_comment(
+        &self,
+        id: i64,
+        session: &AccessSession,
+    ) -> Result<Comment, BlogError> {
+        let comment = self.get_comment_by_id(id).await?;
+        if comment.author_id != session.user_id {
+            let post = self.get_post(comment.post_id).await?;
+            session.ensure_can_modify(post.author_id)?;
+        }
+
+        match self.data.delete_comment(id).await {
+            Ok(()) => Ok(comment),
+            Err(CommentRepositoryError::NotFound) => {
+                Err(BlogError::NotFound("Comment not found".to_string()))
+            }
+            Err(e) => Err(BlogError::Internal(e.to_string())),
+        }
+    }
+
+    /// Чужой неопубликованный пост неотличим от несуществующего
+    async fn ensure_post_visible(
+        &self,
+        post_id: i64,
+        session: Option<&AccessSession>,
+    ) -> Result<(), BlogError> {
+        let post = self.get_post(post_id).await?;
+        if !post.is_visible_to(session) {
+            return Err(BlogError::NotFound("Posts not found".to_string()));
+        }
+        Ok(())
+    }
+
+    async fn get_post(&self, post_id: i64) -> Result<Post, BlogError> {
+        match self.posts.get_post(post_id).await {
+            Ok(p) => Ok(p),
+            Err(PostRepositoryError::NotFound) => {
+                Err(BlogError::NotFound("Posts not found".to_string()))
+            }
+            Err(e) => Err(BlogError::Internal(e.to_string())),
+        }
+    }
+}
+
+fn validate_content(content: &str) -> Result<(), BlogError> {
+    if content.trim().is_empty() {
+        return Err(BlogError::Validation(
+            "comment content is empty".to_string(),
+        ));
+    }
+    Ok(())
+}
@@ -1,5 +1,13 @@
+pub mod account_service;
+pub mod attachment_service;
 pub mod auth_service;
 pub mod blog_service;
+pub mod comment_service;
+pub mod publish_scheduler;
 
+pub use account_service::AccountService;
+pub use attachment_service::AttachmentService;
 pub use auth_service::AuthService;
 pub use blog_service::BlogService;
+pub use comment_service::CommentService;
+pub use publish_scheduler::run_publish_scheduler;
@@ -0,0 +1,26 @@
+use std::time::Duration;
+
+use tokio::time::MissedTickBehavior;
+
+use crate::{application::BlogService, data::posr_repository::PostRepository};
+
+/// Периодически публикует посты, срок публикации которых наступил.
+/// Ошибки логируются, цикл не прерывается
+pub async fn run_publish_scheduler<R: PostRepository>(
+    blog_service: BlogService<R>,
+    period: Duration,
+) {
+    let mut interval = tokio::time::interval(period);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        interval.tick().await;
+        match blog_service.publish_due_posts().await {
+            Ok(ids) if !ids.is_empty() => {
+                tracing::info!(count = ids.len(), post_ids = ?ids, "Scheduled posts published");
+            }
+            Ok(_) => {}
+            Err(e) => tracing::error!(error = %e, "Failed to publish scheduled posts"),
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::domain::attachment::{Attachment, CreateAttachment};
+
+#[async_trait]
+pub trait AttachmentRepository: Send + Sync {
+    /// Повторная загрузка того же файла владельцем к тому же посту возвращает
+    /// существующую запись. К другому посту создаётся новая запись с тем же `sha256`
+    async fn create_attachment(
+        &self,
+        owner_id: Uuid,
+        attachment: CreateAttachment,
+    ) -> Result<Attachment, AttachmentRepositoryError>;
+    async fn get_attachment(&self, id: i64) -> Result<Attachment, AttachmentRepositoryError>;
+    async fn get_attachments_by_post(
+        &self,
+        post_id: i64,
+    ) -> Result<Vec<Attachment>, AttachmentRepositoryError>;
+    async fn get_attachments_by_owner(
+        &self,
+        owner_id: Uuid,
+    ) -> Result<Vec<Attachment>, AttachmentRepositoryError>;
+    /// Хеши из списка, на которые больше не ссылается ни одно вложение.
+    /// Содержимое с такими ключами можно удалить из хранилища
+    async fn get_unreferenced_hashes(
+        &self,
+        hashes: &[String],
+    ) -> Result<Vec<String>, AttachmentRepositoryError>;
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AttachmentRepositoryError {
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+
+    #[error("Attachment not found")]
+    NotFound,
+}
+
+#[derive(Clone)]
+pub struct PostgresAttachmentRepository {
+    pool: sqlx::PgPool,
+}
+
+impl PostgresAttachmentRepository {
+    pub fn new(pool: sqlx::PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl AttachmentRepository for PostgresAttachmentRepository {
+    async fn create_attachment(
+        &self,
+        owner_id: Uuid,
+        attachment: CreateAttachment,
+    ) -> Result<Attachment, AttachmentRepositoryError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| AttachmentRepositoryError::DatabaseError(e.to_string()))?;
+
+        let attachment = sqlx::query_as!(
+            Attachment,
+            r#"
+            INSERT INTO attachments (owner_id, post_id, file_name, content_type, size, sha256)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (owner_id, COALESCE(post_id, 0), sha256)
+                WHERE owner_id <> '00000000-0000-0000-0000-000000000000'
+            DO UPDATE
+                SET post_id = attachments.post_id
+            RETURNING id, owner_id, post_id, file_name, content_type, size, sha256,
+            created_at as "created_at!: chrono::DateTime<chrono::Utc>"
+            "#,
+            owner_id,
+            attachment.post_id,
+            attachment.file_name,
+            attachment.content_type,
+            attachment.size,
+            attachment.sha256
+        )
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| AttachmentRepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(attachment)
+    }
+
+    async fn get_attachment(&self, id: i64) -> Result<Attachment, AttachmentRepositoryError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| AttachmentRepositoryError::DatabaseError(e.to_string()))?;
+
+        sqlx::query_as!(
+            Attachment,
+            r#"
+            SELECT id, owner_id, post_id, file_name, content_type, size, sha256,
+            created_at as "created_at!: chrono::DateTime<chrono::Utc>"
+            FROM attachments WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| AttachmentRepositoryError::DatabaseError(e.to_string()))?
+        .ok_or(AttachmentRepositoryError::NotFound)
+    }
+
+    async fn get_attachments_by_post(
+        &self,
+        post_id: i64,
+    ) -> Result<Vec<Attachment>, AttachmentRepositoryError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| AttachmentRepositoryError::DatabaseError(e.to_string()))?;
+
+        let attachments = sqlx::query_as!(
+            Attachment,
+            r#"
+            SELECT id, owner_id, post_id, file_name, content_type, size, sha256,
+            created_at as "created_at!: chrono::DateTime<chrono::Utc>"
+            FROM attachments WHERE post_id = $1
+            ORDER BY id
+            "#,
+            post_id
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| AttachmentRepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(attachments)
+    }
+
+    async fn get_attachments_by_owner(
+        &self,
+        owner_id: Uuid,
+    ) -> Result<Vec<Attachment>, AttachmentRepositoryError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| AttachmentRepositoryError::DatabaseError(e.to_string()))?;
+
+        let attachments = sqlx::query_as!(
+            Attachment,
+            r#"
+            SELECT id, owner_id, post_id, file_name, content_type, size, sha256,
+            created_at as "created_at!: chrono::DateTime<chrono::Utc>"
+            FROM attachments WHERE owner_id = $1
+            ORDER BY id
+            "#,
+            owner_id
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| AttachmentRepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(attachments)
+    }
+
+    async fn get_unreferenced_hashes(
+        &self,
+        hashes: &[String],
+    ) -> Result<Vec<String>, AttachmentRepositoryError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| AttachmentRepositoryError::DatabaseError(e.to_string()))?;
+
+        let hashes = sqlx::query_scalar!(
+            r#"
+            SELECT DISTINCT h.sha256 as "sha256!"
+            FROM UNNEST($1::text[]) AS h(sha256)
+            WHERE NOT EXISTS (SELECT 1 FROM attachments a WHERE a.sha256 = h.sha256)
+            "#,
+            hashes
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| AttachmentRepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(hashes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        data::{
+            posr_repository::{PostRepository, PostgresPostRepository},
+            user_repository::{PostgresUserRepository, UserRepository},
+        },
+        domain::{auth::User, content::ContentFormat, post::CreatePost},
+    };
+
+    #[sqlx::test]
+    async fn test_same_file_in_two_posts(pool: sqlx::PgPool) {
+        let owner_id = PostgresUserRepository::new(pool.clone())
+            .create_user(User::new(
+                "author".to_string(),
+                "author@example.com".to_string(),
+                "hash".to_string(),
+            ))
+            .await
+            .expect("Failed to create user")
+            .id;
+        let posts = PostgresPostRepository::new(pool.clone());
+        let mut post_ids = Vec::new();
+        for title in ["First", "Second"] {
+            let post = CreatePost {
+                title: title.to_string(),
+                content: "text".to_string(),
+                content_format: ContentFormat::default(),
+                tags: Vec::new(),
+                draft: false,
+                publish_at: None,
+            };
+            let post = posts
+                .create_post(post, owner_id, "<p>text</p>")
+                .await
+                .expect("Failed to create post");
+            post_ids.push(post.id);
+        }
+
+        let repo = PostgresAttachmentRepository::new(pool);
+        let upload = |post_id| CreateAttachment {
+            post_id: Some(post_id),
+            file_name: "image.png".to_string(),
+            content_type: "image/png".to_string(),
+            size: 3,
+            sha256: "a".repeat(64),
+        };
+        let first = repo
+            .create_attachment(owner_id, upload(post_ids[0]))
+            .await
+            .expect("Failed to create attachment");
+        let second = repo
+            .create_attachment(owner_id, upload(post_ids[1]))
+            .await
+            .expect("Failed to create attachment");
+        let again = repo
+            .create_attachment(owner_id, upload(post_ids[0]))
+            .await
+            .expect("Failed to create attachment");
+
+        assert_ne!(first.id, second.id);
+        assert_eq!(again.id, first.id);
+        for (post_id, attachment) in post_ids.iter().zip([&first, &second]) {
+            let listed = repo
+                .get_attachments_by_post(*post_id)
+                .await
+                .expect("Failed to list attachments");
+            let ids: Vec<_> = listed.iter().map(|a| a.id).collect();
+            assert_eq!(ids, [attachment.id]);
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::domain::comment::{Comment, CreateComment, UpdateComment};
+
+#[async_trait]
+pub trait CommentRepository: Send + Sync {
+    async fn create_comment(
+        &self,
+        post_id: i64,
+        comment: CreateComment,
+        author_id: Uuid,
+    ) -> Result<Comment, CommentRepositoryError>;
+    async fn get_comment(&self, comment_id: i64) -> Result<Comment, CommentRepositoryError>;
+
+    /// Все комментарии поста в порядке создания.
+    /// Дерево строится на клиенте по `parent_id`
+    async fn get_comments_by_post(
+        &self,
+        post_id: i64,
+    ) -> Result<Vec<Comment>, CommentRepositoryError>;
+    /// Все комментарии пользователя, от старых к новым
+    async fn get_comments_by_author(
+        &self,
+        author_id: Uuid,
+    ) -> Result<Vec<Comment>, CommentRepositoryError>;
+    async fn update_comment(
+        &self,
+        comment_id: i64,
+        comment: UpdateComment,
+    ) -> Result<Comment, CommentRepositoryError>;
+    async fn delete_comment(&self, comment_id: i64) -> Result<(), CommentRepositoryError>;
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CommentRepositoryError {
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+
+    #[error("Comment not found")]
+    NotFound,
+}
+
+#[derive(Clone)]
+pub struct PostgresCommentRepository {
+    pool: sqlx::PgPool,
+}
+
+impl PostgresCommentRepository {
+    pub fn new(pool: sqlx::PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl CommentRepository for PostgresCommentRepository {
+    async fn create_comment(
+        &self,
+        post_id: i64,
+        comment: CreateComment,
+        author_id: Uuid,
+    ) -> Result<Comment, CommentRepositoryError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| CommentRepositoryError::DatabaseError(e.to_string()))?;
+
+        let new_comment = sqlx::query_as!(
+            Comment,
+            r#"
+            INSERT INTO comments (post_id, author_id, parent_id, content)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, post_id, author_id, parent_id, content, created_at, updated_at
+            "#,
+            post_id,
+            author_id,
+            comment.parent_id,
+            comment.content
+        )
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| CommentRepositoryError::DatabaseError(e.to_string()))?;
+        Ok(new_comment)
+    }
+
+    async fn get_comment(&self, comment_id: i64) -> Result<Comment, CommentRepositoryError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| CommentRepositoryError::DatabaseError(e.to_string()))?;
+
+        let comment = sqlx::query_as!(
+            Comment,
+            r#"
+            SELECT id, post_id, author_id, parent_id, content, created_at, updated_at
+            FROM comments WHERE id = $1
+            "#,
+            comment_id
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| CommentRepositoryError::DatabaseError(e.to_string()))?;
+
+        comment.ok_or(CommentRepositoryError::NotFound)
+    }
+
+    async fn get_comments_by_post(
+        &self,
+        post_id: i64,
+    ) -> Result<Vec<Comment>, CommentRepositoryError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| CommentRepositoryError::DatabaseError(e.to_string()))?;
+
+        let comments = sqlx::query_as!(
+            Comment,
+            r#"
+            SELECT id, post_id, author_id, parent_id, content, created_at, updated_at
+            FROM comments
+            WHERE post_id = $1
+            ORDER BY created_at, id
+            "#,
+            post_id
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| CommentRepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(comments)
+    }
+
+    async fn get_comments_by_author(
+        &self,
+        author_id: Uuid,
+    ) -> Result<Vec<Comment>, CommentRepositoryError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| CommentRepositoryError::DatabaseError(e.to_string()))?;
+
+        let comments = sqlx::query_as!(
+            Comment,
+            r#"
+            SELECT id, post_id, author_id, parent_id, content, created_at, updated_at
+            FROM comments
+            WHERE author_id = $1
+            ORDER BY created_at, id
+            "#,
+            author_id
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| CommentRepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(comments)
+    }
+
+    async fn update_comment(
+        &self,
+        comment_id: i64,
+        comment: UpdateComment,
+    ) -> Result<Comment, CommentRepositoryError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| CommentRepositoryError::DatabaseError(e.to_string()))?;
+
+        let comment = sqlx::query_as!(
+            Comment,
+            r#"
+            UPDATE comments
+            SET
+                content = $1,
+                updated_at = NOW()
+            WHERE id = $2
+            RETURNING id, post_id, author_id, parent_id, content, created_at, updated_at
+            "#,
+            comment.content,
+            comment_id
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| CommentRepositoryError::DatabaseError(e.to_string()))?;
+
+        comment.ok_or(CommentRepositoryError::NotFound)
+    }
+
+    async fn delete_comment(&self, comment_id: i64) -> Result<(), CommentRepositoryError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| CommentRepositoryError::DatabaseError(e.to_string()))?;
+
+        let res = sqlx::query!("DELETE FROM comments WHERE id = $1", comment_id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| CommentRepositoryError::DatabaseError(e.to_string()))?;
+
+        if res.rows_affected() == 0 {
+            return Err(CommentRepositoryError::NotFound);
+        }
+        Ok(())
+    }
+}
@@ -1,2 +1,6 @@
+pub mod attachment_repository;
+pub mod comment_repository;
+pub mod mailer;
 pub mod posr_repository;
+pub mod storage;
 pub mod user_repository;
@@ -1,32 +1,147 @@
 use async_trait::async_trait;
+use sqlx::{PgConnection, types::Json};
 use uuid::Uuid;
 
-use crate::domain::post::{CreatePost, Post, UpdatePost};
+use crate::domain::{
+    content::ContentFormat,
+    pagination::PageRequest,
+    post::{CreatePost, Post, PostStatus, TagCount, UpdatePost},
+    profile::AuthorSummary,
+    reaction::{PostOrder, PostReactions, ReactionCounts, ReactionKind},
+    revision::PostRevision,
+    search::{HIGHLIGHT_START, HIGHLIGHT_STOP, SearchHit, SearchResults, highlight_snippet},
+    slug::{pick_free_slug, slugify},
+};
 
 #[async_trait]
 pub trait PostRepository: Send + Sync {
+    /// `content_html` - текст поста, уже отрендеренный в HTML
     async fn create_post(
         &self,
         post: CreatePost,
         author_id: Uuid,
+        content_html: &str,
     ) -> Result<Post, PostRepositoryError>;
     async fn get_post(&self, post_id: i64) -> Result<Post, PostRepositoryError>;
+    /// Ищет пост по текущему или прежнему slug
+    async fn get_post_by_slug(&self, slug: &str) -> Result<Post, PostRepositoryError>;
 
+    /// Только опубликованные посты.
     /// len: i32 количество постов
-    /// offset: Option<i32> отступ от самого последнего поста
+    /// page: отступ от самого последнего поста или курсор предыдущей страницы
+    /// tags: только посты, отмеченные всеми перечисленными тегами
+    /// order: курсор поддерживается только для порядка `Latest`
     async fn get_last_posts(
         &self,
         len: i64,
-        offset: Option<i64>,
+        page: PageRequest,
+        tags: &[String],
+        order: PostOrder,
     ) -> Result<Vec<Post>, PostRepositoryError>;
-    #[allow(dead_code)]
-    async fn get_posts_by_author(&self, user_id: Uuid) -> Result<Vec<Post>, PostRepositoryError>;
+    /// Количество опубликованных постов с указанными тегами
+    async fn count_published_posts(&self, tags: &[String]) -> Result<i64, PostRepositoryError>;
+    /// Опубликованные посты авторов, на которых подписан пользователь, от новых к старым
+    async fn get_feed_posts(
+        &self,
+        user_id: Uuid,
+        len: i64,
+        page: PageRequest,
+    ) -> Result<Vec<Post>, PostRepositoryError>;
+    async fn count_feed_posts(&self, user_id: Uuid) -> Result<i64, PostRepositoryError>;
+    /// Опубликованные посты автора, от новых к старым
+    async fn get_posts_by_author(
+        &self,
+        user_id: Uuid,
+        len: i64,
+        offset: i64,
+    ) -> Result<Vec<Post>, PostRepositoryError>;
+    /// Посты автора в заданном статусе, от новых к старым
+    async fn get_posts_by_status(
+        &self,
+        user_id: Uuid,
+        status: PostStatus,
+        len: i64,
+        offset: i64,
+    ) -> Result<Vec<Post>, PostRepositoryError>;
+    async fn count_posts_by_status(
+        &self,
+        user_id: Uuid,
+        status: PostStatus,
+    ) -> Result<i64, PostRepositoryError>;
+    /// Перед обновлением сохраняет прежние заголовок и текст как новую ревизию,
+    /// если они меняются
     async fn update_post(
         &self,
         post_id: i64,
         post: UpdatePost,
+        editor_id: Uuid,
+        content_html: &str,
     ) -> Result<Post, PostRepositoryError>;
     async fn delete_post(&self, post_id: i64) -> Result<(), PostRepositoryError>;
+    /// Меняет статус поста. `published_at` выставляется при первой публикации
+    async fn set_status(
+        &self,
+        post_id: i64,
+        status: PostStatus,
+    ) -> Result<Post, PostRepositoryError>;
+    /// Теги, у которых есть хотя бы один пост
+    async fn get_tags(&self) -> Result<Vec<TagCount>, PostRepositoryError>;
+    /// Публикует черновики, время публикации которых наступило.
+    /// Строки блокируются через `SKIP LOCKED`, поэтому несколько экземпляров
+    /// сервера не опубликуют один пост дважды
+    async fn publish_due_posts(&self, limit: i64) -> Result<Vec<i64>, PostRepositoryError>;
+    /// Полнотекстовый поиск по опубликованным постам, от более релевантных
+    async fn search_posts(
+        &self,
+        query: &str,
+        len: i64,
+        offset: i64,
+    ) -> Result<SearchResults, PostRepositoryError>;
+    /// Ревизии поста от старых к новым
+    async fn get_revisions(&self, post_id: i64) -> Result<Vec<PostRevision>, PostRepositoryError>;
+    async fn get_revision(
+        &self,
+        post_id: i64,
+        revision: i32,
+    ) -> Result<PostRevision, PostRepositoryError>;
+
+    /// Повторная реакция того же вида ничего не меняет
+    async fn add_reaction(
+        &self,
+        post_id: i64,
+        user_id: Uuid,
+        kind: ReactionKind,
+    ) -> Result<(), PostRepositoryError>;
+    async fn remove_reaction(
+        &self,
+        post_id: i64,
+        user_id: Uuid,
+        kind: ReactionKind,
+    ) -> Result<(), PostRepositoryError>;
+    async fn get_reactions(
+        &self,
+        post_id: i64,
+        user_id: Uuid,
+    ) -> Result<PostReactions, PostRepositoryError>;
+
+    /// Повторное добавление ничего не меняет
+    async fn add_bookmark(&self, user_id: Uuid, post_id: i64) -> Result<(), PostRepositoryError>;
+    async fn remove_bookmark(&self, user_id: Uuid, post_id: i64)
+    -> Result<(), PostRepositoryError>;
+    /// Опубликованные посты из закладок, от новых закладок к старым
+    async fn get_bookmarked_posts(
+        &self,
+        user_id: Uuid,
+        len: i64,
+        offset: i64,
+    ) -> Result<Vec<Post>, PostRepositoryError>;
+    async fn count_bookmarks(&self, user_id: Uuid) -> Result<i64, PostRepositoryError>;
+    /// Какие из переданных постов есть в закладках у пользователя
+    async fn get_bookmarked_ids(
+        &self,
+        user_id: Uuid,
+        post_ids: &[i64],
+    ) -> Result<Vec<i64>, PostRepositoryError>;
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -40,6 +155,23 @@ pub enum PostRepositoryError {
     #[allow(dead_code)]
     #[error("Autor not found")]
     AutorNotFound,
+
+    /// Свободный slug выбирается до записи, и параллельный запрос успел его занять
+    #[error("Slug is already taken")]
+    SlugTaken,
+}
+
+/// Сколько раз создание поста повторяется при гонке за slug
+const SLUG_ATTEMPTS: usize = 3;
+
+fn map_slug_conflict(error: sqlx::Error) -> PostRepositoryError {
+    if let sqlx::Error::Database(db_error) = &error
+        && db_error.is_unique_violation()
+        && db_error.constraint() == Some("posts_slug_key")
+    {
+        return PostRepositoryError::SlugTaken;
+    }
+    PostRepositoryError::DatabaseError(error.to_string())
 }
 
 #[derive(Clone)]
@@ -53,79 +185,444 @@ impl PostgresPostRepository {
     }
 }
 
+impl PostgresPostRepository {
+    async fn fetch_post(
+        conn: &mut PgConnection,
+        post_id: i64,
+    ) -> Result<Option<Post>, PostRepositoryError> {
+        sqlx::query_as!(
+            Post,
+            r#"
+            SELECT
+            id as "id!",
+            slug as "slug!",
+            title as "title!",
+            content as "content!: String",
+            content_format as "content_format!: ContentFormat",
+            content_html as "content_html!",
+            author_id as "author_id!: Uuid",
+            author as "author!: Json<AuthorSummary>",
+            reactions as "reactions!: Json<ReactionCounts>",
+            NULL::boolean as "bookmarked?",
+            created_at as "created_at!: chrono::DateTime<chrono::Utc>",
+            updated_at as "updated_at?: chrono::DateTime<chrono::Utc>",
+            tags as "tags!: Vec<String>",
+            status as "status!: PostStatus",
+            published_at as "published_at?: chrono::DateTime<chrono::Utc>",
+            publish_at as "publish_at?: chrono::DateTime<chrono::Utc>"
+            FROM posts_view WHERE id = $1
+            "#,
+            post_id
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))
+    }
+
+    /// Посты с перечисленными id, порядок не гарантируется
+    async fn fetch_posts(
+        conn: &mut PgConnection,
+        post_ids: &[i64],
+    ) -> Result<Vec<Post>, PostRepositoryError> {
+        sqlx::query_as!(
+            Post,
+            r#"
+            SELECT
+            id as "id!",
+            slug as "slug!",
+            title as "title!",
+            content as "content!: String",
+            content_format as "content_format!: ContentFormat",
+            content_html as "content_html!",
+            author_id as "author_id!: Uuid",
+            author as "author!: Json<AuthorSummary>",
+            reactions as "reactions!: Json<ReactionCounts>",
+            NULL::boolean as "bookmarked?",
+            created_at as "created_at!: chrono::DateTime<chrono::Utc>",
+            updated_at as "updated_at?: chrono::DateTime<chrono::Utc>",
+            tags as "tags!: Vec<String>",
+            status as "status!: PostStatus",
+            published_at as "published_at?: chrono::DateTime<chrono::Utc>",
+            publish_at as "publish_at?: chrono::DateTime<chrono::Utc>"
+            FROM posts_view WHERE id = ANY($1)
+            "#,
+            post_ids
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))
+    }
+
+    /// Одна попытка создания поста, slug подбирается заново
+    async fn insert_post(
+        &self,
+        post: &CreatePost,
+        author_id: Uuid,
+        content_html: &str,
+    ) -> Result<Post, PostRepositoryError> {
+        let status = if post.draft {
+            PostStatus::Draft
+        } else {
+            PostStatus::Published
+        };
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        let slug = Self::unique_slug(&mut tx, &post.title, None).await?;
+        let post_id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO posts (
+                title, content, author_id, status, published_at, publish_at, slug,
+                content_format, content_html
+            ) 
+            VALUES (
+                $1, $2, $3,
+                $4::text::post_status,
+                CASE WHEN $4::text = 'published' THEN NOW() END,
+                $5, $6,
+                $7::text::content_format, $8
+            ) 
+            RETURNING id
+            "#,
+            post.title,
+            post.content,
+            author_id,
+            status.as_str(),
+            post.publish_at,
+            slug,
+            post.content_format.as_str(),
+            content_html
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(map_slug_conflict)?;
+
+        Self::set_post_tags(&mut tx, post_id, &post.tags).await?;
+        let new_post = Self::fetch_post(&mut tx, post_id)
+            .await?
+            .ok_or(PostRepositoryError::NotFound)?;
+
+        tx.commit()
+            .await
+            .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+        Ok(new_post)
+    }
+
+    /// Свободный slug для заголовка. Прежние slug самого поста считаются
+    /// свободными, чтобы возврат к старому заголовку вернул и старый адрес
+    async fn unique_slug(
+        conn: &mut PgConnection,
+        title: &str,
+        post_id: Option<i64>,
+    ) -> Result<String, PostRepositoryError> {
+        let base = slugify(title);
+        let taken = sqlx::query_scalar!(
+            r#"
+            SELECT slug as "slug!" FROM posts
+            WHERE (slug = $1 OR slug LIKE $1 || '-%') AND id IS DISTINCT FROM $2
+            UNION
+            SELECT slug FROM post_slug_redirects
+            WHERE (slug = $1 OR slug LIKE $1 || '-%') AND post_id IS DISTINCT FROM $2
+            "#,
+            base,
+            post_id
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(pick_free_slug(&base, &taken))
+    }
+
+    /// Заменяет теги поста, создавая отсутствующие
+    async fn set_post_tags(
+        conn: &mut PgConnection,
+        post_id: i64,
+        tags: &[String],
+    ) -> Result<(), PostRepositoryError> {
+        sqlx::query!("DELETE FROM post_tags WHERE post_id = $1", post_id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        if tags.is_empty() {
+            return Ok(());
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO tags (name)
+            SELECT * FROM UNNEST($1::text[])
+            ON CONFLICT (name) DO NOTHING
+            "#,
+            tags
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO post_tags (post_id, tag_id)
+            SELECT $1, id FROM tags WHERE name = ANY($2)
+            "#,
+            post_id,
+            tags
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
 #[async_trait]
 impl PostRepository for PostgresPostRepository {
     async fn create_post(
         &self,
         post: CreatePost,
         author_id: Uuid,
+        content_html: &str,
     ) -> Result<Post, PostRepositoryError> {
+        let mut attempt = 1;
+        loop {
+            match self.insert_post(&post, author_id, content_html).await {
+                Err(PostRepositoryError::SlugTaken) if attempt < SLUG_ATTEMPTS => attempt += 1,
+                result => return result,
+            }
+        }
+    }
+
+    async fn get_post(&self, post_id: i64) -> Result<Post, PostRepositoryError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        if let Some(post) = Self::fetch_post(&mut conn, post_id).await? {
+            return Ok(post);
+        }
+        Err(PostRepositoryError::NotFound)
+    }
+
+    async fn get_post_by_slug(&self, slug: &str) -> Result<Post, PostRepositoryError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        let post_id = sqlx::query_scalar!(
+            r#"
+            SELECT id as "id!" FROM posts WHERE slug = $1
+            UNION ALL
+            SELECT post_id FROM post_slug_redirects WHERE slug = $1
+            LIMIT 1
+            "#,
+            slug
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?
+        .ok_or(PostRepositoryError::NotFound)?;
+
+        Self::fetch_post(&mut conn, post_id)
+            .await?
+            .ok_or(PostRepositoryError::NotFound)
+    }
+
+    async fn get_last_posts(
+        &self,
+        len: i64,
+        page: PageRequest,
+        tags: &[String],
+        order: PostOrder,
+    ) -> Result<Vec<Post>, PostRepositoryError> {
+        // `posts.created_at` хранится без часового пояса в UTC, поэтому курсор
+        // приводится к UTC явно и не зависит от `TimeZone` сессии
+        let (offset, after_created_at, after_id) = match page {
+            PageRequest::Offset(offset) => (offset, None, None),
+            PageRequest::After(cursor) => (0, Some(cursor.created_at), Some(cursor.id)),
+        };
         let mut conn = self
             .pool
             .acquire()
             .await
             .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
 
-        let new_post = sqlx::query_as!(
+        let posts = sqlx::query_as!(
             Post,
             r#"
-            INSERT INTO posts (title, content, author_id) 
-            VALUES ($1, $2, $3) 
-            RETURNING 
-            id, 
-            title, 
-            content as "content!: String", 
-            author_id as "author_id!: Uuid", 
-            created_at as "created_at!: chrono::DateTime<chrono::Utc>", 
-            updated_at as "updated_at?: chrono::DateTime<chrono::Utc>"
+            SELECT
+            id as "id!",
+            slug as "slug!",
+            title as "title!",
+            content as "content!: String",
+            content_format as "content_format!: ContentFormat",
+            content_html as "content_html!",
+            author_id as "author_id!: Uuid",
+            author as "author!: Json<AuthorSummary>",
+            reactions as "reactions!: Json<ReactionCounts>",
+            NULL::boolean as "bookmarked?",
+            created_at as "created_at!: chrono::DateTime<chrono::Utc>",
+            updated_at as "updated_at?: chrono::DateTime<chrono::Utc>",
+            tags as "tags!: Vec<String>",
+            status as "status!: PostStatus",
+            published_at as "published_at?: chrono::DateTime<chrono::Utc>",
+            publish_at as "publish_at?: chrono::DateTime<chrono::Utc>"
+            FROM posts_view 
+            WHERE status = 'published' AND (
+                cardinality($3::text[]) = 0 OR (
+                    SELECT count(*) FROM post_tags pt JOIN tags t ON t.id = pt.tag_id
+                    WHERE pt.post_id = posts_view.id AND t.name = ANY($3)
+                ) = cardinality($3::text[])
+            ) AND (
+                $4::timestamptz IS NULL
+                OR (created_at, id) < ($4::timestamptz AT TIME ZONE 'UTC', $5::bigint)
+            )
+            ORDER BY
+                CASE WHEN $6 = 'liked' THEN (
+                    SELECT count(*) FROM post_reactions r
+                    WHERE r.post_id = posts_view.id AND r.kind = 'like'
+                ) END DESC NULLS LAST,
+                created_at DESC, id DESC
+            LIMIT $1 
+            OFFSET $2
             "#,
-            post.title,
-            post.content,
-            author_id
+            len,
+            offset,
+            tags,
+            after_created_at,
+            after_id,
+            order.as_str()
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(posts)
+    }
+
+    async fn count_published_posts(&self, tags: &[String]) -> Result<i64, PostRepositoryError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        let total = sqlx::query_scalar!(
+            r#"
+            SELECT count(*) as "total!" FROM posts
+            WHERE status = 'published' AND (
+                cardinality($1::text[]) = 0 OR (
+                    SELECT count(*) FROM post_tags pt JOIN tags t ON t.id = pt.tag_id
+                    WHERE pt.post_id = posts.id AND t.name = ANY($1)
+                ) = cardinality($1::text[])
+            )
+            "#,
+            tags
         )
         .fetch_one(&mut *conn)
         .await
         .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
-        Ok(new_post)
+
+        Ok(total)
     }
 
-    async fn get_post(&self, post_id: i64) -> Result<Post, PostRepositoryError> {
+    async fn get_feed_posts(
+        &self,
+        user_id: Uuid,
+        len: i64,
+        page: PageRequest,
+    ) -> Result<Vec<Post>, PostRepositoryError> {
+        let (offset, after_created_at, after_id) = match page {
+            PageRequest::Offset(offset) => (offset, None, None),
+            PageRequest::After(cursor) => (0, Some(cursor.created_at), Some(cursor.id)),
+        };
         let mut conn = self
             .pool
             .acquire()
             .await
             .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
 
-        let new_post = sqlx::query_as!(
+        let posts = sqlx::query_as!(
             Post,
             r#"
-            SELECT 
-            id,
-            title,
+            SELECT
+            id as "id!",
+            slug as "slug!",
+            title as "title!",
             content as "content!: String",
+            content_format as "content_format!: ContentFormat",
+            content_html as "content_html!",
             author_id as "author_id!: Uuid",
-            created_at as "created_at!: chrono::DateTime<chrono::Utc>", 
-            updated_at as "updated_at?: chrono::DateTime<chrono::Utc>"
-            FROM posts WHERE id = $1
+            author as "author!: Json<AuthorSummary>",
+            reactions as "reactions!: Json<ReactionCounts>",
+            NULL::boolean as "bookmarked?",
+            created_at as "created_at!: chrono::DateTime<chrono::Utc>",
+            updated_at as "updated_at?: chrono::DateTime<chrono::Utc>",
+            tags as "tags!: Vec<String>",
+            status as "status!: PostStatus",
+            published_at as "published_at?: chrono::DateTime<chrono::Utc>",
+            publish_at as "publish_at?: chrono::DateTime<chrono::Utc>"
+            FROM posts_view
+            WHERE status = 'published' AND author_id IN (
+                SELECT followee_id FROM follows WHERE follower_id = $3
+            ) AND (
+                $4::timestamptz IS NULL
+                OR (created_at, id) < ($4::timestamptz AT TIME ZONE 'UTC', $5::bigint)
+            )
+            ORDER BY created_at DESC, id DESC
+            LIMIT $1
+            OFFSET $2
             "#,
-            post_id
+            len,
+            offset,
+            user_id,
+            after_created_at,
+            after_id
         )
-        .fetch_optional(&mut *conn)
+        .fetch_all(&mut *conn)
         .await
         .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
 
-        if let Some(post) = new_post {
-            return Ok(post);
-        }
-        Err(PostRepositoryError::NotFound)
+        Ok(posts)
     }
 
-    async fn get_last_posts(
+    async fn count_feed_posts(&self, user_id: Uuid) -> Result<i64, PostRepositoryError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        let total = sqlx::query_scalar!(
+            r#"
+            SELECT count(*) as "total!" FROM posts
+            WHERE status = 'published' AND author_id IN (
+                SELECT followee_id FROM follows WHERE follower_id = $1
+            )
+            "#,
+            user_id
+        )
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(total)
+    }
+
+    async fn get_posts_by_author(
         &self,
+        user_id: Uuid,
         len: i64,
-        offset: Option<i64>,
+        offset: i64,
     ) -> Result<Vec<Post>, PostRepositoryError> {
-        let offset = offset.unwrap_or(0);
         let mut conn = self
             .pool
             .acquire()
@@ -135,18 +632,30 @@ impl PostRepository for PostgresPostRepository {
         let posts = sqlx::query_as!(
             Post,
             r#"
-            SELECT 
-            id,
-            title,
+            SELECT
+            id as "id!",
+            slug as "slug!",
+            title as "title!",
             content as "content!: String",
+            content_format as "content_format!: ContentFormat",
+            content_html as "content_html!",
             author_id as "author_id!: Uuid",
-            created_at as "created_at!: chrono::DateTime<chrono::Utc>", 
-            updated_at as "updated_at?: chrono::DateTime<chrono::Utc>"
-            FROM posts 
-            ORDER BY created_at DESC 
-            LIMIT $1 
-            OFFSET $2
+            author as "author!: Json<AuthorSummary>",
+            reactions as "reactions!: Json<ReactionCounts>",
+            NULL::boolean as "bookmarked?",
+            created_at as "created_at!: chrono::DateTime<chrono::Utc>",
+            updated_at as "updated_at?: chrono::DateTime<chrono::Utc>",
+            tags as "tags!: Vec<String>",
+            status as "status!: PostStatus",
+            published_at as "published_at?: chrono::DateTime<chrono::Utc>",
+            publish_at as "publish_at?: chrono::DateTime<chrono::Utc>"
+            FROM posts_view 
+            WHERE author_id = $1 AND status = 'published'
+            ORDER BY created_at DESC, id DESC
+            LIMIT $2
+            OFFSET $3
             "#,
+            user_id,
             len,
             offset
         )
@@ -157,7 +666,13 @@ impl PostRepository for PostgresPostRepository {
         Ok(posts)
     }
 
-    async fn get_posts_by_author(&self, user_id: Uuid) -> Result<Vec<Post>, PostRepositoryError> {
+    async fn get_posts_by_status(
+        &self,
+        user_id: Uuid,
+        status: PostStatus,
+        len: i64,
+        offset: i64,
+    ) -> Result<Vec<Post>, PostRepositoryError> {
         let mut conn = self
             .pool
             .acquire()
@@ -167,17 +682,33 @@ impl PostRepository for PostgresPostRepository {
         let posts = sqlx::query_as!(
             Post,
             r#"
-            SELECT 
-            id,
-            title,
+            SELECT
+            id as "id!",
+            slug as "slug!",
+            title as "title!",
             content as "content!: String",
+            content_format as "content_format!: ContentFormat",
+            content_html as "content_html!",
             author_id as "author_id!: Uuid",
-            created_at as "created_at!: chrono::DateTime<chrono::Utc>", 
-            updated_at as "updated_at?: chrono::DateTime<chrono::Utc>"
-            FROM posts 
-            WHERE author_id = $1
+            author as "author!: Json<AuthorSummary>",
+            reactions as "reactions!: Json<ReactionCounts>",
+            NULL::boolean as "bookmarked?",
+            created_at as "created_at!: chrono::DateTime<chrono::Utc>",
+            updated_at as "updated_at?: chrono::DateTime<chrono::Utc>",
+            tags as "tags!: Vec<String>",
+            status as "status!: PostStatus",
+            published_at as "published_at?: chrono::DateTime<chrono::Utc>",
+            publish_at as "publish_at?: chrono::DateTime<chrono::Utc>"
+            FROM posts_view 
+            WHERE author_id = $1 AND status = $2::text::post_status
+            ORDER BY created_at DESC, id DESC
+            LIMIT $3 
+            OFFSET $4
             "#,
-            user_id
+            user_id,
+            status.as_str(),
+            len,
+            offset
         )
         .fetch_all(&mut *conn)
         .await
@@ -186,59 +717,709 @@ impl PostRepository for PostgresPostRepository {
         Ok(posts)
     }
 
-    async fn update_post(
+    async fn count_posts_by_status(
         &self,
-        post_id: i64,
-        post: UpdatePost,
-    ) -> Result<Post, PostRepositoryError> {
+        user_id: Uuid,
+        status: PostStatus,
+    ) -> Result<i64, PostRepositoryError> {
         let mut conn = self
             .pool
             .acquire()
             .await
             .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
 
-        let new_post = sqlx::query_as!(
-            Post,
+        let total = sqlx::query_scalar!(
             r#"
-            UPDATE posts 
-            SET 
-                title = COALESCE($1, title),
-                content = COALESCE($2, content),
-                updated_at = NOW()
-            WHERE id = $3 
-            RETURNING 
-            id, 
-            title, 
-            content as "content!: String", 
-            author_id as "author_id!: Uuid", 
-            created_at as "created_at!: chrono::DateTime<chrono::Utc>", 
-            updated_at as "updated_at?: chrono::DateTime<chrono::Utc>"
+            SELECT count(*) as "total!" FROM posts
+            WHERE author_id = $1 AND status = $2::text::post_status
             "#,
-            post.title,
-            post.content,
-            post_id
+            user_id,
+            status.as_str()
         )
         .fetch_one(&mut *conn)
         .await
         .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
-        Ok(new_post)
+
+        Ok(total)
     }
 
-    async fn delete_post(&self, post_id: i64) -> Result<(), PostRepositoryError> {
-        let mut conn = self
+    async fn update_post(
+        &self,
+        post_id: i64,
+        post: UpdatePost,
+        editor_id: Uuid,
+        content_html: &str,
+    ) -> Result<Post, PostRepositoryError> {
+        let mut tx = self
             .pool
-            .acquire()
+            .begin()
             .await
             .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
 
-        let res = sqlx::query!("DELETE FROM posts WHERE id = $1", post_id)
-            .execute(&mut *conn)
+        // Блокировка строки поста упорядочивает нумерацию ревизий
+        let previous = sqlx::query!(
+            r#"
+            SELECT title, content as "content!: String", slug
+            FROM posts WHERE id = $1
+            FOR UPDATE
+            "#,
+            post_id
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?
+        .ok_or(PostRepositoryError::NotFound)?;
+
+        // Ревизия сохраняется только при изменении текста, смена статуса,
+        // тегов или расписания в историю не попадает
+        if post.title != previous.title || post.content != previous.content {
+            sqlx::query!(
+                r#"
+                INSERT INTO post_revisions (post_id, revision, editor_id, title, content)
+                VALUES (
+                    $1,
+                    (SELECT COALESCE(MAX(revision), 0) + 1 FROM post_revisions WHERE post_id = $1),
+                    $2, $3, $4
+                )
+                "#,
+                post_id,
+                editor_id,
+                previous.title,
+                previous.content
+            )
+            .execute(&mut *tx)
             .await
             .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+        }
 
-        if res.rows_affected() == 0 {
-            return Err(PostRepositoryError::NotFound);
+        // При смене заголовка прежний slug продолжает вести на пост
+        let mut slug = previous.slug.clone();
+        if slugify(&post.title) != slugify(&previous.title) {
+            slug = Self::unique_slug(&mut tx, &post.title, Some(post_id)).await?;
         }
-        Ok(())
+        if slug != previous.slug {
+            sqlx::query!(
+                r#"
+                INSERT INTO post_slug_redirects (slug, post_id)
+                VALUES ($1, $2)
+                ON CONFLICT (slug) DO UPDATE SET post_id = EXCLUDED.post_id
+                "#,
+                previous.slug,
+                post_id
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+            sqlx::query!("DELETE FROM post_slug_redirects WHERE slug = $1", slug)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+        }
+
+        let updated = sqlx::query_scalar!(
+            r#"
+            UPDATE posts 
+            SET 
+                title = COALESCE($1, title),
+                content = COALESCE($2, content),
+                status = COALESCE($4::text::post_status, status),
+                published_at = CASE
+                    WHEN $4::text = 'published' THEN COALESCE(published_at, NOW())
+                    ELSE published_at
+                END,
+                publish_at = CASE
+                    WHEN $5::timestamptz IS NOT NULL THEN $5
+                    WHEN $4::text IS NOT NULL THEN NULL
+                    ELSE publish_at
+                END,
+                slug = $6,
+                content_format = COALESCE($7::text::content_format, content_format),
+                content_html = $8,
+                updated_at = NOW()
+            WHERE id = $3 
+            RETURNING id
+            "#,
+            post.title,
+            post.content,
+            post_id,
+            post.status.map(|s| s.as_str()),
+            post.publish_at,
+            slug,
+            post.content_format.map(|f| f.as_str()),
+            content_html
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(map_slug_conflict)?;
+
+        if updated.is_none() {
+            return Err(PostRepositoryError::NotFound);
+        }
+
+        if let Some(tags) = &post.tags {
+            Self::set_post_tags(&mut tx, post_id, tags).await?;
+        }
+        let new_post = Self::fetch_post(&mut tx, post_id)
+            .await?
+            .ok_or(PostRepositoryError::NotFound)?;
+
+        tx.commit()
+            .await
+            .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+        Ok(new_post)
+    }
+
+    async fn delete_post(&self, post_id: i64) -> Result<(), PostRepositoryError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        let res = sqlx::query!("DELETE FROM posts WHERE id = $1", post_id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        if res.rows_affected() == 0 {
+            return Err(PostRepositoryError::NotFound);
+        }
+        Ok(())
+    }
+
+    async fn set_status(
+        &self,
+        post_id: i64,
+        status: PostStatus,
+    ) -> Result<Post, PostRepositoryError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        let updated = sqlx::query_scalar!(
+            r#"
+            UPDATE posts 
+            SET 
+                status = $2::text::post_status,
+                published_at = CASE
+                    WHEN $2::text = 'published' THEN COALESCE(published_at, NOW())
+                    ELSE published_at
+                END,
+                publish_at = NULL
+            WHERE id = $1 
+            RETURNING id
+            "#,
+            post_id,
+            status.as_str()
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        if updated.is_none() {
+            return Err(PostRepositoryError::NotFound);
+        }
+
+        Self::fetch_post(&mut conn, post_id)
+            .await?
+            .ok_or(PostRepositoryError::NotFound)
+    }
+
+    async fn get_tags(&self) -> Result<Vec<TagCount>, PostRepositoryError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        let tags = sqlx::query_as!(
+            TagCount,
+            r#"
+            SELECT t.name, count(pt.post_id) as "post_count!"
+            FROM tags t
+            JOIN post_tags pt ON pt.tag_id = t.id
+            JOIN posts p ON p.id = pt.post_id
+            WHERE p.status = 'published'
+            GROUP BY t.name
+            ORDER BY count(pt.post_id) DESC, t.name
+            "#
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(tags)
+    }
+
+    async fn publish_due_posts(&self, limit: i64) -> Result<Vec<i64>, PostRepositoryError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        let ids = sqlx::query_scalar!(
+            r#"
+            UPDATE posts
+            SET
+                status = 'published',
+                published_at = COALESCE(published_at, publish_at),
+                publish_at = NULL
+            WHERE id IN (
+                SELECT id FROM posts
+                WHERE status = 'draft' AND publish_at <= NOW()
+                ORDER BY publish_at
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id
+            "#,
+            limit
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(ids)
+    }
+
+    async fn get_revisions(&self, post_id: i64) -> Result<Vec<PostRevision>, PostRepositoryError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        let revisions = sqlx::query_as!(
+            PostRevision,
+            r#"
+            SELECT id, post_id, revision, editor_id, title, content, created_at
+            FROM post_revisions
+            WHERE post_id = $1
+            ORDER BY revision
+            "#,
+            post_id
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(revisions)
+    }
+
+    async fn get_revision(
+        &self,
+        post_id: i64,
+        revision: i32,
+    ) -> Result<PostRevision, PostRepositoryError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        let revision = sqlx::query_as!(
+            PostRevision,
+            r#"
+            SELECT id, post_id, revision, editor_id, title, content, created_at
+            FROM post_revisions
+            WHERE post_id = $1 AND revision = $2
+            "#,
+            post_id,
+            revision
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        revision.ok_or(PostRepositoryError::NotFound)
+    }
+
+    async fn search_posts(
+        &self,
+        query: &str,
+        len: i64,
+        offset: i64,
+    ) -> Result<SearchResults, PostRepositoryError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        let headline_options = format!(
+            "StartSel={}, StopSel={}, MaxFragments=2, MaxWords=30, MinWords=10",
+            HIGHLIGHT_START, HIGHLIGHT_STOP
+        );
+        let rows = sqlx::query!(
+            r#"
+            WITH q AS (
+                SELECT websearch_to_tsquery('russian', $1)
+                    || websearch_to_tsquery('english', $1) AS query
+            )
+            SELECT
+                p.id,
+                ts_rank_cd(p.search_vector, q.query) as "rank!",
+                ts_headline('russian', coalesce(p.content, ''), q.query, $4) as "snippet!",
+                count(*) OVER () as "total!"
+            FROM posts p, q
+            WHERE p.status = 'published' AND p.search_vector @@ q.query
+            ORDER BY 2 DESC, p.created_at DESC, p.id DESC
+            LIMIT $2 OFFSET $3
+            "#,
+            query,
+            len,
+            offset,
+            headline_options
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        let total = rows.first().map(|r| r.total).unwrap_or(0);
+        let ids = rows.iter().map(|r| r.id).collect::<Vec<_>>();
+        let mut posts = Self::fetch_posts(&mut conn, &ids).await?;
+
+        let hits = rows
+            .into_iter()
+            .filter_map(|row| {
+                let index = posts.iter().position(|p| p.id == row.id)?;
+                Some(SearchHit {
+                    post: posts.swap_remove(index),
+                    rank: row.rank,
+                    snippet: highlight_snippet(&row.snippet),
+                })
+            })
+            .collect();
+
+        Ok(SearchResults { hits, total })
+    }
+
+    async fn add_reaction(
+        &self,
+        post_id: i64,
+        user_id: Uuid,
+        kind: ReactionKind,
+    ) -> Result<(), PostRepositoryError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO post_reactions (post_id, kind, user_id)
+            VALUES ($1, $2, $3)
+            ON CONFLICT DO NOTHING
+            "#,
+            post_id,
+            kind as ReactionKind,
+            user_id
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn remove_reaction(
+        &self,
+        post_id: i64,
+        user_id: Uuid,
+        kind: ReactionKind,
+    ) -> Result<(), PostRepositoryError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        sqlx::query!(
+            "DELETE FROM post_reactions WHERE post_id = $1 AND kind = $2 AND user_id = $3",
+            post_id,
+            kind as ReactionKind,
+            user_id
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn get_reactions(
+        &self,
+        post_id: i64,
+        user_id: Uuid,
+    ) -> Result<PostReactions, PostRepositoryError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        let rows = sqlx::query!(
+            r#"
+            SELECT kind as "kind!: ReactionKind", count(*) as "count!",
+            bool_or(user_id = $2) as "reacted!"
+            FROM post_reactions WHERE post_id = $1
+            GROUP BY kind
+            ORDER BY kind
+            "#,
+            post_id,
+            user_id
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(PostReactions {
+            post_id,
+            counts: rows.iter().map(|row| (row.kind, row.count)).collect(),
+            reacted: rows
+                .iter()
+                .filter(|row| row.reacted)
+                .map(|row| row.kind)
+                .collect(),
+        })
+    }
+
+    async fn add_bookmark(&self, user_id: Uuid, post_id: i64) -> Result<(), PostRepositoryError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO bookmarks (user_id, post_id) VALUES ($1, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+            user_id,
+            post_id
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn remove_bookmark(
+        &self,
+        user_id: Uuid,
+        post_id: i64,
+    ) -> Result<(), PostRepositoryError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        sqlx::query!(
+            "DELETE FROM bookmarks WHERE user_id = $1 AND post_id = $2",
+            user_id,
+            post_id
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn get_bookmarked_posts(
+        &self,
+        user_id: Uuid,
+        len: i64,
+        offset: i64,
+    ) -> Result<Vec<Post>, PostRepositoryError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        let posts = sqlx::query_as!(
+            Post,
+            r#"
+            SELECT
+            id as "id!",
+            slug as "slug!",
+            title as "title!",
+            content as "content!: String",
+            content_format as "content_format!: ContentFormat",
+            content_html as "content_html!",
+            author_id as "author_id!: Uuid",
+            author as "author!: Json<AuthorSummary>",
+            reactions as "reactions!: Json<ReactionCounts>",
+            true as "bookmarked?",
+            created_at as "created_at!: chrono::DateTime<chrono::Utc>",
+            updated_at as "updated_at?: chrono::DateTime<chrono::Utc>",
+            tags as "tags!: Vec<String>",
+            status as "status!: PostStatus",
+            published_at as "published_at?: chrono::DateTime<chrono::Utc>",
+            publish_at as "publish_at?: chrono::DateTime<chrono::Utc>"
+            FROM posts_view
+            WHERE status = 'published' AND id IN (
+                SELECT post_id FROM bookmarks WHERE user_id = $3
+            )
+            ORDER BY (
+                SELECT b.created_at FROM bookmarks b
+                WHERE b.user_id = $3 AND b.post_id = posts_view.id
+            ) DESC, id DESC
+            LIMIT $1
+            OFFSET $2
+            "#,
+            len,
+            offset,
+            user_id
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(posts)
+    }
+
+    async fn count_bookmarks(&self, user_id: Uuid) -> Result<i64, PostRepositoryError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        let total = sqlx::query_scalar!(
+            r#"
+            SELECT count(*) as "total!" FROM bookmarks b
+            JOIN posts p ON p.id = b.post_id
+            WHERE b.user_id = $1 AND p.status = 'published'
+            "#,
+            user_id
+        )
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(total)
+    }
+
+    async fn get_bookmarked_ids(
+        &self,
+        user_id: Uuid,
+        post_ids: &[i64],
+    ) -> Result<Vec<i64>, PostRepositoryError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        let ids = sqlx::query_scalar!(
+            "SELECT post_id FROM bookmarks WHERE user_id = $1 AND post_id = ANY($2)",
+            user_id,
+            post_ids
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| PostRepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        data::user_repository::{PostgresUserRepository, UserRepository},
+        domain::auth::User,
+    };
+
+    async fn create_user(pool: &sqlx::PgPool, username: &str) -> Uuid {
+        PostgresUserRepository::new(pool.clone())
+            .create_user(User::new(
+                username.to_string(),
+                format!("{}@example.com", username),
+                "hash".to_string(),
+            ))
+            .await
+            .expect("Failed to create user")
+            .id
+    }
+
+    async fn create_post(repo: &PostgresPostRepository, author_id: Uuid, title: &str) -> i64 {
+        let post = CreatePost {
+            title: title.to_string(),
+            content: "text".to_string(),
+            content_format: ContentFormat::default(),
+            tags: Vec::new(),
+            draft: false,
+            publish_at: None,
+        };
+        repo.create_post(post, author_id, "<p>text</p>")
+            .await
+            .expect("Failed to create post")
+            .id
+    }
+
+    #[sqlx::test]
+    async fn test_liked_order_counts_only_likes(pool: sqlx::PgPool) {
+        let repo = PostgresPostRepository::new(pool.clone());
+        let author = create_user(&pool, "author").await;
+        let mut readers = Vec::new();
+        for name in ["reader1", "reader2", "reader3"] {
+            readers.push(create_user(&pool, name).await);
+        }
+
+        let loved = create_post(&repo, author, "Loved").await;
+        let liked_once = create_post(&repo, author, "Liked once").await;
+        let liked_twice = create_post(&repo, author, "Liked twice").await;
+        create_post(&repo, author, "Newest").await;
+
+        for reader in &readers {
+            repo.add_reaction(loved, *reader, ReactionKind::Love)
+                .await
+                .expect("Failed to react");
+        }
+        for reader in &readers[..2] {
+            repo.add_reaction(liked_twice, *reader, ReactionKind::Like)
+                .await
+                .expect("Failed to react");
+        }
+        repo.add_reaction(liked_once, readers[0], ReactionKind::Like)
+            .await
+            .expect("Failed to react");
+
+        let posts = repo
+            .get_last_posts(10, PageRequest::Offset(0), &[], PostOrder::Liked)
+            .await
+            .expect("Failed to list posts");
+        let titles: Vec<_> = posts.iter().map(|p| p.title.as_str()).collect();
+
+        assert_eq!(titles, ["Liked twice", "Liked once", "Newest", "Loved"]);
+    }
+
+    #[sqlx::test]
+    async fn test_concurrent_posts_get_distinct_slugs(pool: sqlx::PgPool) {
+        let repo = PostgresPostRepository::new(pool.clone());
+        let author = create_user(&pool, "author").await;
+
+        let (first, second) = tokio::join!(
+            create_post(&repo, author, "Same title"),
+            create_post(&repo, author, "Same title"),
+        );
+
+        let first = repo.get_post(first).await.expect("Failed to get post");
+        let second = repo.get_post(second).await.expect("Failed to get post");
+        let mut slugs = [first.slug, second.slug];
+        slugs.sort();
+        assert_eq!(slugs, ["same-title", "same-title-2"]);
     }
 }
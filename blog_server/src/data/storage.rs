@@ -0,0 +1,93 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+
+/// Хранилище содержимого файлов. Ключ - sha256 содержимого в hex
+#[async_trait]
+pub trait BlobStorage: Send + Sync {
+    /// Повторное сохранение того же ключа ничего не делает
+    async fn put(&self, key: &str, data: &[u8]) -> Result<(), StorageError>;
+    async fn get(&self, key: &str) -> Result<Vec<u8>, StorageError>;
+    /// Удаление отсутствующего ключа не считается ошибкой
+    async fn delete(&self, key: &str) -> Result<(), StorageError>;
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum StorageError {
+    #[error("Storage error: {0}")]
+    Io(String),
+
+    #[error("Invalid storage key")]
+    InvalidKey,
+
+    #[error("Blob not found")]
+    NotFound,
+}
+
+/// Файлы на локальном диске: `<root>/ab/abcdef...`
+#[derive(Clone)]
+pub struct LocalFileStorage {
+    root: PathBuf,
+}
+
+impl LocalFileStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path(&self, key: &str) -> Result<PathBuf, StorageError> {
+        if key.len() < 3 || !key.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(StorageError::InvalidKey);
+        }
+        Ok(self.root.join(&key[..2]).join(key))
+    }
+}
+
+#[async_trait]
+impl BlobStorage for LocalFileStorage {
+    async fn put(&self, key: &str, data: &[u8]) -> Result<(), StorageError> {
+        let path = self.path(key)?;
+        if tokio::fs::try_exists(&path)
+            .await
+            .map_err(|e| StorageError::Io(e.to_string()))?
+        {
+            return Ok(());
+        }
+
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir)
+                .await
+                .map_err(|e| StorageError::Io(e.to_string()))?;
+        }
+
+        // Пишем во временный файл и переименовываем, чтобы не отдать недописанный
+        let tmp = path.with_extension(format!("tmp-{}", uuid::Uuid::new_v4()));
+        tokio::fs::write(&tmp, data)
+            .await
+            .map_err(|e| StorageError::Io(e.to_string()))?;
+        if let Err(e) = tokio::fs::rename(&tmp, &path).await {
+            let _ = tokio::fs::remove_file(&tmp).await;
+            return Err(StorageError::Io(e.to_string()));
+        }
+
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, StorageError> {
+        let path = self.path(key)?;
+        match tokio::fs::read(&path).await {
+            Ok(data) => Ok(data),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Err(StorageError::NotFound),
+            Err(e) => Err(StorageError::Io(e.to_string())),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        let path = self.path(key)?;
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(StorageError::Io(e.to_string())),
+        }
+    }
+}
@@ -1,15 +1,149 @@
 use async_trait::async_trait;
 use uuid::Uuid;
 
-use crate::domain::{auth::User, error::DomainError};
+use crate::domain::{
+    account::{DELETED_USER_ID, PostsOnDelete},
+    auth::{OneTimeToken, RefreshToken, User},
+    error::DomainError,
+    follow::FollowUser,
+    personal_token::{PersonalAccessToken, TokenScope},
+    profile::{UpdateProfile, UserProfile},
+    role::Role,
+};
 
 #[async_trait]
 pub trait UserRepository: Send + Sync {
     async fn create_user(&self, user: User) -> Result<User, DomainError>;
     async fn get_user_by_id(&self, id: Uuid) -> Result<User, DomainError>;
     async fn get_user_by_username(&self, username: &str) -> Result<User, DomainError>;
-    #[allow(dead_code)]
     async fn get_user_by_email(&self, email: &str) -> Result<User, DomainError>;
+
+    async fn create_refresh_token(&self, token: &RefreshToken) -> Result<(), DomainError>;
+    async fn get_refresh_token(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<RefreshToken>, DomainError>;
+    /// Помечает токен использованным. `false`, если его уже использовали
+    /// (например, параллельный запрос успел раньше)
+    async fn mark_refresh_token_used(&self, id: Uuid) -> Result<bool, DomainError>;
+    /// Отзывает все токены семейства
+    async fn revoke_token_family(&self, family_id: Uuid) -> Result<(), DomainError>;
+    /// Отзывает все refresh токены пользователя
+    async fn revoke_user_refresh_tokens(&self, user_id: Uuid) -> Result<(), DomainError>;
+
+    async fn revoke_access_token(
+        &self,
+        jti: Uuid,
+        user_id: Uuid,
+        expires_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), DomainError>;
+    async fn is_access_token_revoked(&self, jti: Uuid) -> Result<bool, DomainError>;
+    /// Делает недействительными все access токены, выданные раньше `at`
+    async fn set_tokens_valid_after(
+        &self,
+        user_id: Uuid,
+        at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), DomainError>;
+
+    async fn update_password(&self, user_id: Uuid, password_hash: &str) -> Result<(), DomainError>;
+    async fn create_password_reset(&self, token: &OneTimeToken) -> Result<(), DomainError>;
+    /// Действующий токен сброса без отметки об использовании
+    async fn get_password_reset(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<OneTimeToken>, DomainError>;
+    /// Атомарно помечает действующий токен сброса использованным и возвращает его
+    async fn take_password_reset(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<OneTimeToken>, DomainError>;
+
+    async fn create_email_verification(&self, token: &OneTimeToken) -> Result<(), DomainError>;
+    /// Атомарно помечает действующий токен подтверждения использованным и возвращает его
+    async fn take_email_verification(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<OneTimeToken>, DomainError>;
+    async fn mark_email_verified(&self, user_id: Uuid) -> Result<(), DomainError>;
+
+    async fn list_users(&self, limit: i64, offset: i64) -> Result<Vec<User>, DomainError>;
+    async fn set_user_role(&self, user_id: Uuid, role: Role) -> Result<User, DomainError>;
+    async fn has_admin(&self) -> Result<bool, DomainError>;
+
+    async fn create_personal_token(&self, token: &PersonalAccessToken) -> Result<(), DomainError>;
+    /// Действующие (не отозванные и не истёкшие) токены пользователя
+    async fn list_personal_tokens(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<PersonalAccessToken>, DomainError>;
+    /// `false`, если у пользователя нет такого действующего токена
+    async fn revoke_personal_token(&self, user_id: Uuid, id: Uuid) -> Result<bool, DomainError>;
+    async fn revoke_user_personal_tokens(&self, user_id: Uuid) -> Result<(), DomainError>;
+    /// Находит действующий токен и отмечает время его использования
+    async fn use_personal_token(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<PersonalAccessToken>, DomainError>;
+
+    /// Новый секрет TOTP (ещё не подтверждённый) или `None`, чтобы отключить 2FA
+    async fn set_totp_secret(&self, user_id: Uuid, secret: Option<&str>)
+    -> Result<(), DomainError>;
+    async fn enable_totp(&self, user_id: Uuid) -> Result<(), DomainError>;
+    /// Запоминает использованный шаг TOTP. `false`, если код этого шага уже принимали
+    async fn record_totp_step(&self, user_id: Uuid, step: i64) -> Result<bool, DomainError>;
+    /// Заменяет все коды восстановления пользователя
+    async fn replace_recovery_codes(
+        &self,
+        user_id: Uuid,
+        code_hashes: &[String],
+    ) -> Result<(), DomainError>;
+    /// Помечает код использованным. `false`, если такого неиспользованного кода нет
+    async fn use_recovery_code(&self, user_id: Uuid, code_hash: &str) -> Result<bool, DomainError>;
+
+    async fn create_two_factor_challenge(&self, token: &OneTimeToken) -> Result<(), DomainError>;
+    /// Действующий запрос второго фактора, у которого не исчерпаны попытки
+    async fn get_two_factor_challenge(
+        &self,
+        token_hash: &str,
+        max_attempts: i32,
+    ) -> Result<Option<OneTimeToken>, DomainError>;
+    async fn fail_two_factor_challenge(&self, id: Uuid) -> Result<(), DomainError>;
+    /// Атомарно помечает запрос использованным. `false`, если его уже использовали
+    async fn take_two_factor_challenge(&self, id: Uuid) -> Result<bool, DomainError>;
+
+    /// Имя сравнивается без учёта регистра, как и при входе
+    async fn get_profile(&self, username: &str) -> Result<UserProfile, DomainError>;
+    async fn update_profile(
+        &self,
+        user_id: Uuid,
+        profile: &UpdateProfile,
+    ) -> Result<UserProfile, DomainError>;
+
+    /// Повторная подписка ничего не меняет
+    async fn follow(&self, follower_id: Uuid, followee_id: Uuid) -> Result<(), DomainError>;
+    async fn unfollow(&self, follower_id: Uuid, followee_id: Uuid) -> Result<(), DomainError>;
+    async fn is_following(&self, follower_id: Uuid, followee_id: Uuid)
+    -> Result<bool, DomainError>;
+    /// Подписчики пользователя, от новых подписок к старым
+    async fn list_followers(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<FollowUser>, DomainError>;
+    async fn count_followers(&self, user_id: Uuid) -> Result<i64, DomainError>;
+    /// Авторы, на которых подписан пользователь, от новых подписок к старым
+    async fn list_following(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<FollowUser>, DomainError>;
+    async fn count_following(&self, user_id: Uuid) -> Result<i64, DomainError>;
+
+    /// Удаляет пользователя в одной транзакции. Токены и прочие данные аккаунта
+    /// удаляются каскадом, посты и комментарии - по выбору пользователя
+    async fn delete_user(&self, user_id: Uuid, posts: PostsOnDelete) -> Result<(), DomainError>;
 }
 
 #[derive(Clone)]
@@ -32,33 +166,14 @@ impl UserRepository for PostgresUserRepository {
             .await
             .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
 
-        // Проверяем, существует ли пользователь с таким же именем или email
-        let existing_user = sqlx::query_as!(
-            User,
-            "SELECT * FROM users WHERE username = $1 OR email = $2",
-            new_user.username,
-            new_user.email,
-        )
-        .fetch_optional(&mut *conn)
-        .await
-        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
-
-        // Если пользователь существует, возвращаем ошибку
-        if let Some(ex_user) = existing_user {
-            if ex_user.username == new_user.username {
-                return Err(DomainError::Validation("Username exists".to_string()));
-            }
-            if ex_user.email == new_user.email {
-                return Err(DomainError::Validation("email exists".to_string()));
-            }
-        }
-
         let user = sqlx::query_as!(
             User,
             r#"
             INSERT INTO users (id, username, email, password_hash) 
             VALUES ($1, $2, $3, $4) 
-            RETURNING id, username, email, password_hash, created_at
+            RETURNING id, username, email, password_hash, created_at, tokens_valid_after,
+                      email_verified_at, role as "role: Role", totp_secret, totp_enabled_at,
+                      totp_last_step
             "#,
             new_user.id,
             new_user.username,
@@ -67,7 +182,7 @@ impl UserRepository for PostgresUserRepository {
         )
         .fetch_one(&mut *conn)
         .await
-        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+        .map_err(map_user_conflict)?;
 
         Ok(user)
     }
@@ -78,10 +193,19 @@ impl UserRepository for PostgresUserRepository {
             .acquire()
             .await
             .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
-        let user = sqlx::query_as!(User, "SELECT * FROM users WHERE id = $1 ", id)
-            .fetch_optional(&mut *conn)
-            .await
-            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+        let user = sqlx::query_as!(
+            User,
+            r#"
+            SELECT id, username, email, password_hash, created_at, tokens_valid_after,
+                   email_verified_at, role as "role: Role", totp_secret, totp_enabled_at,
+                   totp_last_step
+            FROM users WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
 
         if let Some(user) = user {
             return Ok(user);
@@ -94,10 +218,19 @@ impl UserRepository for PostgresUserRepository {
             .acquire()
             .await
             .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
-        let user = sqlx::query_as!(User, "SELECT * FROM users WHERE username = $1 ", username)
-            .fetch_optional(&mut *conn)
-            .await
-            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+        let user = sqlx::query_as!(
+            User,
+            r#"
+            SELECT id, username, email, password_hash, created_at, tokens_valid_after,
+                   email_verified_at, role as "role: Role", totp_secret, totp_enabled_at,
+                   totp_last_step
+            FROM users WHERE lower(username) = lower($1)
+            "#,
+            username
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
 
         if let Some(user) = user {
             return Ok(user);
@@ -111,14 +244,1055 @@ impl UserRepository for PostgresUserRepository {
             .acquire()
             .await
             .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
-        let user = sqlx::query_as!(User, "SELECT * FROM users WHERE email = $1 ", email)
-            .fetch_optional(&mut *conn)
-            .await
-            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+        let user = sqlx::query_as!(
+            User,
+            r#"
+            SELECT id, username, email, password_hash, created_at, tokens_valid_after,
+                   email_verified_at, role as "role: Role", totp_secret, totp_enabled_at,
+                   totp_last_step
+            FROM users WHERE email = $1
+            "#,
+            email
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
 
         if let Some(user) = user {
             return Ok(user);
         }
         Err(DomainError::UserNotFound)
     }
+
+    async fn create_refresh_token(&self, token: &RefreshToken) -> Result<(), DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO refresh_tokens (id, user_id, family_id, token_hash, expires_at, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+            token.id,
+            token.user_id,
+            token.family_id,
+            token.token_hash,
+            token.expires_at,
+            token.created_at
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn get_refresh_token(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<RefreshToken>, DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        sqlx::query_as!(
+            RefreshToken,
+            r#"
+            SELECT id, user_id, family_id, token_hash, expires_at, created_at, used_at, revoked_at
+            FROM refresh_tokens WHERE token_hash = $1
+            "#,
+            token_hash
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))
+    }
+
+    async fn mark_refresh_token_used(&self, id: Uuid) -> Result<bool, DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        let result = sqlx::query!(
+            "UPDATE refresh_tokens SET used_at = NOW() WHERE id = $1 AND used_at IS NULL",
+            id
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn revoke_token_family(&self, family_id: Uuid) -> Result<(), DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        sqlx::query!(
+            r#"
+            UPDATE refresh_tokens SET revoked_at = NOW()
+            WHERE family_id = $1 AND revoked_at IS NULL
+            "#,
+            family_id
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn revoke_user_refresh_tokens(&self, user_id: Uuid) -> Result<(), DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        sqlx::query!(
+            r#"
+            UPDATE refresh_tokens SET revoked_at = NOW()
+            WHERE user_id = $1 AND revoked_at IS NULL
+            "#,
+            user_id
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn revoke_access_token(
+        &self,
+        jti: Uuid,
+        user_id: Uuid,
+        expires_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        // Заодно убираем записи об уже истёкших токенах
+        sqlx::query!("DELETE FROM revoked_tokens WHERE expires_at < NOW()")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO revoked_tokens (jti, user_id, expires_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (jti) DO NOTHING
+            "#,
+            jti,
+            user_id,
+            expires_at
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn is_access_token_revoked(&self, jti: Uuid) -> Result<bool, DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        let revoked = sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM revoked_tokens WHERE jti = $1) AS "revoked!""#,
+            jti
+        )
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        Ok(revoked)
+    }
+
+    async fn set_tokens_valid_after(
+        &self,
+        user_id: Uuid,
+        at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        sqlx::query!(
+            "UPDATE users SET tokens_valid_after = $2 WHERE id = $1",
+            user_id,
+            at
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn update_password(&self, user_id: Uuid, password_hash: &str) -> Result<(), DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        let result = sqlx::query!(
+            "UPDATE users SET password_hash = $2 WHERE id = $1",
+            user_id,
+            password_hash
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(DomainError::UserNotFound);
+        }
+        Ok(())
+    }
+
+    async fn create_password_reset(&self, token: &OneTimeToken) -> Result<(), DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO password_reset_tokens (id, user_id, token_hash, expires_at, created_at)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+            token.id,
+            token.user_id,
+            token.token_hash,
+            token.expires_at,
+            token.created_at
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn get_password_reset(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<OneTimeToken>, DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        sqlx::query_as!(
+            OneTimeToken,
+            r#"
+            SELECT id, user_id, token_hash, expires_at, created_at
+            FROM password_reset_tokens
+            WHERE token_hash = $1 AND used_at IS NULL AND expires_at > NOW()
+            "#,
+            token_hash
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))
+    }
+
+    async fn take_password_reset(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<OneTimeToken>, DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        sqlx::query_as!(
+            OneTimeToken,
+            r#"
+            UPDATE password_reset_tokens SET used_at = NOW()
+            WHERE token_hash = $1 AND used_at IS NULL AND expires_at > NOW()
+            RETURNING id, user_id, token_hash, expires_at, created_at
+            "#,
+            token_hash
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))
+    }
+
+    async fn create_email_verification(&self, token: &OneTimeToken) -> Result<(), DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO email_verification_tokens (id, user_id, token_hash, expires_at, created_at)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+            token.id,
+            token.user_id,
+            token.token_hash,
+            token.expires_at,
+            token.created_at
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn take_email_verification(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<OneTimeToken>, DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        sqlx::query_as!(
+            OneTimeToken,
+            r#"
+            UPDATE email_verification_tokens SET used_at = NOW()
+            WHERE token_hash = $1 AND used_at IS NULL AND expires_at > NOW()
+            RETURNING id, user_id, token_hash, expires_at, created_at
+            "#,
+            token_hash
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))
+    }
+
+    async fn mark_email_verified(&self, user_id: Uuid) -> Result<(), DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        sqlx::query!(
+            r#"
+            UPDATE users SET email_verified_at = COALESCE(email_verified_at, NOW())
+            WHERE id = $1
+            "#,
+            user_id
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn list_users(&self, limit: i64, offset: i64) -> Result<Vec<User>, DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        sqlx::query_as!(
+            User,
+            r#"
+            SELECT id, username, email, password_hash, created_at, tokens_valid_after,
+                   email_verified_at, role as "role: Role", totp_secret, totp_enabled_at,
+                   totp_last_step
+            FROM users ORDER BY created_at, id
+            LIMIT $1 OFFSET $2
+            "#,
+            limit,
+            offset
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))
+    }
+
+    async fn set_user_role(&self, user_id: Uuid, role: Role) -> Result<User, DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        sqlx::query_as!(
+            User,
+            r#"
+            UPDATE users SET role = $2 WHERE id = $1
+            RETURNING id, username, email, password_hash, created_at, tokens_valid_after,
+                      email_verified_at, role as "role: Role", totp_secret, totp_enabled_at,
+                      totp_last_step
+            "#,
+            user_id,
+            role as Role
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?
+        .ok_or(DomainError::UserNotFound)
+    }
+
+    async fn has_admin(&self) -> Result<bool, DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM users WHERE role = 'admin') AS "exists!""#
+        )
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))
+    }
+
+    async fn create_personal_token(&self, token: &PersonalAccessToken) -> Result<(), DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO personal_access_tokens
+                (id, user_id, name, token_hash, scopes, created_at, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+            token.id,
+            token.user_id,
+            token.name,
+            token.token_hash,
+            &token.scopes as &[TokenScope],
+            token.created_at,
+            token.expires_at
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn list_personal_tokens(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<PersonalAccessToken>, DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        sqlx::query_as!(
+            PersonalAccessToken,
+            r#"
+            SELECT id, user_id, name, token_hash, scopes as "scopes: Vec<TokenScope>",
+                   created_at, expires_at, last_used_at
+            FROM personal_access_tokens
+            WHERE user_id = $1 AND revoked_at IS NULL
+              AND (expires_at IS NULL OR expires_at > NOW())
+            ORDER BY created_at DESC
+            "#,
+            user_id
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))
+    }
+
+    async fn revoke_personal_token(&self, user_id: Uuid, id: Uuid) -> Result<bool, DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE personal_access_tokens SET revoked_at = NOW()
+            WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL
+            "#,
+            id,
+            user_id
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn revoke_user_personal_tokens(&self, user_id: Uuid) -> Result<(), DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        sqlx::query!(
+            r#"
+            UPDATE personal_access_tokens SET revoked_at = NOW()
+            WHERE user_id = $1 AND revoked_at IS NULL
+            "#,
+            user_id
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn use_personal_token(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<PersonalAccessToken>, DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        sqlx::query_as!(
+            PersonalAccessToken,
+            r#"
+            UPDATE personal_access_tokens SET last_used_at = NOW()
+            WHERE token_hash = $1 AND revoked_at IS NULL
+              AND (expires_at IS NULL OR expires_at > NOW())
+            RETURNING id, user_id, name, token_hash, scopes as "scopes: Vec<TokenScope>",
+                      created_at, expires_at, last_used_at
+            "#,
+            token_hash
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))
+    }
+
+    async fn set_totp_secret(
+        &self,
+        user_id: Uuid,
+        secret: Option<&str>,
+    ) -> Result<(), DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        sqlx::query!(
+            r#"
+            UPDATE users SET totp_secret = $2, totp_enabled_at = NULL, totp_last_step = NULL
+            WHERE id = $1
+            "#,
+            user_id,
+            secret
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn enable_totp(&self, user_id: Uuid) -> Result<(), DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        sqlx::query!(
+            r#"
+            UPDATE users SET totp_enabled_at = NOW()
+            WHERE id = $1 AND totp_secret IS NOT NULL
+            "#,
+            user_id
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn record_totp_step(&self, user_id: Uuid, step: i64) -> Result<bool, DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE users SET totp_last_step = $2
+            WHERE id = $1 AND (totp_last_step IS NULL OR totp_last_step < $2)
+            "#,
+            user_id,
+            step
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn replace_recovery_codes(
+        &self,
+        user_id: Uuid,
+        code_hashes: &[String],
+    ) -> Result<(), DomainError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        sqlx::query!("DELETE FROM recovery_codes WHERE user_id = $1", user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        for code_hash in code_hashes {
+            sqlx::query!(
+                "INSERT INTO recovery_codes (id, user_id, code_hash) VALUES ($1, $2, $3)",
+                Uuid::new_v4(),
+                user_id,
+                code_hash
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn use_recovery_code(&self, user_id: Uuid, code_hash: &str) -> Result<bool, DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE recovery_codes SET used_at = NOW()
+            WHERE user_id = $1 AND code_hash = $2 AND used_at IS NULL
+            "#,
+            user_id,
+            code_hash
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn create_two_factor_challenge(&self, token: &OneTimeToken) -> Result<(), DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO two_factor_challenges (id, user_id, token_hash, expires_at, created_at)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+            token.id,
+            token.user_id,
+            token.token_hash,
+            token.expires_at,
+            token.created_at
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn get_two_factor_challenge(
+        &self,
+        token_hash: &str,
+        max_attempts: i32,
+    ) -> Result<Option<OneTimeToken>, DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        sqlx::query_as!(
+            OneTimeToken,
+            r#"
+            SELECT id, user_id, token_hash, expires_at, created_at
+            FROM two_factor_challenges
+            WHERE token_hash = $1 AND used_at IS NULL AND expires_at > NOW() AND attempts < $2
+            "#,
+            token_hash,
+            max_attempts
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))
+    }
+
+    async fn fail_two_factor_challenge(&self, id: Uuid) -> Result<(), DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        sqlx::query!(
+            "UPDATE two_factor_challenges SET attempts = attempts + 1 WHERE id = $1",
+            id
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn take_two_factor_challenge(&self, id: Uuid) -> Result<bool, DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        let result = sqlx::query!(
+            "UPDATE two_factor_challenges SET used_at = NOW() WHERE id = $1 AND used_at IS NULL",
+            id
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn get_profile(&self, username: &str) -> Result<UserProfile, DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        sqlx::query_as!(
+            UserProfile,
+            r#"
+            SELECT id, username, display_name, bio, avatar_url, links, created_at
+            FROM users WHERE lower(username) = lower($1)
+            "#,
+            username
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?
+        .ok_or(DomainError::UserNotFound)
+    }
+
+    async fn update_profile(
+        &self,
+        user_id: Uuid,
+        profile: &UpdateProfile,
+    ) -> Result<UserProfile, DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        sqlx::query_as!(
+            UserProfile,
+            r#"
+            UPDATE users SET display_name = $2, bio = $3, avatar_url = $4, links = $5
+            WHERE id = $1
+            RETURNING id, username, display_name, bio, avatar_url, links, created_at
+            "#,
+            user_id,
+            profile.display_name,
+            profile.bio,
+            profile.avatar_url,
+            &profile.links
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?
+        .ok_or(DomainError::UserNotFound)
+    }
+
+    async fn follow(&self, follower_id: Uuid, followee_id: Uuid) -> Result<(), DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO follows (follower_id, followee_id) VALUES ($1, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+            follower_id,
+            followee_id
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn unfollow(&self, follower_id: Uuid, followee_id: Uuid) -> Result<(), DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        sqlx::query!(
+            "DELETE FROM follows WHERE follower_id = $1 AND followee_id = $2",
+            follower_id,
+            followee_id
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn is_following(
+        &self,
+        follower_id: Uuid,
+        followee_id: Uuid,
+    ) -> Result<bool, DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM follows WHERE follower_id = $1 AND followee_id = $2
+            ) as "exists!"
+            "#,
+            follower_id,
+            followee_id
+        )
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))
+    }
+
+    async fn list_followers(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<FollowUser>, DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        sqlx::query_as!(
+            FollowUser,
+            r#"
+            SELECT u.username, u.display_name, f.created_at as followed_at
+            FROM follows f JOIN users u ON u.id = f.follower_id
+            WHERE f.followee_id = $1
+            ORDER BY f.created_at DESC, u.username
+            LIMIT $2 OFFSET $3
+            "#,
+            user_id,
+            limit,
+            offset
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))
+    }
+
+    async fn count_followers(&self, user_id: Uuid) -> Result<i64, DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        sqlx::query_scalar!(
+            r#"SELECT count(*) as "total!" FROM follows WHERE followee_id = $1"#,
+            user_id
+        )
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))
+    }
+
+    async fn list_following(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<FollowUser>, DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        sqlx::query_as!(
+            FollowUser,
+            r#"
+            SELECT u.username, u.display_name, f.created_at as followed_at
+            FROM follows f JOIN users u ON u.id = f.followee_id
+            WHERE f.follower_id = $1
+            ORDER BY f.created_at DESC, u.username
+            LIMIT $2 OFFSET $3
+            "#,
+            user_id,
+            limit,
+            offset
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))
+    }
+
+    async fn count_following(&self, user_id: Uuid) -> Result<i64, DomainError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        sqlx::query_scalar!(
+            r#"SELECT count(*) as "total!" FROM follows WHERE follower_id = $1"#,
+            user_id
+        )
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| DomainError::Internal(format!("database error: {}", e)))
+    }
+
+    async fn delete_user(&self, user_id: Uuid, posts: PostsOnDelete) -> Result<(), DomainError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+        if posts == PostsOnDelete::Anonymize {
+            // Черновики никому, кроме автора, не видны и удаляются вместе с ним
+            sqlx::query!(
+                "UPDATE posts SET author_id = $2 WHERE author_id = $1 AND status <> 'draft'",
+                user_id,
+                DELETED_USER_ID
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+            sqlx::query!(
+                "UPDATE comments SET author_id = $2 WHERE author_id = $1",
+                user_id,
+                DELETED_USER_ID
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+
+            // Файлы, вставленные в оставшиеся посты, должны открываться и дальше
+            sqlx::query!(
+                r#"
+                UPDATE attachments SET owner_id = $2
+                WHERE owner_id = $1 AND post_id IN (SELECT id FROM posts WHERE author_id = $2)
+                "#,
+                user_id,
+                DELETED_USER_ID
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+        }
+
+        let result = sqlx::query!("DELETE FROM users WHERE id = $1", user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+        if result.rows_affected() == 0 {
+            return Err(DomainError::UserNotFound);
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| DomainError::Internal(format!("database error: {}", e)))?;
+        Ok(())
+    }
+}
+
+/// Уникальность имени и email проверяет база, поэтому параллельные регистрации не гонятся
+fn map_user_conflict(error: sqlx::Error) -> DomainError {
+    if let sqlx::Error::Database(db_error) = &error
+        && db_error.is_unique_violation()
+    {
+        match db_error.constraint() {
+            Some("users_username_key" | "users_username_lower_key") => {
+                return DomainError::Validation("Username exists".to_string());
+            }
+            Some("users_email_key") => {
+                return DomainError::Validation("email exists".to_string());
+            }
+            _ => {}
+        }
+    }
+    DomainError::Internal(format!("database error: {}", error))
 }
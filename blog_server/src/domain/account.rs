@@ -0,0 +1,98 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use serde_with::{TimestampSeconds, serde_as};
+use uuid::Uuid;
+
+use crate::domain::{
+    attachment::Attachment, comment::Comment, error::BlogError,
+    personal_token::PersonalAccessToken, post::Post, profile::UserProfile, role::Role,
+};
+
+/// Системный пользователь `[deleted]`, которому передаются обезличенные посты.
+/// Создаётся миграцией, войти под ним нельзя
+pub const DELETED_USER_ID: Uuid = Uuid::nil();
+
+/// Что делать с постами и комментариями при удалении аккаунта
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PostsOnDelete {
+    /// Удалить вместе с аккаунтом
+    Delete,
+    /// Оставить от имени `[deleted]`. Черновики удаляются в любом случае
+    Anonymize,
+}
+
+impl FromStr for PostsOnDelete {
+    type Err = BlogError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "delete" => Ok(PostsOnDelete::Delete),
+            "anonymize" => Ok(PostsOnDelete::Anonymize),
+            other => Err(BlogError::invalid_field(
+                "posts",
+                format!("must be 'delete' or 'anonymize', got '{}'", other),
+            )),
+        }
+    }
+}
+
+/// Удаление аккаунта подтверждается текущим паролем
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeleteAccount {
+    pub password: String,
+    pub posts: PostsOnDelete,
+}
+
+impl TryFrom<crate::blog_grpc::DeleteAccountRequest> for DeleteAccount {
+    type Error = BlogError;
+    fn try_from(value: crate::blog_grpc::DeleteAccountRequest) -> Result<Self, Self::Error> {
+        Ok(Self {
+            password: value.password,
+            posts: value.posts.parse()?,
+        })
+    }
+}
+
+/// Закрытые данные аккаунта. Хеш пароля и секрет 2FA не выгружаются
+#[serde_as]
+#[derive(Debug, Serialize)]
+pub struct AccountInfo {
+    pub id: Uuid,
+    pub username: String,
+    pub email: String,
+    pub role: Role,
+    #[serde_as(as = "TimestampSeconds<i64>")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    #[serde_as(as = "Option<TimestampSeconds<i64>>")]
+    pub email_verified_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub two_factor_enabled: bool,
+}
+
+/// Выгрузка всех данных пользователя. Содержимое вложений доступно
+/// по адресу `/api/attachments/{id}`
+#[serde_as]
+#[derive(Debug, Serialize)]
+pub struct AccountExport {
+    #[serde_as(as = "TimestampSeconds<i64>")]
+    pub exported_at: chrono::DateTime<chrono::Utc>,
+    pub account: AccountInfo,
+    pub profile: UserProfile,
+    /// Посты во всех статусах, включая черновики и архив
+    pub posts: Vec<Post>,
+    pub comments: Vec<Comment>,
+    pub attachments: Vec<Attachment>,
+    pub personal_tokens: Vec<PersonalAccessToken>,
+}
+
+impl AccountExport {
+    /// Имя файла для скачивания
+    pub fn file_name(&self) -> String {
+        format!(
+            "blog-export-{}-{}.json",
+            self.account.username,
+            self.exported_at.format("%Y%m%d")
+        )
+    }
+}
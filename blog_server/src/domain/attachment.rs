@@ -0,0 +1,138 @@
+use serde::{Deserialize, Serialize};
+use serde_with::{TimestampSeconds, serde_as};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::domain::error::BlogError;
+
+const MAX_FILE_NAME_LEN: usize = 255;
+
+/// Загруженный файл. Содержимое лежит в хранилище под ключом `sha256`,
+/// поэтому одинаковые файлы хранятся один раз
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Attachment {
+    pub id: i64,
+    pub owner_id: Uuid,
+    pub post_id: Option<i64>,
+    pub file_name: String,
+    pub content_type: String,
+    pub size: i64,
+    pub sha256: String,
+    #[serde_as(as = "TimestampSeconds<i64>")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl Attachment {
+    /// Публичный адрес содержимого
+    pub fn url(&self) -> String {
+        format!("/api/attachments/{}", self.id)
+    }
+}
+
+/// Файл от пользователя до сохранения
+#[derive(Debug)]
+pub struct UploadAttachment {
+    pub file_name: String,
+    pub post_id: Option<i64>,
+    pub data: Vec<u8>,
+}
+
+/// Метаданные для записи в базу, когда содержимое уже сохранено
+#[derive(Debug)]
+pub struct CreateAttachment {
+    pub post_id: Option<i64>,
+    pub file_name: String,
+    pub content_type: String,
+    pub size: i64,
+    pub sha256: String,
+}
+
+/// Тип файла по сигнатуре. Заголовку клиента не доверяем,
+/// допускаются только картинки и PDF
+pub fn sniff_content_type(data: &[u8]) -> Option<&'static str> {
+    const SIGNATURES: &[(&[u8], &str)] = &[
+        (b"\x89PNG\r\n\x1a\n", "image/png"),
+        (b"\xff\xd8\xff", "image/jpeg"),
+        (b"GIF87a", "image/gif"),
+        (b"GIF89a", "image/gif"),
+        (b"%PDF-", "application/pdf"),
+    ];
+
+    if data.len() >= 12 && &data[..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+        return Some("image/webp");
+    }
+
+    SIGNATURES
+        .iter()
+        .find(|(signature, _)| data.starts_with(signature))
+        .map(|(_, content_type)| *content_type)
+}
+
+/// Оставляет от имени только последний компонент пути без управляющих символов
+pub fn sanitize_file_name(name: &str) -> Result<String, BlogError> {
+    let name: String = name
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| !c.is_control())
+        .take(MAX_FILE_NAME_LEN)
+        .collect();
+    let name = name.trim();
+
+    if name.is_empty() || name == "." || name == ".." {
+        return Err(BlogError::Validation("file name is empty".to_string()));
+    }
+
+    Ok(name.to_string())
+}
+
+pub fn content_hash(data: &[u8]) -> String {
+    let digest = Sha256::digest(data);
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+impl From<Attachment> for crate::blog_grpc::Attachment {
+    fn from(value: Attachment) -> Self {
+        Self {
+            url: value.url(),
+            id: value.id,
+            owner_id: value.owner_id.to_string(),
+            post_id: value.post_id,
+            file_name: value.file_name,
+            content_type: value.content_type,
+            size: value.size,
+            sha256: value.sha256,
+            created_at: value.created_at.timestamp(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff_and_sanitize() {
+        assert_eq!(
+            sniff_content_type(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"),
+            Some("image/png")
+        );
+        assert_eq!(
+            sniff_content_type(b"RIFF\0\0\0\0WEBPVP8 "),
+            Some("image/webp")
+        );
+        assert_eq!(sniff_content_type(b"<svg onload=alert(1)>"), None);
+
+        assert_eq!(
+            sanitize_file_name("../../etc/cat.png").expect("valid name"),
+            "cat.png"
+        );
+        assert_eq!(
+            sanitize_file_name("C:\\photos\\кот.jpg").expect("valid name"),
+            "кот.jpg"
+        );
+        assert!(sanitize_file_name("uploads/").is_err());
+    }
+}
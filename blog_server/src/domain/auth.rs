@@ -1,6 +1,18 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::domain::{
+    error::{BlogError, FieldError},
+    personal_token::TokenScope,
+    role::Role,
+};
+
+const MIN_PASSWORD_LEN: usize = 8;
+const MAX_PASSWORD_LEN: usize = 128;
+const MAX_EMAIL_LEN: usize = 254;
+const MIN_USERNAME_LEN: usize = 3;
+const MAX_USERNAME_LEN: usize = 32;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct User {
     pub id: Uuid,
@@ -8,6 +20,15 @@ pub struct User {
     pub email: String,
     pub password_hash: String,
     pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Водяной знак: access токены, выданные раньше, недействительны
+    pub tokens_valid_after: Option<chrono::DateTime<chrono::Utc>>,
+    pub email_verified_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub role: Role,
+    /// Секрет TOTP в base32. До подтверждения `totp_enabled_at` пустой
+    pub totp_secret: Option<String>,
+    pub totp_enabled_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Последний принятый шаг TOTP, чтобы один код нельзя было использовать дважды
+    pub totp_last_step: Option<i64>,
 }
 
 impl User {
@@ -18,6 +39,12 @@ impl User {
             email,
             password_hash,
             created_at: chrono::Utc::now(),
+            tokens_valid_after: None,
+            email_verified_at: None,
+            role: Role::Author,
+            totp_secret: None,
+            totp_enabled_at: None,
+            totp_last_step: None,
         }
     }
 }
@@ -31,11 +58,406 @@ pub struct Auth {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Login {
+    /// Имя пользователя или email
+    #[serde(alias = "login", alias = "email")]
     pub username: String,
     pub password: String,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RefreshRequest {
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChangePassword {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PasswordResetRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PasswordResetConfirm {
+    pub token: String,
+    pub new_password: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VerifyEmail {
+    pub token: String,
+}
+
+/// Код из приложения-аутентификатора или код восстановления
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TwoFactorCode {
+    pub code: String,
+}
+
+/// Второй шаг входа
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LoginTwoFactor {
+    pub challenge: String,
+    pub code: String,
+}
+
+/// Данные для подключения 2FA. Коды восстановления показываются один раз
+#[derive(Debug, Serialize)]
+pub struct TwoFactorEnrollment {
+    pub secret: String,
+    pub otpauth_uri: String,
+    pub recovery_codes: Vec<String>,
+}
+
+/// Email сравнивается без учёта регистра, поэтому хранится в нижнем
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+/// Регистр имени сохраняется для отображения, уникальность проверяется без него
+pub fn normalize_username(username: &str) -> String {
+    username.trim().to_string()
+}
+
+/// Проверяет все поля регистрации сразу, чтобы клиент показал все ошибки
+pub fn validate_registration(username: &str, email: &str, password: &str) -> Result<(), BlogError> {
+    let errors: Vec<FieldError> = [
+        ("username", check_username(username)),
+        ("email", check_email(email)),
+        ("password", check_password(password, &[username, email])),
+    ]
+    .into_iter()
+    .filter_map(|(field, result)| result.err().map(|message| FieldError::new(field, message)))
+    .collect();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(BlogError::InvalidFields(errors))
+    }
+}
+
+pub fn validate_email(email: &str) -> Result<(), BlogError> {
+    check_email(email).map_err(|message| BlogError::invalid_field("email", message))
+}
+
+/// `identities` - имя и email пользователя, пароль не должен с ними совпадать
+pub fn validate_password(password: &str, identities: &[&str]) -> Result<(), BlogError> {
+    check_password(password, identities)
+        .map_err(|message| BlogError::invalid_field("password", message))
+}
+
+fn check_username(username: &str) -> Result<(), String> {
+    let len = username.chars().count();
+    if !(MIN_USERNAME_LEN..=MAX_USERNAME_LEN).contains(&len) {
+        return Err(format!(
+            "must be {} to {} characters long",
+            MIN_USERNAME_LEN, MAX_USERNAME_LEN
+        ));
+    }
+    if !username
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+    {
+        return Err("may contain only latin letters, digits, '_', '.' and '-'".to_string());
+    }
+    if !username.starts_with(|c: char| c.is_ascii_alphanumeric()) {
+        return Err("must start with a letter or digit".to_string());
+    }
+    Ok(())
+}
+
+/// Простая проверка формы адреса, реальную проверку делает письмо со ссылкой
+fn check_email(email: &str) -> Result<(), String> {
+    let invalid = || "must be a valid address".to_string();
+    if email.len() > MAX_EMAIL_LEN || email.chars().any(char::is_whitespace) {
+        return Err(invalid());
+    }
+    let (local, domain) = email.split_once('@').ok_or_else(invalid)?;
+    if local.is_empty()
+        || domain.contains('@')
+        || !domain.contains('.')
+        || domain.starts_with('.')
+        || domain.ends_with('.')
+    {
+        return Err(invalid());
+    }
+    Ok(())
+}
+
+fn check_password(password: &str, identities: &[&str]) -> Result<(), String> {
+    let len = password.chars().count();
+    if len < MIN_PASSWORD_LEN {
+        return Err(format!(
+            "must be at least {} characters long",
+            MIN_PASSWORD_LEN
+        ));
+    }
+    if len > MAX_PASSWORD_LEN {
+        return Err(format!(
+            "must be at most {} characters long",
+            MAX_PASSWORD_LEN
+        ));
+    }
+    if !password.chars().any(char::is_alphabetic) || !password.chars().any(|c| !c.is_alphabetic()) {
+        return Err("must contain a letter and a digit or symbol".to_string());
+    }
+    if identities
+        .iter()
+        .any(|identity| !identity.is_empty() && password.eq_ignore_ascii_case(identity))
+    {
+        return Err("must not match the username or email".to_string());
+    }
+    Ok(())
+}
+
 pub struct AuthAnswer {
     pub token: String,
+    pub refresh_token: String,
+    /// Время жизни access токена, в секундах
+    pub expires_in: i64,
     pub uuid: Uuid,
 }
+
+/// Результат входа по паролю
+pub enum LoginOutcome {
+    Authenticated(AuthAnswer),
+    /// Пароль верный, но вход нужно подтвердить кодом второго фактора
+    TwoFactorRequired(TwoFactorChallenge),
+}
+
+pub struct TwoFactorChallenge {
+    pub challenge: String,
+    /// Время жизни запроса, в секундах
+    pub expires_in: i64,
+}
+
+/// Проверенный access токен
+#[derive(Debug, Clone)]
+pub struct AccessSession {
+    pub user_id: Uuid,
+    pub jti: Uuid,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    pub email_verified: bool,
+    /// Роль на момент выдачи токена
+    pub role: Role,
+    /// Права токена доступа. `None` у обычной сессии, ей разрешено всё
+    pub scopes: Option<Vec<TokenScope>>,
+}
+
+impl AccessSession {
+    pub fn require_role(&self, min: Role) -> Result<(), BlogError> {
+        if self.role < min {
+            return Err(BlogError::Forbidden);
+        }
+        Ok(())
+    }
+
+    pub fn require_scope(&self, scope: TokenScope) -> Result<(), BlogError> {
+        match &self.scopes {
+            Some(scopes) if !scopes.contains(&scope) => Err(BlogError::Forbidden),
+            _ => Ok(()),
+        }
+    }
+
+    /// Управление аккаунтом, токенами и пользователями недоступно по токену доступа
+    pub fn require_interactive(&self) -> Result<(), BlogError> {
+        if self.scopes.is_some() {
+            return Err(BlogError::Forbidden);
+        }
+        Ok(())
+    }
+
+    /// Пост может менять его автор или модератор
+    pub fn ensure_can_modify(&self, author_id: Uuid) -> Result<(), BlogError> {
+        if self.user_id == author_id {
+            return Ok(());
+        }
+        self.require_role(Role::Moderator)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetRole {
+    pub role: Role,
+}
+
+/// Refresh токен. В базе хранится только его хеш.
+/// При обновлении токен помечается использованным и заменяется новым
+/// из того же семейства
+#[derive(Debug)]
+pub struct RefreshToken {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub family_id: Uuid,
+    pub token_hash: String,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub used_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub revoked_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl RefreshToken {
+    pub fn new(user_id: Uuid, family_id: Uuid, token_hash: String, ttl: chrono::Duration) -> Self {
+        let now = chrono::Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            user_id,
+            family_id,
+            token_hash,
+            expires_at: now + ttl,
+            created_at: now,
+            used_at: None,
+            revoked_at: None,
+        }
+    }
+}
+
+/// Одноразовый токен из письма (сброс пароля, подтверждение email).
+/// В базе хранится только хеш
+#[derive(Debug)]
+pub struct OneTimeToken {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub token_hash: String,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl OneTimeToken {
+    pub fn new(user_id: Uuid, token_hash: String, ttl: chrono::Duration) -> Self {
+        let now = chrono::Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            user_id,
+            token_hash,
+            expires_at: now + ttl,
+            created_at: now,
+        }
+    }
+}
+
+impl From<AuthAnswer> for crate::blog_grpc::AuthResponse {
+    fn from(value: AuthAnswer) -> Self {
+        Self {
+            token: value.token,
+            refresh_token: value.refresh_token,
+            expires_in: value.expires_in,
+            two_factor_challenge: None,
+        }
+    }
+}
+
+impl From<TwoFactorChallenge> for crate::blog_grpc::AuthResponse {
+    fn from(value: TwoFactorChallenge) -> Self {
+        Self {
+            token: String::new(),
+            refresh_token: String::new(),
+            expires_in: value.expires_in,
+            two_factor_challenge: Some(value.challenge),
+        }
+    }
+}
+
+impl From<LoginOutcome> for crate::blog_grpc::AuthResponse {
+    fn from(value: LoginOutcome) -> Self {
+        match value {
+            LoginOutcome::Authenticated(answer) => answer.into(),
+            LoginOutcome::TwoFactorRequired(challenge) => challenge.into(),
+        }
+    }
+}
+
+impl From<User> for crate::blog_grpc::User {
+    fn from(value: User) -> Self {
+        Self {
+            id: value.id.to_string(),
+            username: value.username,
+            email: value.email,
+            role: value.role.as_str().to_string(),
+            email_verified: value.email_verified_at.is_some(),
+            created_at: value.created_at.timestamp(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_email() {
+        assert!(validate_email("user@example.com").is_ok());
+        assert!(validate_email("first.last+tag@mail.example.org").is_ok());
+
+        assert!(validate_email("").is_err());
+        assert!(validate_email("user").is_err());
+        assert!(validate_email("@example.com").is_err());
+        assert!(validate_email("user@localhost").is_err());
+        assert!(validate_email("user@@example.com").is_err());
+        assert!(validate_email("us er@example.com").is_err());
+        assert!(validate_email("user@example.").is_err());
+    }
+
+    #[test]
+    fn test_validate_registration() {
+        assert!(validate_registration("alice_1", "alice@example.com", "secret123").is_ok());
+        assert_eq!(normalize_email(" Alice@Example.COM "), "alice@example.com");
+
+        let Err(BlogError::InvalidFields(errors)) =
+            validate_registration("a", "alice@example", "password")
+        else {
+            panic!("expected field errors");
+        };
+        let fields: Vec<_> = errors.iter().map(|e| e.field).collect();
+        assert_eq!(fields, ["username", "email", "password"]);
+
+        assert!(validate_registration("-alice", "alice@example.com", "secret123").is_err());
+        assert!(validate_registration("али", "alice@example.com", "secret123").is_err());
+        assert!(validate_registration("alice", "alice@example.com", "ALICE").is_err());
+        assert!(validate_password("Alice123", &["alice123"]).is_err());
+    }
+
+    #[test]
+    fn test_access_session_guards() {
+        let author_id = Uuid::new_v4();
+        let session = |user_id, role| AccessSession {
+            user_id,
+            jti: Uuid::new_v4(),
+            expires_at: chrono::Utc::now(),
+            email_verified: true,
+            role,
+            scopes: None,
+        };
+
+        assert!(
+            session(author_id, Role::Author)
+                .ensure_can_modify(author_id)
+                .is_ok()
+        );
+        assert!(
+            session(Uuid::new_v4(), Role::Author)
+                .ensure_can_modify(author_id)
+                .is_err()
+        );
+        assert!(
+            session(Uuid::new_v4(), Role::Moderator)
+                .ensure_can_modify(author_id)
+                .is_ok()
+        );
+
+        assert!(
+            session(author_id, Role::Reader)
+                .require_role(Role::Author)
+                .is_err()
+        );
+        assert!(
+            session(author_id, Role::Admin)
+                .require_role(Role::Moderator)
+                .is_ok()
+        );
+    }
+}
@@ -0,0 +1,17 @@
+use serde::Serialize;
+
+/// Состояние закладки после добавления или удаления
+#[derive(Debug, Serialize)]
+pub struct Bookmark {
+    pub post_id: i64,
+    pub bookmarked: bool,
+}
+
+impl From<Bookmark> for crate::blog_grpc::BookmarkResponse {
+    fn from(value: Bookmark) -> Self {
+        Self {
+            post_id: value.post_id,
+            bookmarked: value.bookmarked,
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+use serde_with::{TimestampSeconds, serde_as};
+use uuid::Uuid;
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Comment {
+    pub id: i64,
+    pub post_id: i64,
+    pub author_id: Uuid,
+    /// Комментарий, на который отвечают. `None` для комментария верхнего уровня
+    pub parent_id: Option<i64>,
+    pub content: String,
+    #[serde_as(as = "TimestampSeconds<i64>")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    #[serde_as(as = "Option<TimestampSeconds<i64>>")]
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateComment {
+    pub content: String,
+    #[serde(default)]
+    pub parent_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateComment {
+    pub content: String,
+}
+
+impl From<Comment> for crate::blog_grpc::Comment {
+    fn from(value: Comment) -> Self {
+        Self {
+            id: value.id,
+            post_id: value.post_id,
+            author_id: value.author_id.to_string(),
+            parent_id: value.parent_id,
+            content: value.content,
+            created_at: value.created_at.timestamp(),
+            updated_at: value.updated_at.map(|e| e.timestamp()),
+        }
+    }
+}
+
+impl From<crate::blog_grpc::CreateCommentRequest> for CreateComment {
+    fn from(value: crate::blog_grpc::CreateCommentRequest) -> Self {
+        Self {
+            content: value.content,
+            parent_id: value.parent_id,
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use std::{collections::HashSet, str::FromStr, sync::LazyLock};
+
+use pulldown_cmark::{Options, Parser, html};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::error::BlogError;
+
+/// Формат текста поста
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "content_format", rename_all = "lowercase")]
+pub enum ContentFormat {
+    Plain,
+    #[default]
+    Markdown,
+}
+
+impl ContentFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContentFormat::Plain => "plain",
+            ContentFormat::Markdown => "markdown",
+        }
+    }
+}
+
+impl FromStr for ContentFormat {
+    type Err = BlogError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "plain" => Ok(ContentFormat::Plain),
+            "markdown" => Ok(ContentFormat::Markdown),
+            other => Err(BlogError::Validation(format!(
+                "unknown content format: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// Санитайзер для HTML из Markdown: без скриптов, обработчиков событий
+/// и опасных схем ссылок, внешние ссылки получают `rel="noopener noreferrer nofollow"`
+static SANITIZER: LazyLock<ammonia::Builder<'static>> = LazyLock::new(|| {
+    let mut builder = ammonia::Builder::default();
+    builder
+        .url_schemes(HashSet::from(["http", "https", "mailto"]))
+        .link_rel(Some("noopener noreferrer nofollow"));
+    builder
+});
+
+/// HTML для отображения поста. Результат сохраняется вместе с постом
+pub fn render_html(content: &str, format: ContentFormat) -> String {
+    match format {
+        ContentFormat::Plain => render_plain(content),
+        ContentFormat::Markdown => {
+            let options =
+                Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+            let mut unsafe_html = String::with_capacity(content.len() * 3 / 2);
+            html::push_html(&mut unsafe_html, Parser::new_ext(content, options));
+            SANITIZER.clean(&unsafe_html).to_string()
+        }
+    }
+}
+
+/// Обычный текст: экранирование и переносы строк.
+/// Совпадает с заполнением `content_html` в миграции
+fn render_plain(content: &str) -> String {
+    let escaped = content
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\n', "<br>");
+    format!("<p>{}</p>", escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_markdown_sanitized() {
+        let html = render_html(
+            "**bold** <script>alert(1)</script> [link](javascript:alert(1)) [ok](https://example.com)",
+            ContentFormat::Markdown,
+        );
+
+        assert!(html.contains("<strong>bold</strong>"));
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("javascript:"));
+        assert!(html.contains(
+            r#"<a href="https://example.com" rel="noopener noreferrer nofollow">ok</a>"#
+        ));
+    }
+
+    #[test]
+    fn test_render_plain() {
+        assert_eq!(
+            render_html("a < b\nc", ContentFormat::Plain),
+            "<p>a &lt; b<br>c</p>"
+        );
+    }
+}
@@ -1,4 +1,7 @@
-use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use actix_web::{
+    HttpResponse, ResponseError,
+    http::{StatusCode, header::RETRY_AFTER},
+};
 use serde::Serialize;
 use serde_json::json;
 use thiserror::Error;
@@ -24,10 +27,29 @@ pub enum DomainError {
     Internal(String),
 }
 
+/// Ошибка проверки одного поля запроса
+#[derive(Debug, Serialize)]
+pub struct FieldError {
+    pub field: &'static str,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            field,
+            message: message.into(),
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum BlogError {
     #[error("Validation error: {0}")]
     Validation(String),
+    /// Ошибки по полям, в ответе HTTP они перечислены в `details.fields`
+    #[error("Validation error: {}", format_fields(.0))]
+    InvalidFields(Vec<FieldError>),
     #[error("Forbidden")]
     Forbidden,
 
@@ -37,10 +59,40 @@ pub enum BlogError {
     #[error("Unauthorized")]
     Unautorized,
 
+    /// Запрос столкнулся с параллельным изменением, его можно повторить
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    /// Через сколько секунд можно повторить
+    #[error("Too many requests, retry after {0} seconds")]
+    TooManyRequests(u64),
+
     #[error("Internal: {0}")]
     Internal(String),
 }
 
+fn format_fields(errors: &[FieldError]) -> String {
+    errors
+        .iter()
+        .map(|error| format!("{} {}", error.field, error.message))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+impl BlogError {
+    pub fn invalid_field(field: &'static str, message: impl Into<String>) -> Self {
+        BlogError::InvalidFields(vec![FieldError::new(field, message)])
+    }
+
+    pub fn too_many_requests(retry_after: std::time::Duration) -> Self {
+        let mut secs = retry_after.as_secs();
+        if retry_after.subsec_nanos() > 0 {
+            secs += 1;
+        }
+        BlogError::TooManyRequests(secs.max(1))
+    }
+}
+
 #[derive(Serialize)]
 struct ErrorBody<'a> {
     error: &'a str,
@@ -54,8 +106,10 @@ impl ResponseError for BlogError {
             BlogError::Forbidden => StatusCode::FORBIDDEN,
             BlogError::NotFound(_) => StatusCode::NOT_FOUND,
             BlogError::Unautorized => StatusCode::UNAUTHORIZED,
+            BlogError::Conflict(_) => StatusCode::CONFLICT,
+            BlogError::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
             BlogError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
-            BlogError::Validation(_) => StatusCode::BAD_REQUEST,
+            BlogError::Validation(_) | BlogError::InvalidFields(_) => StatusCode::BAD_REQUEST,
         }
     }
 
@@ -65,9 +119,17 @@ impl ResponseError for BlogError {
 
         let details = match self {
             BlogError::Validation(msg) => Some(json!({"resource": msg })),
+            BlogError::InvalidFields(errors) => Some(json!({
+                "resource": format_fields(errors),
+                "fields": errors,
+            })),
             BlogError::Forbidden => None,
             BlogError::NotFound(res) => Some(json!({"resource": res})),
             BlogError::Unautorized => None,
+            BlogError::Conflict(res) => Some(json!({"resource": res})),
+            BlogError::TooManyRequests(secs) => {
+                Some(json!({"resource": message, "retry_after": secs}))
+            }
             BlogError::Internal(_) => None,
         };
 
@@ -76,7 +138,11 @@ impl ResponseError for BlogError {
             details,
         };
 
-        HttpResponse::build(self.status_code()).json(body)
+        let mut response = HttpResponse::build(self.status_code());
+        if let BlogError::TooManyRequests(secs) = self {
+            response.insert_header((RETRY_AFTER, secs.to_string()));
+        }
+        response.json(body)
     }
 }
 
@@ -96,9 +162,16 @@ impl From<BlogError> for Status {
     fn from(value: BlogError) -> Self {
         match value {
             BlogError::Validation(e) => Status::invalid_argument(e),
+            BlogError::InvalidFields(errors) => Status::invalid_argument(format_fields(&errors)),
             BlogError::Forbidden => Status::permission_denied("Forbiden"),
             BlogError::NotFound(e) => Status::not_found(e),
             BlogError::Unautorized => Status::unauthenticated("Unautorized"),
+            BlogError::Conflict(e) => Status::aborted(e),
+            BlogError::TooManyRequests(secs) => {
+                let mut status = Status::resource_exhausted(value.to_string());
+                status.metadata_mut().insert("retry-after", secs.into());
+                status
+            }
             BlogError::Internal(e) => Status::internal(e),
         }
     }
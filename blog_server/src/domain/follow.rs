@@ -0,0 +1,48 @@
+use serde::Serialize;
+use serde_with::{TimestampSeconds, serde_as};
+
+/// Пользователь в списке подписчиков или подписок
+#[serde_as]
+#[derive(Debug, Serialize)]
+pub struct FollowUser {
+    pub username: String,
+    pub display_name: Option<String>,
+    /// Когда оформлена подписка
+    #[serde_as(as = "TimestampSeconds<i64>")]
+    pub followed_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Страница списка, от новых подписок к старым
+#[derive(Debug)]
+pub struct FollowPage {
+    pub users: Vec<FollowUser>,
+    pub total: i64,
+}
+
+/// Подписан ли текущий пользователь на автора и сколько у автора подписчиков
+#[derive(Debug, Serialize)]
+pub struct FollowStatus {
+    pub username: String,
+    pub following: bool,
+    pub followers: i64,
+}
+
+impl From<FollowUser> for crate::blog_grpc::FollowUser {
+    fn from(value: FollowUser) -> Self {
+        Self {
+            username: value.username,
+            display_name: value.display_name,
+            followed_at: value.followed_at.timestamp(),
+        }
+    }
+}
+
+impl From<FollowStatus> for crate::blog_grpc::FollowStatus {
+    fn from(value: FollowStatus) -> Self {
+        Self {
+            username: value.username,
+            following: value.following,
+            followers: value.followers,
+        }
+    }
+}
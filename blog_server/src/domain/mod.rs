@@ -1,3 +1,17 @@
+pub mod account;
+pub mod attachment;
 pub mod auth;
+pub mod bookmark;
+pub mod comment;
+pub mod content;
 pub mod error;
+pub mod follow;
+pub mod pagination;
+pub mod personal_token;
 pub mod post;
+pub mod profile;
+pub mod reaction;
+pub mod revision;
+pub mod role;
+pub mod search;
+pub mod slug;
@@ -0,0 +1,86 @@
+use crate::domain::{error::BlogError, post::Post};
+
+/// Позиция в ленте, отсортированной по `(created_at, id)` от новых к старым
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PostCursor {
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub id: i64,
+}
+
+impl PostCursor {
+    pub fn from_post(post: &Post) -> Self {
+        Self {
+            created_at: post.created_at,
+            id: post.id,
+        }
+    }
+
+    /// Непрозрачная для клиента строка: время в микросекундах и id в hex
+    pub fn encode(&self) -> String {
+        format!(
+            "{:016x}{:016x}",
+            self.created_at.timestamp_micros() as u64,
+            self.id as u64
+        )
+    }
+
+    pub fn decode(value: &str) -> Result<Self, BlogError> {
+        let invalid = || BlogError::Validation("invalid cursor".to_string());
+        if value.len() != 32 || !value.is_ascii() {
+            return Err(invalid());
+        }
+
+        let micros = u64::from_str_radix(&value[..16], 16).map_err(|_| invalid())? as i64;
+        let id = u64::from_str_radix(&value[16..], 16).map_err(|_| invalid())? as i64;
+        let created_at = chrono::DateTime::from_timestamp_micros(micros).ok_or_else(invalid)?;
+
+        Ok(Self { created_at, id })
+    }
+}
+
+/// Страница ленты: по смещению (старый режим) или после курсора
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageRequest {
+    Offset(i64),
+    After(PostCursor),
+}
+
+impl PageRequest {
+    /// Курсор, если он передан, имеет приоритет над смещением.
+    /// Отрицательное смещение считается нулевым
+    pub fn new(offset: i64, cursor: Option<&str>) -> Result<Self, BlogError> {
+        match cursor.filter(|c| !c.is_empty()) {
+            Some(cursor) => Ok(PageRequest::After(PostCursor::decode(cursor)?)),
+            None => Ok(PageRequest::Offset(offset.max(0))),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct PostPage {
+    pub posts: Vec<Post>,
+    /// Сколько всего постов подходит под фильтр
+    pub total: i64,
+    /// Курсор следующей страницы. `None` на последней странице
+    pub next_cursor: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_roundtrip() {
+        let cursor = PostCursor {
+            created_at: chrono::DateTime::from_timestamp_micros(1_792_315_249_123_456)
+                .expect("valid timestamp"),
+            id: 42,
+        };
+
+        let decoded = PostCursor::decode(&cursor.encode()).expect("Failed to decode cursor");
+
+        assert_eq!(decoded, cursor);
+        assert!(PostCursor::decode("not-a-cursor").is_err());
+        assert_eq!(PageRequest::new(-5, None).unwrap(), PageRequest::Offset(0));
+    }
+}
@@ -0,0 +1,153 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use serde_with::{TimestampSeconds, serde_as};
+use uuid::Uuid;
+
+use crate::domain::error::BlogError;
+
+/// Префикс отличает токен доступа от JWT в заголовке `Authorization`
+pub const PERSONAL_TOKEN_PREFIX: &str = "blog_pat_";
+
+const MAX_TOKEN_NAME_LEN: usize = 100;
+const MAX_TOKEN_LIFETIME_DAYS: i64 = 3650;
+
+/// Права токена доступа
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "token_scope")]
+pub enum TokenScope {
+    /// Чтение своих черновиков и неопубликованных постов, ленты подписок,
+    /// чтение и изменение закладок
+    #[serde(rename = "posts:read")]
+    #[sqlx(rename = "posts:read")]
+    PostsRead,
+    /// Создание, изменение, публикация и удаление постов, загрузка файлов
+    #[serde(rename = "posts:write")]
+    #[sqlx(rename = "posts:write")]
+    PostsWrite,
+    /// Комментарии и реакции на посты. Отдельного права для реакций нет:
+    /// это тот же публичный отклик читателя, что и комментарий
+    #[serde(rename = "comments:write")]
+    #[sqlx(rename = "comments:write")]
+    CommentsWrite,
+}
+
+impl TokenScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenScope::PostsRead => "posts:read",
+            TokenScope::PostsWrite => "posts:write",
+            TokenScope::CommentsWrite => "comments:write",
+        }
+    }
+}
+
+impl FromStr for TokenScope {
+    type Err = BlogError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "posts:read" => Ok(TokenScope::PostsRead),
+            "posts:write" => Ok(TokenScope::PostsWrite),
+            "comments:write" => Ok(TokenScope::CommentsWrite),
+            other => Err(BlogError::Validation(format!("unknown scope: {}", other))),
+        }
+    }
+}
+
+/// Долгоживущий токен для автоматизации (CI и т.п.).
+/// В базе хранится только хеш, сам токен показывается один раз при создании
+#[serde_as]
+#[derive(Debug, Serialize)]
+pub struct PersonalAccessToken {
+    pub id: Uuid,
+    #[serde(skip)]
+    pub user_id: Uuid,
+    pub name: String,
+    #[serde(skip)]
+    pub token_hash: String,
+    pub scopes: Vec<TokenScope>,
+    #[serde_as(as = "TimestampSeconds<i64>")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    #[serde_as(as = "Option<TimestampSeconds<i64>>")]
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde_as(as = "Option<TimestampSeconds<i64>>")]
+    pub last_used_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreatePersonalToken {
+    pub name: String,
+    pub scopes: Vec<TokenScope>,
+    /// Без срока токен действует до отзыва или смены пароля
+    #[serde(default)]
+    pub expires_in_days: Option<i64>,
+}
+
+impl CreatePersonalToken {
+    pub fn validate(&self) -> Result<(), BlogError> {
+        let name = self.name.trim();
+        if name.is_empty() || name.chars().count() > MAX_TOKEN_NAME_LEN {
+            return Err(BlogError::Validation(format!(
+                "token name must be 1 to {} characters long",
+                MAX_TOKEN_NAME_LEN
+            )));
+        }
+        if self.scopes.is_empty() {
+            return Err(BlogError::Validation(
+                "at least one scope is required".to_string(),
+            ));
+        }
+        if self
+            .expires_in_days
+            .is_some_and(|days| !(1..=MAX_TOKEN_LIFETIME_DAYS).contains(&days))
+        {
+            return Err(BlogError::Validation(format!(
+                "expires_in_days must be between 1 and {}",
+                MAX_TOKEN_LIFETIME_DAYS
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl PersonalAccessToken {
+    pub fn new(user_id: Uuid, data: CreatePersonalToken, token_hash: String) -> Self {
+        let now = chrono::Utc::now();
+        let mut scopes = Vec::new();
+        for scope in data.scopes {
+            if !scopes.contains(&scope) {
+                scopes.push(scope);
+            }
+        }
+        Self {
+            id: Uuid::new_v4(),
+            user_id,
+            name: data.name.trim().to_string(),
+            token_hash,
+            scopes,
+            created_at: now,
+            expires_at: data
+                .expires_in_days
+                .map(|days| now + chrono::Duration::days(days)),
+            last_used_at: None,
+        }
+    }
+}
+
+impl From<PersonalAccessToken> for crate::blog_grpc::PersonalAccessToken {
+    fn from(value: PersonalAccessToken) -> Self {
+        Self {
+            id: value.id.to_string(),
+            name: value.name,
+            scopes: value
+                .scopes
+                .iter()
+                .map(|s| s.as_str().to_string())
+                .collect(),
+            created_at: value.created_at.timestamp(),
+            expires_at: value.expires_at.map(|t| t.timestamp()),
+            last_used_at: value.last_used_at.map(|t| t.timestamp()),
+        }
+    }
+}
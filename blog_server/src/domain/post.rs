@@ -1,60 +1,256 @@
 use std::str::FromStr;
 
 use serde::{Deserialize, Serialize};
-use serde_with::{TimestampSeconds, serde_as};
+use serde_with::{DefaultOnNull, TimestampSeconds, serde_as};
+use sqlx::types::Json;
 use uuid::Uuid;
 
+use crate::domain::{
+    auth::AccessSession,
+    content::ContentFormat,
+    error::BlogError,
+    personal_token::TokenScope,
+    profile::AuthorSummary,
+    reaction::{ReactionCounts, ReactionKind, counts_to_grpc},
+};
+
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Post {
     pub id: i64,
+    /// Уникальный адрес поста, строится из заголовка
+    pub slug: String,
     pub title: String,
     pub content: String,
+    pub content_format: ContentFormat,
+    /// Отрендеренный и очищенный HTML, обновляется при изменении текста
+    pub content_html: String,
     pub author_id: Uuid,
+    /// Имя автора читается из `users` вместе с постом
+    pub author: Json<AuthorSummary>,
+    /// Счётчики реакций читаются тем же запросом, что и пост
+    pub reactions: Json<ReactionCounts>,
+    /// Есть ли пост в закладках у текущего пользователя.
+    /// Без авторизации не заполняется и в ответ не попадает
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bookmarked: Option<bool>,
     #[serde_as(as = "TimestampSeconds<i64>")]
     pub created_at: chrono::DateTime<chrono::Utc>,
     #[serde_as(as = "Option<TimestampSeconds<i64>>")]
     pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub tags: Vec<String>,
+    pub status: PostStatus,
+    #[serde_as(as = "Option<TimestampSeconds<i64>>")]
+    pub published_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Запланированное время публикации черновика
+    #[serde_as(as = "Option<TimestampSeconds<i64>>")]
+    pub publish_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Жизненный цикл поста. Публично виден только `Published`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "post_status", rename_all = "lowercase")]
+pub enum PostStatus {
+    Draft,
+    Published,
+    Archived,
+}
+
+impl PostStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PostStatus::Draft => "draft",
+            PostStatus::Published => "published",
+            PostStatus::Archived => "archived",
+        }
+    }
+}
+
+impl FromStr for PostStatus {
+    type Err = BlogError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "draft" => Ok(PostStatus::Draft),
+            "published" => Ok(PostStatus::Published),
+            "archived" => Ok(PostStatus::Archived),
+            other => Err(BlogError::Validation(format!(
+                "unknown post status: {}",
+                other
+            ))),
+        }
+    }
 }
 
+#[serde_as]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreatePost {
     pub title: String,
     pub content: String,
+    #[serde_as(as = "DefaultOnNull")]
+    #[serde(default)]
+    pub content_format: ContentFormat,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Сохранить как черновик вместо публикации
+    #[serde(default)]
+    pub draft: bool,
+    /// Опубликовать автоматически в указанное время
+    #[serde_as(as = "Option<TimestampSeconds<i64>>")]
+    #[serde(default)]
+    pub publish_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
+#[serde_as]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UpdatePost {
     pub title: String,
     pub content: String,
+    /// `None` оставляет формат без изменений
+    #[serde(default)]
+    pub content_format: Option<ContentFormat>,
+    /// `None` оставляет теги поста без изменений
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    /// `None` оставляет статус поста без изменений
+    #[serde(default)]
+    pub status: Option<PostStatus>,
+    /// Запланировать публикацию. Смена статуса без расписания снимает его
+    #[serde_as(as = "Option<TimestampSeconds<i64>>")]
+    #[serde(default)]
+    pub publish_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl CreatePost {
+    /// Пост будет опубликован сразу или по расписанию
+    pub fn publishes(&self) -> bool {
+        !self.draft || self.publish_at.is_some()
+    }
+}
+
+impl UpdatePost {
+    pub fn publishes(&self) -> bool {
+        self.status == Some(PostStatus::Published) || self.publish_at.is_some()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TagCount {
+    pub name: String,
+    pub post_count: i64,
+}
+
+const MAX_TAG_LEN: usize = 64;
+
+/// Время публикации должно быть в будущем
+pub fn validate_publish_at(
+    publish_at: Option<chrono::DateTime<chrono::Utc>>,
+) -> Result<(), BlogError> {
+    match publish_at {
+        Some(at) if at <= chrono::Utc::now() => Err(BlogError::Validation(
+            "publish_at must be in the future".to_string(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Приводит теги к нижнему регистру, убирает пробелы по краям и повторы
+pub fn normalize_tags(tags: Vec<String>) -> Result<Vec<String>, BlogError> {
+    let mut normalized: Vec<String> = Vec::with_capacity(tags.len());
+    for tag in tags {
+        let tag = tag.trim().to_lowercase();
+        if tag.is_empty() {
+            continue;
+        }
+        if tag.chars().count() > MAX_TAG_LEN {
+            return Err(BlogError::Validation(format!(
+                "tag is longer than {} characters",
+                MAX_TAG_LEN
+            )));
+        }
+        if !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    Ok(normalized)
 }
 
 impl Post {
     pub fn new(id: i64, title: String, content: String, author_id: Uuid) -> Self {
         Self {
             id,
+            slug: crate::domain::slug::slugify(&title),
             title,
+            content_html: crate::domain::content::render_html(&content, ContentFormat::default()),
             content,
+            content_format: ContentFormat::default(),
             author_id,
+            author: Json(AuthorSummary::default()),
+            reactions: Json(ReactionCounts::new()),
+            bookmarked: None,
             created_at: chrono::Utc::now(),
             updated_at: None,
+            tags: Vec::new(),
+            status: PostStatus::Published,
+            published_at: Some(chrono::Utc::now()),
+            publish_at: None,
         }
     }
+
+    /// Неопубликованный пост виден только автору и модераторам,
+    /// токену доступа для этого нужно право `posts:read`
+    pub fn is_visible_to(&self, session: Option<&AccessSession>) -> bool {
+        if self.status == PostStatus::Published {
+            return true;
+        }
+        session.is_some_and(|session| {
+            session.require_scope(TokenScope::PostsRead).is_ok()
+                && session.ensure_can_modify(self.author_id).is_ok()
+        })
+    }
 }
 
 impl TryFrom<crate::blog_grpc::Post> for Post {
-    type Error = crate::domain::error::BlogError;
+    type Error = BlogError;
     fn try_from(value: crate::blog_grpc::Post) -> Result<Self, Self::Error> {
         Ok(Self {
             id: value.id,
+            slug: value.slug,
             title: value.title,
             content: value.content,
+            content_format: value.content_format.parse()?,
+            content_html: value.content_html,
             author_id: Uuid::from_str(&value.author_id)
-                .map_err(|e| crate::domain::error::BlogError::Validation(e.to_string()))?,
-            created_at: chrono::DateTime::from_timestamp(value.created_at, 0).ok_or_else(|| {
-                crate::domain::error::BlogError::Validation(value.created_at.to_string())
-            })?,
+                .map_err(|e| BlogError::Validation(e.to_string()))?,
+            author: Json(
+                value
+                    .author
+                    .map(|author| AuthorSummary {
+                        username: author.username,
+                        display_name: author.display_name,
+                    })
+                    .unwrap_or_default(),
+            ),
+            reactions: Json(
+                value
+                    .reactions
+                    .into_iter()
+                    .map(|(kind, count)| Ok((kind.parse::<ReactionKind>()?, count)))
+                    .collect::<Result<_, BlogError>>()?,
+            ),
+            bookmarked: value.bookmarked,
+            created_at: chrono::DateTime::from_timestamp(value.created_at, 0)
+                .ok_or_else(|| BlogError::Validation(value.created_at.to_string()))?,
             updated_at: chrono::DateTime::from_timestamp(value.created_at, 0),
+            tags: value.tags,
+            status: value.status.parse()?,
+            published_at: value
+                .published_at
+                .and_then(|e| chrono::DateTime::from_timestamp(e, 0)),
+            publish_at: value
+                .publish_at
+                .and_then(|e| chrono::DateTime::from_timestamp(e, 0)),
         })
     }
 }
@@ -66,17 +262,103 @@ impl From<Post> for crate::blog_grpc::Post {
             title: value.title,
             content: value.content,
             author_id: value.author_id.to_string(),
+            author: Some(crate::blog_grpc::PostAuthor {
+                username: value.author.0.username,
+                display_name: value.author.0.display_name,
+            }),
+            reactions: counts_to_grpc(&value.reactions),
+            bookmarked: value.bookmarked,
             created_at: value.created_at.timestamp(),
             updated_at: value.updated_at.map(|e| e.timestamp()),
+            tags: value.tags,
+            status: value.status.as_str().to_string(),
+            published_at: value.published_at.map(|e| e.timestamp()),
+            publish_at: value.publish_at.map(|e| e.timestamp()),
+            slug: value.slug,
+            content_format: value.content_format.as_str().to_string(),
+            content_html: value.content_html,
         }
     }
 }
 
-impl From<crate::blog_grpc::CreatePostRequest> for CreatePost {
-    fn from(value: crate::blog_grpc::CreatePostRequest) -> Self {
-        Self {
+impl TryFrom<crate::blog_grpc::CreatePostRequest> for CreatePost {
+    type Error = BlogError;
+    fn try_from(value: crate::blog_grpc::CreatePostRequest) -> Result<Self, Self::Error> {
+        Ok(Self {
             title: value.title,
             content: value.content,
+            content_format: value
+                .content_format
+                .map(|f| f.parse())
+                .transpose()?
+                .unwrap_or_default(),
+            tags: value.tags,
+            draft: value.draft,
+            publish_at: value
+                .publish_at
+                .and_then(|e| chrono::DateTime::from_timestamp(e, 0)),
+        })
+    }
+}
+
+impl From<TagCount> for crate::blog_grpc::Tag {
+    fn from(value: TagCount) -> Self {
+        Self {
+            name: value.name,
+            post_count: value.post_count,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::role::Role;
+
+    #[test]
+    fn test_normalize_tags() {
+        let tags = vec![
+            " Rust ".to_string(),
+            "wasm".to_string(),
+            "rust".to_string(),
+            "".to_string(),
+        ];
+
+        let tags = normalize_tags(tags).expect("Failed to normalize tags");
+
+        assert_eq!(tags, vec!["rust".to_string(), "wasm".to_string()]);
+    }
+
+    #[test]
+    fn test_normalize_tags_too_long() {
+        let result = normalize_tags(vec!["a".repeat(MAX_TAG_LEN + 1)]);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_post_visibility() {
+        let author_id = Uuid::new_v4();
+        let session = |user_id, role, scopes| AccessSession {
+            user_id,
+            jti: Uuid::new_v4(),
+            expires_at: chrono::Utc::now(),
+            email_verified: true,
+            role,
+            scopes,
+        };
+        let mut post = Post::new(1, "Title".to_string(), "text".to_string(), author_id);
+        assert!(post.is_visible_to(None));
+
+        post.status = PostStatus::Draft;
+        assert!(!post.is_visible_to(None));
+        assert!(post.is_visible_to(Some(&session(author_id, Role::Author, None))));
+        assert!(!post.is_visible_to(Some(&session(Uuid::new_v4(), Role::Author, None))));
+        assert!(post.is_visible_to(Some(&session(Uuid::new_v4(), Role::Moderator, None))));
+        assert!(!post.is_visible_to(Some(&session(
+            author_id,
+            Role::Author,
+            Some(vec![TokenScope::CommentsWrite])
+        ))));
+    }
+}
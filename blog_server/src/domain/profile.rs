@@ -0,0 +1,186 @@
+use serde::{Deserialize, Serialize};
+use serde_with::{TimestampSeconds, serde_as};
+use uuid::Uuid;
+
+use crate::domain::error::{BlogError, FieldError};
+
+const MAX_DISPLAY_NAME_LEN: usize = 64;
+const MAX_BIO_LEN: usize = 1000;
+const MAX_URL_LEN: usize = 500;
+const MAX_LINKS: usize = 5;
+
+/// Загруженные в блог файлы можно использовать как аватар без внешнего адреса
+const ATTACHMENT_PATH_PREFIX: &str = "/api/attachments/";
+
+/// Публичный профиль пользователя, email и прочие закрытые данные сюда не попадают
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UserProfile {
+    pub id: Uuid,
+    pub username: String,
+    pub display_name: Option<String>,
+    pub bio: Option<String>,
+    pub avatar_url: Option<String>,
+    pub links: Vec<String>,
+    #[serde_as(as = "TimestampSeconds<i64>")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Автор в ответах с постами, чтобы клиенту не нужен был отдельный запрос профиля
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuthorSummary {
+    pub username: String,
+    pub display_name: Option<String>,
+}
+
+/// Профиль заменяется целиком: не переданное поле очищается
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct UpdateProfile {
+    pub display_name: Option<String>,
+    pub bio: Option<String>,
+    pub avatar_url: Option<String>,
+    #[serde(default)]
+    pub links: Vec<String>,
+}
+
+impl UpdateProfile {
+    /// Обрезает пробелы, пустые строки считает незаполненными полями
+    pub fn normalize(self) -> Self {
+        let non_empty = |value: Option<String>| {
+            value
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        let mut links: Vec<String> = Vec::new();
+        for link in self.links {
+            let link = link.trim().to_string();
+            if !link.is_empty() && !links.contains(&link) {
+                links.push(link);
+            }
+        }
+
+        Self {
+            display_name: non_empty(self.display_name),
+            bio: non_empty(self.bio),
+            avatar_url: non_empty(self.avatar_url),
+            links,
+        }
+    }
+
+    /// Проверяет все поля сразу, как и регистрация
+    pub fn validate(&self) -> Result<(), BlogError> {
+        let mut errors = Vec::new();
+        if let Some(name) = &self.display_name
+            && (name.chars().count() > MAX_DISPLAY_NAME_LEN || name.chars().any(char::is_control))
+        {
+            errors.push(FieldError::new(
+                "display_name",
+                format!(
+                    "must be at most {} characters without control characters",
+                    MAX_DISPLAY_NAME_LEN
+                ),
+            ));
+        }
+        if let Some(bio) = &self.bio
+            && bio.chars().count() > MAX_BIO_LEN
+        {
+            errors.push(FieldError::new(
+                "bio",
+                format!("must be at most {} characters long", MAX_BIO_LEN),
+            ));
+        }
+        if let Some(avatar_url) = &self.avatar_url
+            && !(is_web_url(avatar_url)
+                || avatar_url.starts_with(ATTACHMENT_PATH_PREFIX)
+                    && avatar_url.len() <= MAX_URL_LEN)
+        {
+            errors.push(FieldError::new(
+                "avatar_url",
+                "must be an http(s) URL or an attachment path",
+            ));
+        }
+        if self.links.len() > MAX_LINKS {
+            errors.push(FieldError::new(
+                "links",
+                format!("at most {} links allowed", MAX_LINKS),
+            ));
+        } else if !self.links.iter().all(|link| is_web_url(link)) {
+            errors.push(FieldError::new("links", "each link must be an http(s) URL"));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(BlogError::InvalidFields(errors))
+        }
+    }
+}
+
+impl From<UserProfile> for crate::blog_grpc::UserProfile {
+    fn from(value: UserProfile) -> Self {
+        Self {
+            id: value.id.to_string(),
+            username: value.username,
+            display_name: value.display_name,
+            bio: value.bio,
+            avatar_url: value.avatar_url,
+            links: value.links,
+            created_at: value.created_at.timestamp(),
+        }
+    }
+}
+
+impl From<crate::blog_grpc::UpdateProfileRequest> for UpdateProfile {
+    fn from(value: crate::blog_grpc::UpdateProfileRequest) -> Self {
+        Self {
+            display_name: value.display_name,
+            bio: value.bio,
+            avatar_url: value.avatar_url,
+            links: value.links,
+        }
+    }
+}
+
+fn is_web_url(value: &str) -> bool {
+    let rest = value
+        .strip_prefix("https://")
+        .or_else(|| value.strip_prefix("http://"));
+    value.len() <= MAX_URL_LEN
+        && !value.chars().any(|c| c.is_whitespace() || c.is_control())
+        && rest.is_some_and(|rest| !rest.is_empty() && !rest.starts_with('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_profile_validation() {
+        let profile = UpdateProfile {
+            display_name: Some("  Алиса  ".to_string()),
+            bio: Some(" ".to_string()),
+            avatar_url: Some("/api/attachments/42".to_string()),
+            links: vec![
+                "https://example.com".to_string(),
+                " https://example.com ".to_string(),
+                String::new(),
+            ],
+        }
+        .normalize();
+        assert_eq!(profile.display_name.as_deref(), Some("Алиса"));
+        assert_eq!(profile.bio, None);
+        assert_eq!(profile.links, ["https://example.com"]);
+        assert!(profile.validate().is_ok());
+
+        let Err(BlogError::InvalidFields(errors)) = UpdateProfile {
+            avatar_url: Some("javascript:alert(1)".to_string()),
+            links: vec!["ftp://example.com".to_string()],
+            ..Default::default()
+        }
+        .validate() else {
+            panic!("expected field errors");
+        };
+        let fields: Vec<_> = errors.iter().map(|e| e.field).collect();
+        assert_eq!(fields, ["avatar_url", "links"]);
+    }
+}
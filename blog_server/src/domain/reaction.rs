@@ -0,0 +1,130 @@
+use std::{collections::BTreeMap, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::error::BlogError;
+
+/// Вид реакции на пост
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, sqlx::Type,
+)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "reaction_kind", rename_all = "lowercase")]
+pub enum ReactionKind {
+    Like,
+    Love,
+    Laugh,
+    Wow,
+}
+
+impl ReactionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReactionKind::Like => "like",
+            ReactionKind::Love => "love",
+            ReactionKind::Laugh => "laugh",
+            ReactionKind::Wow => "wow",
+        }
+    }
+}
+
+impl FromStr for ReactionKind {
+    type Err = BlogError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "like" => Ok(ReactionKind::Like),
+            "love" => Ok(ReactionKind::Love),
+            "laugh" => Ok(ReactionKind::Laugh),
+            "wow" => Ok(ReactionKind::Wow),
+            other => Err(BlogError::Validation(format!(
+                "unknown reaction: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// Количество реакций каждого вида. Виды без реакций не попадают в карту
+pub type ReactionCounts = BTreeMap<ReactionKind, i64>;
+
+/// Реакции поста после изменения: общие счётчики и реакции текущего пользователя
+#[derive(Debug, Serialize)]
+pub struct PostReactions {
+    pub post_id: i64,
+    pub counts: ReactionCounts,
+    pub reacted: Vec<ReactionKind>,
+}
+
+/// Порядок ленты опубликованных постов
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PostOrder {
+    /// От новых к старым
+    #[default]
+    Latest,
+    /// По числу реакций `like`, при равенстве от новых к старым. Остальные виды
+    /// реакций на порядок не влияют, хотя и приходят в ответе.
+    /// Листается только по смещению
+    Liked,
+}
+
+impl PostOrder {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PostOrder::Latest => "latest",
+            PostOrder::Liked => "liked",
+        }
+    }
+}
+
+impl FromStr for PostOrder {
+    type Err = BlogError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "" | "latest" => Ok(PostOrder::Latest),
+            "liked" => Ok(PostOrder::Liked),
+            other => Err(BlogError::Validation(format!("unknown order: {}", other))),
+        }
+    }
+}
+
+/// Счётчики в gRPC передаются картой с именами видов в ключах
+pub fn counts_to_grpc(counts: &ReactionCounts) -> std::collections::HashMap<String, i64> {
+    counts
+        .iter()
+        .map(|(kind, count)| (kind.as_str().to_string(), *count))
+        .collect()
+}
+
+impl From<PostReactions> for crate::blog_grpc::PostReactions {
+    fn from(value: PostReactions) -> Self {
+        Self {
+            post_id: value.post_id,
+            counts: counts_to_grpc(&value.counts),
+            reacted: value
+                .reacted
+                .iter()
+                .map(|kind| kind.as_str().to_string())
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reaction_counts_json() {
+        let counts: ReactionCounts =
+            serde_json::from_str(r#"{"like": 3, "wow": 1}"#).expect("valid counts");
+        assert_eq!(counts.get(&ReactionKind::Like), Some(&3));
+        assert_eq!(
+            serde_json::to_string(&counts).expect("serializable"),
+            r#"{"like":3,"wow":1}"#
+        );
+        assert!("dislike".parse::<ReactionKind>().is_err());
+    }
+}
@@ -0,0 +1,132 @@
+use serde::{Deserialize, Serialize};
+use serde_with::{TimestampSeconds, serde_as};
+use similar::{ChangeTag, TextDiff};
+use uuid::Uuid;
+
+/// Состояние поста до очередного обновления
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PostRevision {
+    pub id: i64,
+    pub post_id: i64,
+    /// Порядковый номер ревизии в пределах поста, начиная с 1
+    pub revision: i32,
+    /// Кто внёс изменение. `None`, если пользователь удалён
+    pub editor_id: Option<Uuid>,
+    pub title: String,
+    pub content: String,
+    #[serde_as(as = "TimestampSeconds<i64>")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffKind {
+    Equal,
+    Insert,
+    Delete,
+}
+
+impl DiffKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiffKind::Equal => "equal",
+            DiffKind::Insert => "insert",
+            DiffKind::Delete => "delete",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffLine {
+    pub kind: DiffKind,
+    pub text: String,
+}
+
+/// Построчная разница между ревизией `from` и ревизией `to`.
+/// `to == None` означает текущую версию поста
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RevisionDiff {
+    pub post_id: i64,
+    pub from: i32,
+    pub to: Option<i32>,
+    pub title: Vec<DiffLine>,
+    pub content: Vec<DiffLine>,
+}
+
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    TextDiff::from_lines(old, new)
+        .iter_all_changes()
+        .map(|change| DiffLine {
+            kind: match change.tag() {
+                ChangeTag::Equal => DiffKind::Equal,
+                ChangeTag::Insert => DiffKind::Insert,
+                ChangeTag::Delete => DiffKind::Delete,
+            },
+            text: change.value().trim_end_matches('\n').to_string(),
+        })
+        .collect()
+}
+
+impl From<PostRevision> for crate::blog_grpc::PostRevision {
+    fn from(value: PostRevision) -> Self {
+        Self {
+            id: value.id,
+            post_id: value.post_id,
+            revision: value.revision,
+            editor_id: value.editor_id.map(|e| e.to_string()),
+            title: value.title,
+            content: value.content,
+            created_at: value.created_at.timestamp(),
+        }
+    }
+}
+
+impl From<DiffLine> for crate::blog_grpc::DiffLine {
+    fn from(value: DiffLine) -> Self {
+        Self {
+            kind: value.kind.as_str().to_string(),
+            text: value.text,
+        }
+    }
+}
+
+impl From<RevisionDiff> for crate::blog_grpc::RevisionDiff {
+    fn from(value: RevisionDiff) -> Self {
+        Self {
+            post_id: value.post_id,
+            from: value.from,
+            to: value.to,
+            title: value.title.into_iter().map(|l| l.into()).collect(),
+            content: value.content.into_iter().map(|l| l.into()).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_lines() {
+        let diff = diff_lines("first\nsecond\n", "first\nthird\n");
+
+        assert_eq!(
+            diff,
+            vec![
+                DiffLine {
+                    kind: DiffKind::Equal,
+                    text: "first".to_string()
+                },
+                DiffLine {
+                    kind: DiffKind::Delete,
+                    text: "second".to_string()
+                },
+                DiffLine {
+                    kind: DiffKind::Insert,
+                    text: "third".to_string()
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,80 @@
+use serde::Serialize;
+
+use crate::domain::{error::BlogError, post::Post};
+
+/// Маркеры подсветки, которые Postgres вставляет в фрагмент.
+/// Управляющие символы не встречаются в тексте постов и не конфликтуют с HTML
+pub const HIGHLIGHT_START: char = '\u{2}';
+pub const HIGHLIGHT_STOP: char = '\u{3}';
+
+const MAX_QUERY_LEN: usize = 200;
+
+#[derive(Debug, Serialize)]
+pub struct SearchHit {
+    pub post: Post,
+    pub rank: f32,
+    /// Фрагмент текста с найденными словами в `<mark>`, остальное экранировано
+    pub snippet: String,
+}
+
+#[derive(Debug)]
+pub struct SearchResults {
+    pub hits: Vec<SearchHit>,
+    pub total: i64,
+}
+
+pub fn normalize_query(query: &str) -> Result<String, BlogError> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Err(BlogError::Validation("search query is empty".to_string()));
+    }
+    if query.chars().count() > MAX_QUERY_LEN {
+        return Err(BlogError::Validation(format!(
+            "search query is longer than {} characters",
+            MAX_QUERY_LEN
+        )));
+    }
+    Ok(query.to_string())
+}
+
+/// Экранирует фрагмент и заменяет маркеры подсветки на `<mark>`
+pub fn highlight_snippet(raw: &str) -> String {
+    let mut snippet = String::with_capacity(raw.len() + 16);
+    for c in raw.chars() {
+        match c {
+            HIGHLIGHT_START => snippet.push_str("<mark>"),
+            HIGHLIGHT_STOP => snippet.push_str("</mark>"),
+            '&' => snippet.push_str("&amp;"),
+            '<' => snippet.push_str("&lt;"),
+            '>' => snippet.push_str("&gt;"),
+            '"' => snippet.push_str("&quot;"),
+            c => snippet.push(c),
+        }
+    }
+    snippet
+}
+
+impl From<SearchHit> for crate::blog_grpc::SearchHit {
+    fn from(value: SearchHit) -> Self {
+        Self {
+            post: Some(value.post.into()),
+            rank: value.rank,
+            snippet: value.snippet,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_highlight_snippet() {
+        let raw = format!("a <b> {}rust{} & c", HIGHLIGHT_START, HIGHLIGHT_STOP);
+
+        assert_eq!(
+            highlight_snippet(&raw),
+            "a &lt;b&gt; <mark>rust</mark> &amp; c"
+        );
+    }
+}
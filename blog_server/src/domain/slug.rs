@@ -0,0 +1,104 @@
+/// Максимальная длина slug без числового суффикса
+const MAX_SLUG_LEN: usize = 80;
+const EMPTY_SLUG: &str = "post";
+
+/// Строит slug из заголовка: кириллица транслитерируется,
+/// всё, кроме латинских букв и цифр, заменяется на `-`
+pub fn slugify(title: &str) -> String {
+    let mut slug = String::with_capacity(title.len());
+    for c in title.chars().flat_map(char::to_lowercase) {
+        match transliterate(c) {
+            Some(latin) => slug.push_str(latin),
+            None if c.is_ascii_alphanumeric() => slug.push(c),
+            None => {
+                if !slug.is_empty() && !slug.ends_with('-') {
+                    slug.push('-');
+                }
+            }
+        }
+        if slug.len() >= MAX_SLUG_LEN {
+            break;
+        }
+    }
+
+    slug.truncate(MAX_SLUG_LEN);
+    let slug = slug.trim_matches('-');
+    if slug.is_empty() {
+        EMPTY_SLUG.to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+/// Первый свободный вариант: `base`, `base-2`, `base-3`, ...
+pub fn pick_free_slug(base: &str, taken: &[String]) -> String {
+    if !taken.iter().any(|s| s == base) {
+        return base.to_string();
+    }
+
+    (2..)
+        .map(|n| format!("{}-{}", base, n))
+        .find(|candidate| !taken.iter().any(|s| s == candidate))
+        .unwrap_or_else(|| base.to_string())
+}
+
+fn transliterate(c: char) -> Option<&'static str> {
+    let latin = match c {
+        'а' => "a",
+        'б' => "b",
+        'в' => "v",
+        'г' | 'ґ' => "g",
+        'д' => "d",
+        'е' | 'ё' | 'э' => "e",
+        'є' => "ye",
+        'ж' => "zh",
+        'з' => "z",
+        'и' | 'і' => "i",
+        'ї' => "yi",
+        'й' | 'ы' => "y",
+        'к' => "k",
+        'л' => "l",
+        'м' => "m",
+        'н' => "n",
+        'о' => "o",
+        'п' => "p",
+        'р' => "r",
+        'с' => "s",
+        'т' => "t",
+        'у' => "u",
+        'ф' => "f",
+        'х' => "h",
+        'ц' => "ts",
+        'ч' => "ch",
+        'ш' => "sh",
+        'щ' => "sch",
+        'ъ' | 'ь' => "",
+        'ю' => "yu",
+        'я' => "ya",
+        _ => return None,
+    };
+    Some(latin)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Привет, мир!"), "privet-mir");
+        assert_eq!(
+            slugify("Rust и WebAssembly: часть 2"),
+            "rust-i-webassembly-chast-2"
+        );
+        assert_eq!(slugify("  ?!  "), "post");
+    }
+
+    #[test]
+    fn test_pick_free_slug() {
+        let taken = vec!["hello".to_string(), "hello-2".to_string()];
+
+        assert_eq!(pick_free_slug("hello", &taken), "hello-3");
+        assert_eq!(pick_free_slug("world", &taken), "world");
+    }
+}
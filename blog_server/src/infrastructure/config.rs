@@ -1,5 +1,7 @@
 use serde::Deserialize;
 
+use super::RateLimitConfig;
+
 const MIN_JWT_KEY_LEN: usize = 32;
 
 #[derive(Debug, Deserialize, Clone)]
@@ -7,10 +9,41 @@ pub struct AppConfig {
     pub http_addr: String,
     pub grpc_addr: String,
     pub database_url: String,
-    pub jwt_secret: String,
+    /// Общий секрет HS256. При заданных `jwt_keys` нужен только для старых токенов
+    pub jwt_secret: Option<String>,
+    /// Ключи подписи: идентификатор (`kid`) и путь к PEM файлу
+    pub jwt_keys: Vec<(String, String)>,
+    /// Каким ключом подписывать новые токены, по умолчанию первым закрытым
+    pub jwt_signing_kid: Option<String>,
+    /// Время жизни access токена, в минутах
     pub jwt_expiration: i64,
+    /// Время жизни refresh токена, в днях
+    pub refresh_expiration_days: i64,
     #[serde(default)]
     pub cors_origins: Vec<String>,
+    /// Период проверки запланированных постов, в секундах, не меньше 1
+    pub publish_interval: u64,
+    /// Каталог для загруженных файлов
+    pub upload_dir: String,
+    /// Максимальный размер загружаемого файла, в байтах
+    pub upload_max_size: usize,
+    /// Адрес сайта для ссылок в письмах
+    pub public_url: String,
+    /// Отправитель писем
+    pub mail_from: String,
+    /// SMTP сервер. Если не задан, письма складываются файлами в `mail_dir`
+    pub smtp_host: Option<String>,
+    pub smtp_port: u16,
+    pub smtp_username: Option<String>,
+    pub smtp_password: Option<String>,
+    pub mail_dir: String,
+    /// Публиковать посты можно только с подтверждённым email
+    pub require_verified_email: bool,
+    /// Зарегистрированный пользователь, который при старте станет администратором,
+    /// если администратора ещё нет
+    pub bootstrap_admin: Option<String>,
+    /// Ограничения частоты входа и регистрации
+    pub rate_limit: RateLimitConfig,
 }
 
 impl AppConfig {
@@ -28,37 +61,152 @@ impl AppConfig {
             .map_err(|e| anyhow::anyhow!("invalid HTTP_PORT: {}", e))?;
         let database_url = std::env::var("DATABASE_URL")
             .map_err(|_| anyhow::anyhow!("DATABASE_URL must be set"))?;
-        let jwt_secret = std::env::var("JWT_SECRET")
-            .map_err(|_| anyhow::anyhow!("JWT_SECRET must be set"))
-            .and_then(|secret| {
-                if secret.len() >= MIN_JWT_KEY_LEN {
-                    Ok(secret)
-                } else {
-                    Err(anyhow::anyhow!(
-                        "JWT_SECRET must be at least {} characters long, got {}",
-                        MIN_JWT_KEY_LEN,
-                        secret.len()
-                    ))
-                }
-            })?;
+        let jwt_keys = std::env::var("JWT_KEYS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .map(|entry| {
+                entry
+                    .split_once('=')
+                    .map(|(kid, path)| (kid.trim().to_string(), path.trim().to_string()))
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("invalid JWT_KEYS entry {}, expected kid=path", entry)
+                    })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let jwt_signing_kid = std::env::var("JWT_SIGNING_KID")
+            .ok()
+            .filter(|s| !s.is_empty());
+        let jwt_secret = match std::env::var("JWT_SECRET") {
+            Ok(secret) if secret.len() >= MIN_JWT_KEY_LEN => Some(secret),
+            Ok(secret) => {
+                return Err(anyhow::anyhow!(
+                    "JWT_SECRET must be at least {} characters long, got {}",
+                    MIN_JWT_KEY_LEN,
+                    secret.len()
+                ));
+            }
+            Err(_) if jwt_keys.is_empty() => {
+                return Err(anyhow::anyhow!("JWT_SECRET or JWT_KEYS must be set"));
+            }
+            Err(_) => None,
+        };
         let jwt_expiration = std::env::var("JWT_EXPIRATION")
-            .unwrap_or_else(|_| "1440".into())
+            .unwrap_or_else(|_| "15".into())
             .parse()
             .map_err(|e| anyhow::anyhow!("invalid JWT_EXPIRATION: {}", e))?;
+        let refresh_expiration_days = std::env::var("REFRESH_EXPIRATION_DAYS")
+            .unwrap_or_else(|_| "30".into())
+            .parse()
+            .map_err(|e| anyhow::anyhow!("invalid REFRESH_EXPIRATION_DAYS: {}", e))?;
         let cors_origins = std::env::var("CORS_ORIGINS")
             .unwrap_or_else(|_| "*".into())
             .split(',')
             .map(|s| s.trim().to_string())
             .filter(|s| !s.is_empty())
             .collect();
+        let publish_interval = parse_publish_interval(
+            &std::env::var("PUBLISH_INTERVAL").unwrap_or_else(|_| "30".into()),
+        )?;
+        let upload_dir = std::env::var("UPLOAD_DIR").unwrap_or_else(|_| "uploads".into());
+        let upload_max_size = std::env::var("UPLOAD_MAX_SIZE")
+            .unwrap_or_else(|_| "10485760".into())
+            .parse()
+            .map_err(|e| anyhow::anyhow!("invalid UPLOAD_MAX_SIZE: {}", e))?;
+        let public_url =
+            std::env::var("PUBLIC_URL").unwrap_or_else(|_| "http://127.0.0.1:8080".into());
+        let mail_from =
+            std::env::var("MAIL_FROM").unwrap_or_else(|_| "Blog <noreply@localhost>".into());
+        let smtp_host = std::env::var("SMTP_HOST").ok().filter(|s| !s.is_empty());
+        let smtp_port = std::env::var("SMTP_PORT")
+            .unwrap_or_else(|_| "587".into())
+            .parse()
+            .map_err(|e| anyhow::anyhow!("invalid SMTP_PORT: {}", e))?;
+        let smtp_username = std::env::var("SMTP_USERNAME").ok();
+        let smtp_password = std::env::var("SMTP_PASSWORD").ok();
+        let mail_dir = std::env::var("MAIL_DIR").unwrap_or_else(|_| "mail".into());
+        let require_verified_email = std::env::var("REQUIRE_VERIFIED_EMAIL")
+            .unwrap_or_else(|_| "false".into())
+            .parse()
+            .map_err(|e| anyhow::anyhow!("invalid REQUIRE_VERIFIED_EMAIL: {}", e))?;
+        let bootstrap_admin = std::env::var("BOOTSTRAP_ADMIN")
+            .ok()
+            .filter(|s| !s.is_empty());
+        let rate_limit_default = RateLimitConfig::default();
+        let rate_limit = RateLimitConfig {
+            ip_per_minute: env_or("RATE_LIMIT_IP_PER_MINUTE", rate_limit_default.ip_per_minute)?,
+            username_per_minute: env_or(
+                "RATE_LIMIT_USERNAME_PER_MINUTE",
+                rate_limit_default.username_per_minute,
+            )?,
+            lockout_threshold: env_or("LOCKOUT_THRESHOLD", rate_limit_default.lockout_threshold)?,
+            lockout_base_secs: env_or("LOCKOUT_BASE_SECS", rate_limit_default.lockout_base_secs)?,
+            lockout_max_secs: env_or("LOCKOUT_MAX_SECS", rate_limit_default.lockout_max_secs)?,
+        };
 
         Ok(Self {
             http_addr: format!("{}:{}", host, http_port),
             grpc_addr: format!("{}:{}", host, grpc_port),
             database_url,
             jwt_secret,
+            jwt_keys,
+            jwt_signing_kid,
             jwt_expiration,
+            refresh_expiration_days,
             cors_origins,
+            publish_interval,
+            upload_dir,
+            upload_max_size,
+            public_url,
+            mail_from,
+            smtp_host,
+            smtp_port,
+            smtp_username,
+            smtp_password,
+            mail_dir,
+            require_verified_email,
+            bootstrap_admin,
+            rate_limit,
         })
     }
 }
+
+fn env_or<T>(name: &str, default: T) -> anyhow::Result<T>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    match std::env::var(name) {
+        Ok(value) => value
+            .parse()
+            .map_err(|e| anyhow::anyhow!("invalid {}: {}", name, e)),
+        Err(_) => Ok(default),
+    }
+}
+
+/// Нулевой период таймер tokio не принимает, поэтому он отклоняется при старте
+fn parse_publish_interval(value: &str) -> anyhow::Result<u64> {
+    let interval: u64 = value
+        .parse()
+        .map_err(|e| anyhow::anyhow!("invalid PUBLISH_INTERVAL: {}", e))?;
+    if interval == 0 {
+        return Err(anyhow::anyhow!(
+            "invalid PUBLISH_INTERVAL: must be at least 1"
+        ));
+    }
+    Ok(interval)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_publish_interval() {
+        assert_eq!(parse_publish_interval("30").unwrap(), 30);
+        assert!(parse_publish_interval("0").is_err());
+        assert!(parse_publish_interval("-1").is_err());
+        assert!(parse_publish_interval("soon").is_err());
+    }
+}
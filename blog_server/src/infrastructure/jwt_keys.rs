@@ -0,0 +1,118 @@
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use jsonwebtoken::{
+    Algorithm, DecodingKey, EncodingKey,
+    jwk::{
+        AlgorithmParameters, CommonParameters, EllipticCurve, Jwk, KeyAlgorithm,
+        OctetKeyPairParameters, OctetKeyPairType, PublicKeyUse, RSAKeyParameters, RSAKeyType,
+    },
+};
+use rsa::{
+    RsaPrivateKey, RsaPublicKey,
+    pkcs1::{DecodeRsaPrivateKey, DecodeRsaPublicKey},
+    pkcs8::{DecodePrivateKey, DecodePublicKey},
+    traits::PublicKeyParts,
+};
+
+/// Ключ подписи access токенов. Алгоритм определяется по типу ключа:
+/// RSA - RS256, Ed25519 - EdDSA
+pub struct JwtKey {
+    pub kid: String,
+    pub algorithm: Algorithm,
+    /// Нет у ключей из публичного PEM: они только проверяют токены до конца ротации
+    pub encoding: Option<EncodingKey>,
+    pub decoding: DecodingKey,
+    /// Публичная часть для `/.well-known/jwks.json`
+    pub jwk: Jwk,
+}
+
+impl JwtKey {
+    /// Принимает закрытый ключ (PKCS#8 или PKCS#1) или открытый (SPKI или PKCS#1)
+    pub fn from_pem(kid: &str, pem: &str) -> anyhow::Result<Self> {
+        if let Ok(key) =
+            RsaPrivateKey::from_pkcs8_pem(pem).or_else(|_| RsaPrivateKey::from_pkcs1_pem(pem))
+        {
+            let encoding = EncodingKey::from_rsa_pem(pem.as_bytes())?;
+            return Self::rsa(kid, &key.to_public_key(), Some(encoding));
+        }
+        if let Ok(key) =
+            RsaPublicKey::from_public_key_pem(pem).or_else(|_| RsaPublicKey::from_pkcs1_pem(pem))
+        {
+            return Self::rsa(kid, &key, None);
+        }
+        if let Ok(key) = ed25519_dalek::SigningKey::from_pkcs8_pem(pem) {
+            let encoding = EncodingKey::from_ed_pem(pem.as_bytes())?;
+            return Self::ed25519(kid, &key.verifying_key(), Some(encoding));
+        }
+        if let Ok(key) = ed25519_dalek::VerifyingKey::from_public_key_pem(pem) {
+            return Self::ed25519(kid, &key, None);
+        }
+
+        anyhow::bail!("JWT key {}: expected RSA or Ed25519 key in PEM", kid)
+    }
+
+    fn rsa(kid: &str, key: &RsaPublicKey, encoding: Option<EncodingKey>) -> anyhow::Result<Self> {
+        let parameters = AlgorithmParameters::RSA(RSAKeyParameters {
+            key_type: RSAKeyType::RSA,
+            n: URL_SAFE_NO_PAD.encode(key.n().to_bytes_be()),
+            e: URL_SAFE_NO_PAD.encode(key.e().to_bytes_be()),
+        });
+        Self::new(
+            kid,
+            Algorithm::RS256,
+            KeyAlgorithm::RS256,
+            parameters,
+            encoding,
+        )
+    }
+
+    fn ed25519(
+        kid: &str,
+        key: &ed25519_dalek::VerifyingKey,
+        encoding: Option<EncodingKey>,
+    ) -> anyhow::Result<Self> {
+        let parameters = AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+            key_type: OctetKeyPairType::OctetKeyPair,
+            curve: EllipticCurve::Ed25519,
+            x: URL_SAFE_NO_PAD.encode(key.as_bytes()),
+        });
+        Self::new(
+            kid,
+            Algorithm::EdDSA,
+            KeyAlgorithm::EdDSA,
+            parameters,
+            encoding,
+        )
+    }
+
+    fn new(
+        kid: &str,
+        algorithm: Algorithm,
+        key_algorithm: KeyAlgorithm,
+        parameters: AlgorithmParameters,
+        encoding: Option<EncodingKey>,
+    ) -> anyhow::Result<Self> {
+        let jwk = Jwk {
+            common: CommonParameters {
+                public_key_use: Some(PublicKeyUse::Signature),
+                key_algorithm: Some(key_algorithm),
+                key_id: Some(kid.to_string()),
+                ..Default::default()
+            },
+            algorithm: parameters,
+        };
+
+        Ok(Self {
+            kid: kid.to_string(),
+            algorithm,
+            encoding,
+            decoding: DecodingKey::from_jwk(&jwk)?,
+            jwk,
+        })
+    }
+
+    pub fn from_file(kid: &str, path: &str) -> anyhow::Result<Self> {
+        let pem = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("JWT key {}: cannot read {}: {}", kid, path, e))?;
+        Self::from_pem(kid, &pem)
+    }
+}
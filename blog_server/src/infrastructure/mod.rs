@@ -1,11 +1,28 @@
 mod config;
 mod database;
+mod jwt_keys;
 mod logging;
+mod rate_limit;
+mod revocation_cache;
 mod security;
+mod totp;
 
 pub use config::AppConfig;
 
-pub use security::{JwtService, password_hash, password_verify};
+pub use jwt_keys::JwtKey;
+
+pub use rate_limit::{RateLimitConfig, RateLimiter};
+
+pub use revocation_cache::RevocationCache;
+
+pub use security::{
+    DUMMY_PASSWORD_HASH, JwtService, generate_opaque_token, hash_opaque_token, password_hash,
+    password_verify,
+};
+
+pub use totp::{
+    generate_recovery_codes, generate_totp_secret, normalize_recovery_code, totp_uri, verify_totp,
+};
 
 pub use database::{create_pool, run_migrations};
 
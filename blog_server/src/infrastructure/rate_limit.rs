@@ -0,0 +1,294 @@
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use serde::Deserialize;
+
+/// После этого размера из хранилища вычищаются устаревшие записи
+const PURGE_THRESHOLD: usize = 10_000;
+/// Сколько помнить неудачные попытки входа без новых неудач
+const FAILURES_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+/// Ограничение степени удвоения блокировки, дальше всё равно срабатывает максимум
+const MAX_LOCKOUT_DOUBLINGS: u32 = 16;
+
+/// Корзина токенов: `capacity` запросов подряд, дальше `capacity` запросов в минуту
+#[derive(Debug, Clone, Copy)]
+pub struct RateRule {
+    pub capacity: u32,
+}
+
+impl RateRule {
+    pub fn per_minute(capacity: u32) -> Self {
+        Self { capacity }
+    }
+
+    fn tokens_per_second(&self) -> f64 {
+        self.capacity as f64 / 60.0
+    }
+}
+
+/// Хранилище счётчиков. Методы синхронные, потому что вызываются и из слоя gRPC сервера.
+/// Общее хранилище для нескольких экземпляров сервера реализует этот же трейт
+pub trait RateLimitStore: Send + Sync {
+    /// Забирает токен из корзины `key`, `Err` - через сколько появится следующий
+    fn take(&self, key: &str, rule: RateRule) -> Result<(), Duration>;
+
+    /// Добавляет неудачную попытку и возвращает их число подряд
+    fn add_failure(&self, key: &str, ttl: Duration) -> u32;
+
+    fn clear_failures(&self, key: &str);
+
+    fn lock(&self, key: &str, duration: Duration);
+
+    /// Сколько ещё продлится блокировка
+    fn locked_for(&self, key: &str) -> Option<Duration>;
+}
+
+#[derive(Default)]
+pub struct InMemoryRateLimitStore {
+    buckets: Mutex<HashMap<String, Bucket>>,
+    failures: Mutex<HashMap<String, Failures>>,
+}
+
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+    /// Когда корзина снова заполнится и запись станет не нужна
+    full_at: Instant,
+}
+
+struct Failures {
+    count: u32,
+    expires_at: Instant,
+    locked_until: Option<Instant>,
+}
+
+impl InMemoryRateLimitStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl RateLimitStore for InMemoryRateLimitStore {
+    fn take(&self, key: &str, rule: RateRule) -> Result<(), Duration> {
+        let now = Instant::now();
+        let capacity = rule.capacity as f64;
+        let rate = rule.tokens_per_second();
+
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.len() >= PURGE_THRESHOLD {
+            buckets.retain(|_, bucket| bucket.full_at > now);
+        }
+
+        let bucket = buckets.entry(key.to_string()).or_insert(Bucket {
+            tokens: capacity,
+            updated_at: now,
+            full_at: now,
+        });
+        let elapsed = now.duration_since(bucket.updated_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(capacity);
+        bucket.updated_at = now;
+
+        if bucket.tokens < 1.0 {
+            return Err(Duration::from_secs_f64((1.0 - bucket.tokens) / rate));
+        }
+
+        bucket.tokens -= 1.0;
+        bucket.full_at = now + Duration::from_secs_f64((capacity - bucket.tokens) / rate);
+        Ok(())
+    }
+
+    fn add_failure(&self, key: &str, ttl: Duration) -> u32 {
+        let now = Instant::now();
+        let mut failures = self.failures.lock().unwrap();
+        if failures.len() >= PURGE_THRESHOLD {
+            failures.retain(|_, entry| entry.expires_at > now);
+        }
+
+        let entry = failures.entry(key.to_string()).or_insert(Failures {
+            count: 0,
+            expires_at: now,
+            locked_until: None,
+        });
+        if entry.expires_at <= now {
+            entry.count = 0;
+        }
+        entry.count += 1;
+        entry.expires_at = now + ttl;
+        entry.count
+    }
+
+    fn clear_failures(&self, key: &str) {
+        self.failures.lock().unwrap().remove(key);
+    }
+
+    fn lock(&self, key: &str, duration: Duration) {
+        let now = Instant::now();
+        let mut failures = self.failures.lock().unwrap();
+        let entry = failures.entry(key.to_string()).or_insert(Failures {
+            count: 0,
+            expires_at: now,
+            locked_until: None,
+        });
+        entry.locked_until = Some(now + duration);
+        entry.expires_at = entry.expires_at.max(now + duration);
+    }
+
+    fn locked_for(&self, key: &str) -> Option<Duration> {
+        let now = Instant::now();
+        let failures = self.failures.lock().unwrap();
+        failures
+            .get(key)
+            .and_then(|entry| entry.locked_until)
+            .filter(|until| *until > now)
+            .map(|until| until - now)
+    }
+}
+
+/// Настройки ограничений для входа и регистрации. Ноль выключает ограничение
+#[derive(Debug, Deserialize, Clone)]
+pub struct RateLimitConfig {
+    /// Запросов в минуту к `/api/auth` и RPC входа с одного IP
+    pub ip_per_minute: u32,
+    /// Попыток входа в минуту для одного имени пользователя
+    pub username_per_minute: u32,
+    /// После скольких неверных паролей подряд аккаунт блокируется
+    pub lockout_threshold: u32,
+    /// Первая блокировка, в секундах. Каждая следующая неудача удваивает её
+    pub lockout_base_secs: u64,
+    /// Максимальная блокировка, в секундах
+    pub lockout_max_secs: u64,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            ip_per_minute: 20,
+            username_per_minute: 5,
+            lockout_threshold: 5,
+            lockout_base_secs: 60,
+            lockout_max_secs: 60 * 60,
+        }
+    }
+}
+
+/// Ограничение частоты запросов и блокировка аккаунта после подбора пароля
+pub struct RateLimiter {
+    store: Arc<dyn RateLimitStore>,
+    config: RateLimitConfig,
+}
+
+impl RateLimiter {
+    pub fn new(store: Arc<dyn RateLimitStore>, config: RateLimitConfig) -> Self {
+        Self { store, config }
+    }
+
+    pub fn in_memory(config: RateLimitConfig) -> Self {
+        Self::new(Arc::new(InMemoryRateLimitStore::new()), config)
+    }
+
+    /// `Err` - время до следующей разрешённой попытки
+    pub fn check_ip(&self, ip: IpAddr) -> Result<(), Duration> {
+        if self.config.ip_per_minute == 0 {
+            return Ok(());
+        }
+        self.store.take(
+            &format!("ip:{}", ip),
+            RateRule::per_minute(self.config.ip_per_minute),
+        )
+    }
+
+    /// Проверяется до пароля, чтобы заблокированный аккаунт нельзя было подбирать дальше
+    pub fn check_login(&self, username: &str) -> Result<(), Duration> {
+        let key = login_key(username);
+        if let Some(locked_for) = self.store.locked_for(&key) {
+            return Err(locked_for);
+        }
+        if self.config.username_per_minute == 0 {
+            return Ok(());
+        }
+        self.store.take(
+            &format!("user:{}", username.to_lowercase()),
+            RateRule::per_minute(self.config.username_per_minute),
+        )
+    }
+
+    /// Возвращает срок блокировки, если аккаунт заблокирован
+    pub fn login_failed(&self, username: &str) -> Option<Duration> {
+        if self.config.lockout_threshold == 0 {
+            return None;
+        }
+
+        let key = login_key(username);
+        let failures = self.store.add_failure(&key, FAILURES_TTL);
+        if failures < self.config.lockout_threshold {
+            return None;
+        }
+
+        let doublings = (failures - self.config.lockout_threshold).min(MAX_LOCKOUT_DOUBLINGS);
+        let duration = Duration::from_secs(
+            self.config
+                .lockout_base_secs
+                .saturating_mul(1 << doublings)
+                .min(self.config.lockout_max_secs),
+        );
+        self.store.lock(&key, duration);
+        Some(duration)
+    }
+
+    pub fn login_succeeded(&self, username: &str) {
+        self.store.clear_failures(&login_key(username));
+    }
+}
+
+fn login_key(username: &str) -> String {
+    format!("login:{}", username.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_bucket() {
+        let store = InMemoryRateLimitStore::new();
+        let rule = RateRule::per_minute(2);
+
+        assert!(store.take("a", rule).is_ok());
+        assert!(store.take("a", rule).is_ok());
+        let retry_after = store.take("a", rule).unwrap_err();
+        assert!(retry_after > Duration::from_secs(25) && retry_after <= Duration::from_secs(30));
+
+        assert!(store.take("b", rule).is_ok());
+    }
+
+    #[test]
+    fn test_progressive_lockout() {
+        let limiter = RateLimiter::in_memory(RateLimitConfig {
+            ip_per_minute: 0,
+            username_per_minute: 0,
+            lockout_threshold: 2,
+            lockout_base_secs: 60,
+            lockout_max_secs: 150,
+        });
+
+        assert_eq!(limiter.login_failed("Alice"), None);
+        assert!(limiter.check_login("alice").is_ok());
+        assert_eq!(limiter.login_failed("alice"), Some(Duration::from_secs(60)));
+        assert!(limiter.check_login("ALICE").is_err());
+        assert_eq!(
+            limiter.login_failed("alice"),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            limiter.login_failed("alice"),
+            Some(Duration::from_secs(150))
+        );
+
+        limiter.login_succeeded("alice");
+        assert!(limiter.check_login("alice").is_ok());
+    }
+}
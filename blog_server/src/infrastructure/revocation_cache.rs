@@ -0,0 +1,90 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use uuid::Uuid;
+
+/// После этого размера из кеша вычищаются устаревшие записи
+const PURGE_THRESHOLD: usize = 10_000;
+
+/// Кеш проверок отзыва access токенов по `jti`.
+/// Отзыв необратим, поэтому отозванный токен хранится до своего истечения,
+/// а "не отозван" - только `ttl`, чтобы заметить отзыв другим экземпляром сервера
+pub struct RevocationCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<Uuid, CacheEntry>>,
+}
+
+struct CacheEntry {
+    revoked: bool,
+    valid_until: Instant,
+}
+
+impl RevocationCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// `None` - в кеше нет актуальной записи, нужно спросить базу
+    pub fn get(&self, jti: Uuid) -> Option<bool> {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .get(&jti)
+            .filter(|entry| entry.valid_until > Instant::now())
+            .map(|entry| entry.revoked)
+    }
+
+    pub fn insert_active(&self, jti: Uuid) {
+        self.insert(jti, false, self.ttl);
+    }
+
+    /// `lifetime` - сколько ещё живёт сам токен
+    pub fn insert_revoked(&self, jti: Uuid, lifetime: Duration) {
+        self.insert(jti, true, lifetime);
+    }
+
+    fn insert(&self, jti: Uuid, revoked: bool, lifetime: Duration) {
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= PURGE_THRESHOLD {
+            entries.retain(|_, entry| entry.valid_until > now);
+        }
+        entries.insert(
+            jti,
+            CacheEntry {
+                revoked,
+                valid_until: now + lifetime,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_revocation_cache() {
+        let cache = RevocationCache::new(Duration::from_secs(60));
+        let active = Uuid::new_v4();
+        let revoked = Uuid::new_v4();
+        let expired = Uuid::new_v4();
+
+        cache.insert_active(active);
+        cache.insert_revoked(revoked, Duration::from_secs(60));
+        cache.insert_revoked(expired, Duration::ZERO);
+
+        assert_eq!(cache.get(active), Some(false));
+        assert_eq!(cache.get(revoked), Some(true));
+        assert_eq!(cache.get(expired), None);
+        assert_eq!(cache.get(Uuid::new_v4()), None);
+
+        cache.insert_revoked(active, Duration::from_secs(60));
+        assert_eq!(cache.get(active), Some(true));
+    }
+}
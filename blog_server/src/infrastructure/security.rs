@@ -1,33 +1,123 @@
+use argon2::password_hash::rand_core::RngCore;
 use argon2::{
     Argon2, PasswordVerifier,
     password_hash::{PasswordHash, PasswordHasher, SaltString, rand_core::OsRng},
 };
-use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use std::sync::Arc;
+
+use jsonwebtoken::{
+    DecodingKey, EncodingKey, Header, Validation, decode, decode_header, encode,
+    errors::{Error as JwtError, ErrorKind},
+    jwk::JwkSet,
+};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
+use super::{AppConfig, JwtKey};
+use crate::domain::role::Role;
+
+const OPAQUE_TOKEN_BYTES: usize = 32;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: String,
     pub exp: usize,
     pub iat: usize,
+    /// Идентификатор токена, по нему токен можно отозвать
+    pub jti: String,
+    pub role: Role,
 }
 
 #[derive(Clone)]
 pub struct JwtService {
-    secret: String,
+    keys: Arc<KeyRing>,
     expiration_minuts: i64,
 }
 
+struct KeyRing {
+    /// Общий секрет HS256, токены без `kid`
+    secret: Option<(EncodingKey, DecodingKey)>,
+    /// Ключи проверки по `kid`, новые токены подписывает `keys[signing]`
+    keys: Vec<JwtKey>,
+    signing: Option<usize>,
+    jwks: JwkSet,
+}
+
 impl JwtService {
     pub fn new(secret: String, expiration_minuts: i64) -> Self {
         Self {
-            secret,
+            keys: Arc::new(KeyRing {
+                secret: Some(secret_keys(&secret)),
+                keys: Vec::new(),
+                signing: None,
+                jwks: JwkSet { keys: Vec::new() },
+            }),
             expiration_minuts,
         }
     }
 
-    pub fn generate_token(&self, user_id: Uuid) -> Result<String, jsonwebtoken::errors::Error> {
+    /// Ключи из `JWT_KEYS`, без них - общий секрет `JWT_SECRET`
+    pub fn from_config(config: &AppConfig) -> anyhow::Result<Self> {
+        if config.jwt_keys.is_empty() {
+            let secret = config
+                .jwt_secret
+                .clone()
+                .ok_or_else(|| anyhow::anyhow!("JWT_SECRET must be set"))?;
+            return Ok(Self::new(secret, config.jwt_expiration));
+        }
+
+        let keys = config
+            .jwt_keys
+            .iter()
+            .map(|(kid, path)| JwtKey::from_file(kid, path))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Self::with_keys(
+            keys,
+            config.jwt_signing_kid.as_deref(),
+            config.jwt_secret.clone(),
+            config.jwt_expiration,
+        )
+    }
+
+    /// Подпись ключом `signing_kid`, остальные ключи только проверяют токены.
+    /// Если задан `secret`, принимаются и старые токены HS256 - на время перехода
+    pub fn with_keys(
+        keys: Vec<JwtKey>,
+        signing_kid: Option<&str>,
+        secret: Option<String>,
+        expiration_minuts: i64,
+    ) -> anyhow::Result<Self> {
+        let signing = match signing_kid {
+            Some(kid) => keys.iter().position(|key| key.kid == kid),
+            None => keys.iter().position(|key| key.encoding.is_some()),
+        };
+        let signing = signing
+            .filter(|index| keys[*index].encoding.is_some())
+            .ok_or_else(|| anyhow::anyhow!("no private JWT key to sign tokens with"))?;
+
+        for (index, key) in keys.iter().enumerate() {
+            if keys[..index].iter().any(|other| other.kid == key.kid) {
+                anyhow::bail!("duplicate JWT key id {}", key.kid);
+            }
+        }
+
+        let jwks = JwkSet {
+            keys: keys.iter().map(|key| key.jwk.clone()).collect(),
+        };
+
+        Ok(Self {
+            keys: Arc::new(KeyRing {
+                secret: secret.as_deref().map(secret_keys),
+                keys,
+                signing: Some(signing),
+                jwks,
+            }),
+            expiration_minuts,
+        })
+    }
+
+    pub fn generate_token(&self, user_id: Uuid, role: Role) -> Result<String, JwtError> {
         let claims = Claims {
             sub: user_id.to_string(),
             exp: chrono::Utc::now()
@@ -35,25 +125,92 @@ impl JwtService {
                 .unwrap()
                 .timestamp() as usize,
             iat: chrono::Utc::now().timestamp() as usize,
+            jti: Uuid::new_v4().to_string(),
+            role,
         };
 
-        encode(
-            &Header::default(),
-            &claims,
-            &EncodingKey::from_secret(self.secret.as_bytes()),
-        )
+        if let Some(key) = self.keys.signing.map(|index| &self.keys.keys[index]) {
+            let mut header = Header::new(key.algorithm);
+            header.kid = Some(key.kid.clone());
+            let encoding = key
+                .encoding
+                .as_ref()
+                .ok_or_else(|| JwtError::from(ErrorKind::InvalidKeyFormat))?;
+            return encode(&header, &claims, encoding);
+        }
+
+        let (encoding, _) = self
+            .keys
+            .secret
+            .as_ref()
+            .ok_or_else(|| JwtError::from(ErrorKind::InvalidKeyFormat))?;
+        encode(&Header::default(), &claims, encoding)
+    }
+
+    /// Время жизни access токена, в секундах
+    pub fn expiration_seconds(&self) -> i64 {
+        self.expiration_minuts * 60
     }
 
-    pub fn verify_token(&self, token: &str) -> Result<Claims, jsonwebtoken::errors::Error> {
-        let data = decode::<Claims>(
-            token,
-            &DecodingKey::from_secret(self.secret.as_bytes()),
-            &Validation::default(),
-        )?;
+    /// Ключ выбирается по `kid` из заголовка, токен без `kid` проверяется общим секретом
+    pub fn verify_token(&self, token: &str) -> Result<Claims, JwtError> {
+        let header = decode_header(token)?;
+        let data = match header.kid {
+            Some(kid) => {
+                let key = self
+                    .keys
+                    .keys
+                    .iter()
+                    .find(|key| key.kid == kid)
+                    .ok_or_else(|| JwtError::from(ErrorKind::InvalidToken))?;
+                decode::<Claims>(token, &key.decoding, &Validation::new(key.algorithm))?
+            }
+            None => {
+                let (_, decoding) = self
+                    .keys
+                    .secret
+                    .as_ref()
+                    .ok_or_else(|| JwtError::from(ErrorKind::InvalidToken))?;
+                decode::<Claims>(token, decoding, &Validation::default())?
+            }
+        };
         Ok(data.claims)
     }
+
+    /// Открытые ключи для проверки токенов другими сервисами. Общий секрет сюда не попадает
+    pub fn jwks(&self) -> &JwkSet {
+        &self.keys.jwks
+    }
 }
 
+fn secret_keys(secret: &str) -> (EncodingKey, DecodingKey) {
+    (
+        EncodingKey::from_secret(secret.as_bytes()),
+        DecodingKey::from_secret(secret.as_bytes()),
+    )
+}
+
+/// Случайный токен (refresh, сброс пароля), отдаётся клиенту один раз
+pub fn generate_opaque_token() -> String {
+    let mut bytes = [0u8; OPAQUE_TOKEN_BYTES];
+    OsRng.fill_bytes(&mut bytes);
+    to_hex(&bytes)
+}
+
+/// Токен случайный и длинный, поэтому достаточно быстрого хеша без соли
+pub fn hash_opaque_token(token: &str) -> String {
+    to_hex(&Sha256::digest(token.as_bytes()))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Хеш случайного пароля для проверки при входе неизвестного пользователя,
+/// чтобы время ответа не выдавало, есть ли такой аккаунт.
+/// Параметры совпадают с `Argon2::default()`
+pub const DUMMY_PASSWORD_HASH: &str = "$argon2id$v=19$m=19456,t=2,p=1$qeiawNH2omHXxVem/AZLFw$9tHfY+pS9d7uAbHeBLLaSFVyUQwOyNbjmJr1+S7jQHE";
+
 pub fn password_hash(password: &str) -> Result<String, argon2::password_hash::Error> {
     let salt = SaltString::generate(&mut OsRng);
     let argon2 = Argon2::default();
@@ -79,7 +236,7 @@ mod tests {
         let user_id = Uuid::new_v4();
 
         let token = jwt_service
-            .generate_token(user_id)
+            .generate_token(user_id, Role::Moderator)
             .expect("Failed to generate token");
         dbg!(&token);
 
@@ -88,6 +245,8 @@ mod tests {
             .expect("Failed to verify token");
 
         assert_eq!(claims.sub, user_id.to_string());
+        assert!(Uuid::parse_str(&claims.jti).is_ok());
+        assert_eq!(claims.role, Role::Moderator);
     }
 
     #[test]
@@ -96,11 +255,92 @@ mod tests {
 
         let other_service = JwtService::new("different_secret".to_string(), 24 * 60);
         let token = other_service
-            .generate_token(Uuid::new_v4())
+            .generate_token(Uuid::new_v4(), Role::Author)
             .expect("Failed to generate token");
 
         let result = jwt_service.verify_token(&token);
 
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_jwt_key_rotation() {
+        use ed25519_dalek::{
+            SigningKey,
+            pkcs8::{EncodePrivateKey, EncodePublicKey, spki::der::pem::LineEnding},
+        };
+
+        let old_key = SigningKey::from_bytes(&[1; 32]);
+        let new_key = SigningKey::from_bytes(&[2; 32]);
+        let old_pem = old_key.to_pkcs8_pem(LineEnding::LF).unwrap();
+        let old_public_pem = old_key
+            .verifying_key()
+            .to_public_key_pem(LineEnding::LF)
+            .unwrap();
+        let new_pem = new_key.to_pkcs8_pem(LineEnding::LF).unwrap();
+
+        let before = JwtService::with_keys(
+            vec![JwtKey::from_pem("old", &old_pem).unwrap()],
+            None,
+            None,
+            15,
+        )
+        .unwrap();
+        let after = JwtService::with_keys(
+            vec![
+                JwtKey::from_pem("old", &old_public_pem).unwrap(),
+                JwtKey::from_pem("new", &new_pem).unwrap(),
+            ],
+            Some("new"),
+            None,
+            15,
+        )
+        .unwrap();
+
+        let user_id = Uuid::new_v4();
+        let old_token = before.generate_token(user_id, Role::Author).unwrap();
+        assert_eq!(
+            decode_header(&old_token).unwrap().kid.as_deref(),
+            Some("old")
+        );
+        assert_eq!(
+            after.verify_token(&old_token).unwrap().sub,
+            user_id.to_string()
+        );
+
+        let new_token = after.generate_token(user_id, Role::Author).unwrap();
+        assert_eq!(
+            decode_header(&new_token).unwrap().kid.as_deref(),
+            Some("new")
+        );
+        assert!(before.verify_token(&new_token).is_err());
+        assert_eq!(after.jwks().keys.len(), 2);
+
+        let hmac_token = JwtService::new("secret".to_string(), 15)
+            .generate_token(user_id, Role::Author)
+            .unwrap();
+        assert!(after.verify_token(&hmac_token).is_err());
+    }
+
+    #[test]
+    fn test_opaque_token_hash() {
+        let token = generate_opaque_token();
+
+        assert_eq!(token.len(), OPAQUE_TOKEN_BYTES * 2);
+        assert_ne!(token, generate_opaque_token());
+        assert_eq!(hash_opaque_token(&token), hash_opaque_token(&token));
+        assert_ne!(hash_opaque_token(&token), token);
+    }
+
+    #[test]
+    fn test_dummy_password_hash() {
+        assert!(!password_verify("password", DUMMY_PASSWORD_HASH).unwrap());
+
+        // Та же стоимость, что и у настоящих хешей
+        let params = |hash: &str| hash.rsplitn(3, '$').nth(2).map(str::to_string);
+        assert_eq!(
+            params(DUMMY_PASSWORD_HASH),
+            params(&password_hash("password").unwrap())
+        );
+    }
 }
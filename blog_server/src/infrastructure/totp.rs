@@ -0,0 +1,99 @@
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use totp_rs::{Algorithm, Secret, TOTP, TotpUrlError};
+
+const TOTP_DIGITS: usize = 6;
+/// Длительность шага, в секундах
+const TOTP_STEP: u64 = 30;
+/// Сколько соседних шагов допускается из-за расхождения часов
+const TOTP_SKEW: u64 = 1;
+const TOTP_SECRET_BYTES: usize = 20;
+const TOTP_ISSUER: &str = "Blog";
+const RECOVERY_CODE_BYTES: usize = 5;
+
+/// Случайный секрет в base32, как его принимают приложения-аутентификаторы
+pub fn generate_totp_secret() -> String {
+    let mut bytes = [0u8; TOTP_SECRET_BYTES];
+    OsRng.fill_bytes(&mut bytes);
+    Secret::Raw(bytes.to_vec()).to_encoded().to_string()
+}
+
+/// Адрес `otpauth://` для добавления аккаунта в приложение (обычно через QR код)
+pub fn totp_uri(secret: &str, account: &str) -> Result<String, TotpUrlError> {
+    Ok(totp(secret, account)?.get_url())
+}
+
+/// Номер шага, которому соответствует код, если код верный.
+/// По номеру шага можно не допустить повторного использования кода
+pub fn verify_totp(secret: &str, code: &str, time: u64) -> Option<u64> {
+    let totp = totp(secret, "").ok()?;
+    let current = time / TOTP_STEP;
+    (current.saturating_sub(TOTP_SKEW)..=current + TOTP_SKEW)
+        .find(|step| totp.generate(step * TOTP_STEP) == code)
+}
+
+/// Одноразовые коды восстановления вида `a1b2c-3d4e5`
+pub fn generate_recovery_codes(count: usize) -> Vec<String> {
+    (0..count)
+        .map(|_| {
+            let mut bytes = [0u8; RECOVERY_CODE_BYTES];
+            OsRng.fill_bytes(&mut bytes);
+            let code: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+            format!("{}-{}", &code[..5], &code[5..])
+        })
+        .collect()
+}
+
+/// Код восстановления без разделителей и регистра, в таком виде хранится его хеш
+pub fn normalize_recovery_code(code: &str) -> String {
+    code.trim().replace('-', "").to_lowercase()
+}
+
+fn totp(secret: &str, account: &str) -> Result<TOTP, TotpUrlError> {
+    let secret = Secret::Encoded(secret.to_string())
+        .to_bytes()
+        .map_err(|_| TotpUrlError::Secret("invalid base32".to_string()))?;
+    TOTP::new(
+        Algorithm::SHA1,
+        TOTP_DIGITS,
+        0,
+        TOTP_STEP,
+        secret,
+        Some(TOTP_ISSUER.to_string()),
+        account.to_string(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_totp() {
+        let secret = generate_totp_secret();
+        let totp = totp(&secret, "alice").unwrap();
+        let time = 1_800_000_000;
+
+        let code = totp.generate(time);
+        assert_eq!(verify_totp(&secret, &code, time), Some(time / TOTP_STEP));
+        // код с соседнего шага тоже принимается
+        assert!(verify_totp(&secret, &code, time + TOTP_STEP).is_some());
+        assert!(verify_totp(&secret, &code, time + 3 * TOTP_STEP).is_none());
+        assert!(verify_totp(&secret, "000000x", time).is_none());
+
+        let uri = totp_uri(&secret, "alice").unwrap();
+        assert!(uri.starts_with("otpauth://totp/Blog:alice?"));
+    }
+
+    #[test]
+    fn test_recovery_codes() {
+        let codes = generate_recovery_codes(10);
+
+        assert_eq!(codes.len(), 10);
+        assert!(
+            codes
+                .iter()
+                .all(|c| c.len() == 11 && c.as_bytes()[5] == b'-')
+        );
+        assert_eq!(normalize_recovery_code(" A1B2C-3D4E5 "), "a1b2c3d4e5");
+    }
+}
@@ -8,7 +8,7 @@ mod domain;
 mod infrastructure;
 mod presentation;
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use actix_web::{
     App, HttpServer,
@@ -17,11 +17,24 @@ use actix_web::{
 };
 
 use crate::{
-    application::{AuthService, BlogService},
-    data::{posr_repository::PostgresPostRepository, user_repository::PostgresUserRepository},
-    infrastructure::{AppConfig, JwtService, create_pool, init_logging, run_migrations},
+    application::{
+        AccountService, AttachmentService, AuthService, BlogService, CommentService,
+        run_publish_scheduler,
+    },
+    data::{
+        attachment_repository::PostgresAttachmentRepository,
+        comment_repository::PostgresCommentRepository,
+        mailer::{FileMailer, Mailer, SmtpMailer},
+        posr_repository::PostgresPostRepository,
+        storage::LocalFileStorage,
+        user_repository::PostgresUserRepository,
+    },
+    infrastructure::{
+        AppConfig, JwtService, RateLimiter, create_pool, init_logging, run_migrations,
+    },
     presentation::{
-        JwtAuthMiddleware, RequestIdMiddleware, TimingMiddleware, grpc_service, handlers,
+        AuthRateLimitLayer, JwtAuthMiddleware, RequestIdMiddleware, TimingMiddleware, grpc_service,
+        handlers,
     },
 };
 
@@ -39,17 +52,68 @@ async fn main() -> anyhow::Result<()> {
         .await
         .expect("Failed to run migrations");
 
+    let mailer: Arc<dyn Mailer> = match &config.smtp_host {
+        Some(host) => Arc::new(SmtpMailer::new(
+            host,
+            config.smtp_port,
+            config
+                .smtp_username
+                .clone()
+                .zip(config.smtp_password.clone()),
+            config.mail_from.clone(),
+        )?),
+        None => Arc::new(FileMailer::new(&config.mail_dir)),
+    };
+
     let user_repo = Arc::new(PostgresUserRepository::new(pool.clone()));
     let auth_service = AuthService::new(
         Arc::clone(&user_repo),
-        JwtService::new(config.jwt_secret.clone(), config.jwt_expiration),
-    );
+        JwtService::from_config(&config)?,
+        chrono::Duration::days(config.refresh_expiration_days),
+        mailer,
+        config.public_url.clone(),
+        config.require_verified_email,
+        config.bootstrap_admin.clone(),
+    )
+    .with_rate_limiter(RateLimiter::in_memory(config.rate_limit.clone()));
+    auth_service.bootstrap_admin().await?;
 
     let blog_repo = Arc::new(PostgresPostRepository::new(pool.clone()));
     let blog_service = BlogService::new(Arc::clone(&blog_repo));
 
-    let service_grpc =
-        grpc_service::BlogGrpcService::new(auth_service.clone(), blog_service.clone());
+    let comment_repo = Arc::new(PostgresCommentRepository::new(pool.clone()));
+    let comment_service = CommentService::new(Arc::clone(&comment_repo), Arc::clone(&blog_repo));
+
+    let attachment_repo = Arc::new(PostgresAttachmentRepository::new(pool.clone()));
+    let storage = Arc::new(LocalFileStorage::new(&config.upload_dir));
+    let attachment_service = AttachmentService::new(
+        Arc::clone(&attachment_repo),
+        Arc::clone(&storage),
+        Arc::clone(&blog_repo),
+        config.upload_max_size,
+    );
+
+    let account_service = AccountService::new(
+        user_repo,
+        Arc::clone(&blog_repo),
+        comment_repo,
+        attachment_repo,
+        storage,
+    );
+
+    let service_grpc = grpc_service::BlogGrpcService::new(
+        auth_service.clone(),
+        blog_service.clone(),
+        comment_service.clone(),
+        attachment_service.clone(),
+        account_service.clone(),
+    );
+    let grpc_rate_limit = AuthRateLimitLayer::new(auth_service.rate_limiter());
+
+    let scheduler = run_publish_scheduler(
+        blog_service.clone(),
+        Duration::from_secs(config.publish_interval),
+    );
 
     let config_data = config.clone();
 
@@ -69,7 +133,15 @@ async fn main() -> anyhow::Result<()> {
             .wrap(cors)
             .app_data(web::Data::new(auth_service.clone()))
             .app_data(web::Data::new(blog_service.clone()))
-            .service(web::scope("/api").service(handlers::public::scope()))
+            .app_data(web::Data::new(comment_service.clone()))
+            .app_data(web::Data::new(attachment_service.clone()))
+            .app_data(web::Data::new(account_service.clone()))
+            .service(handlers::public::well_known())
+            .service(
+                web::scope("/api")
+                    .wrap(JwtAuthMiddleware::optional())
+                    .service(handlers::public::scope()),
+            )
             .service(
                 web::scope("/protect")
                     .wrap(JwtAuthMiddleware::new())
@@ -80,6 +152,7 @@ async fn main() -> anyhow::Result<()> {
     .run();
 
     let grpc_server = tonic::transport::Server::builder()
+        .layer(grpc_rate_limit)
         .add_service(blog_grpc::blog_service_server::BlogServiceServer::new(
             service_grpc,
         ))
@@ -101,6 +174,10 @@ async fn main() -> anyhow::Result<()> {
 
         }
 
+        _ = scheduler => {
+            tracing::info!("Publish scheduler stopped");
+        }
+
     }
 
     Ok(())
@@ -3,13 +3,16 @@ use futures_util::future::{Ready, ready};
 use uuid::Uuid;
 
 use crate::{
-    application::AuthService, data::user_repository::PostgresUserRepository,
-    domain::error::BlogError,
+    application::AuthService,
+    data::user_repository::PostgresUserRepository,
+    domain::{auth::AccessSession, error::BlogError},
 };
 
 #[derive(Clone, Debug)]
 pub struct AuthenticatedUser {
     pub id: Uuid,
+    /// Токен, которым аутентифицирован запрос
+    pub session: AccessSession,
 }
 
 impl FromRequest for AuthenticatedUser {
@@ -28,17 +31,10 @@ pub async fn extract_user_from_token(
     token: &str,
     auth_service: &AuthService<PostgresUserRepository>,
 ) -> Result<AuthenticatedUser, BlogError> {
-    let claims = auth_service
-        .keys()
-        .verify_token(token)
-        .map_err(|_| BlogError::Unautorized)?;
+    let session = auth_service.authenticate(token).await?;
 
-    let user_id = Uuid::parse_str(&claims.sub).map_err(|_| BlogError::Unautorized)?;
-
-    let user = auth_service
-        .get_user(user_id)
-        .await
-        .map_err(|_| BlogError::Unautorized)?;
-
-    Ok(AuthenticatedUser { id: user.id })
+    Ok(AuthenticatedUser {
+        id: session.user_id,
+        session,
+    })
 }
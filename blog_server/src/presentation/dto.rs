@@ -1,7 +1,20 @@
 use serde::{Deserialize, Serialize};
+use serde_with::{TimestampSeconds, serde_as};
 use uuid::Uuid;
 
-use crate::domain::post::Post;
+use crate::domain::{
+    attachment::Attachment,
+    auth::{AuthAnswer, TwoFactorChallenge, User},
+    comment::Comment,
+    follow::FollowUser,
+    personal_token::PersonalAccessToken,
+    post::{Post, TagCount},
+    profile::UserProfile,
+    reaction::PostOrder,
+    revision::PostRevision,
+    role::Role,
+    search::SearchHit,
+};
 
 #[derive(Debug, Serialize)]
 pub struct HealthResponse {
@@ -15,6 +28,14 @@ pub struct PaginationParams {
     pub limit: i32,
     #[serde(default = "default_offset")]
     pub offset: i32,
+    /// Курсор из `next_cursor` предыдущей страницы, имеет приоритет над `offset`
+    pub cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PostOrderParams {
+    #[serde(default)]
+    pub order: PostOrder,
 }
 
 fn default_limit() -> i32 {
@@ -30,10 +51,183 @@ pub struct ListPostsResponse {
     pub total: i32,
     pub limit: i32,
     pub offset: i32,
+    pub next_cursor: Option<String>,
+}
+
+/// Значения повторяющегося параметра `tag` из строки запроса
+pub fn tags_from_query(query: &[(String, String)]) -> Vec<String> {
+    query
+        .iter()
+        .filter(|(key, _)| key == "tag")
+        .map(|(_, value)| value.clone())
+        .collect()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SearchParams {
+    pub q: String,
+    #[serde(default = "default_limit")]
+    pub limit: i32,
+    #[serde(default = "default_offset")]
+    pub offset: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchResponse {
+    pub hits: Vec<SearchHit>,
+    pub total: i32,
+    pub limit: i32,
+    pub offset: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListTagsResponse {
+    pub tags: Vec<TagCount>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListCommentsResponse {
+    pub comments: Vec<Comment>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListRevisionsResponse {
+    pub revisions: Vec<PostRevision>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DiffParams {
+    /// Ревизия для сравнения. По умолчанию текущая версия поста
+    pub to: Option<i32>,
+}
+
+/// Вложение вместе с адресом, по которому отдаётся содержимое
+#[derive(Debug, Serialize)]
+pub struct AttachmentView {
+    #[serde(flatten)]
+    pub attachment: Attachment,
+    pub url: String,
+}
+
+impl From<Attachment> for AttachmentView {
+    fn from(attachment: Attachment) -> Self {
+        Self {
+            url: attachment.url(),
+            attachment,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct AttachmentResponse {
+    pub attachment: AttachmentView,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListAttachmentsResponse {
+    pub attachments: Vec<AttachmentView>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UploadParams {
+    /// Пост, к которому относится файл
+    pub post_id: Option<i64>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct AuthResponse {
     pub token: String,
+    pub refresh_token: String,
+    /// Через сколько секунд истечёт `token`
+    pub expires_in: i64,
     pub uuid: Uuid,
 }
+
+impl From<AuthAnswer> for AuthResponse {
+    fn from(value: AuthAnswer) -> Self {
+        Self {
+            token: value.token,
+            refresh_token: value.refresh_token,
+            expires_in: value.expires_in,
+            uuid: value.uuid,
+        }
+    }
+}
+
+/// Пользователь без секретов, для администрирования
+#[serde_as]
+#[derive(Debug, Serialize)]
+pub struct UserView {
+    pub id: Uuid,
+    pub username: String,
+    pub email: String,
+    pub role: Role,
+    pub email_verified: bool,
+    #[serde_as(as = "TimestampSeconds<i64>")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<User> for UserView {
+    fn from(user: User) -> Self {
+        Self {
+            id: user.id,
+            username: user.username,
+            email: user.email,
+            role: user.role,
+            email_verified: user.email_verified_at.is_some(),
+            created_at: user.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct UserResponse {
+    pub user: UserView,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListUsersResponse {
+    pub users: Vec<UserView>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UserProfileResponse {
+    pub profile: UserProfile,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListFollowsResponse {
+    pub users: Vec<FollowUser>,
+    pub total: i32,
+    pub limit: i32,
+    pub offset: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreatePersonalTokenResponse {
+    pub personal_access_token: PersonalAccessToken,
+    /// Значение токена, больше сервер его не покажет
+    pub token: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListPersonalTokensResponse {
+    pub tokens: Vec<PersonalAccessToken>,
+}
+
+/// Ответ на вход по паролю, когда нужен код второго фактора
+#[derive(Debug, Serialize)]
+pub struct TwoFactorChallengeResponse {
+    pub two_factor_challenge: String,
+    /// Через сколько секунд запрос истечёт
+    pub expires_in: i64,
+}
+
+impl From<TwoFactorChallenge> for TwoFactorChallengeResponse {
+    fn from(value: TwoFactorChallenge) -> Self {
+        Self {
+            two_factor_challenge: value.challenge,
+            expires_in: value.expires_in,
+        }
+    }
+}
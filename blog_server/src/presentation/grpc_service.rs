@@ -1,34 +1,135 @@
 use crate::blog_grpc::blog_service_server::BlogService;
 use crate::blog_grpc::{
-    self, AuthResponse, CreatePostRequest, DeletePostRequest, DeletePostResponse, GetPostRequest,
-    ListPostsRequest, ListPostsResponse, LoginRequest, PostResponse, RegisterRequest,
-    UpdatePostRequest,
+    self, AttachmentResponse, AuthResponse, BookmarkRequest, BookmarkResponse,
+    ChangePasswordRequest, CommentResponse, ConfirmPasswordResetRequest, CreateCommentRequest,
+    CreatePersonalAccessTokenRequest, CreatePersonalAccessTokenResponse, CreatePostRequest,
+    DeleteAccountRequest, DeleteAccountResponse, DeleteCommentRequest, DeleteCommentResponse,
+    DeletePostRequest, DeletePostResponse, DiffRevisionsRequest, EnrollTwoFactorRequest,
+    ExportAccountRequest, ExportAccountResponse, FeedRequest, FollowRequest, FollowStatus,
+    GetPostBySlugRequest, GetPostRequest, GetUserProfileRequest, ListBookmarksRequest,
+    ListCommentsRequest, ListCommentsResponse, ListDraftsRequest, ListFollowsRequest,
+    ListFollowsResponse, ListPersonalAccessTokensRequest, ListPersonalAccessTokensResponse,
+    ListPostsRequest, ListPostsResponse, ListRevisionsRequest, ListRevisionsResponse,
+    ListTagsRequest, ListTagsResponse, ListUserPostsRequest, ListUsersRequest, ListUsersResponse,
+    LoginRequest, LoginTwoFactorRequest, LogoutAllRequest, LogoutRequest, LogoutResponse,
+    PasswordResetRequest, PasswordResetResponse, PostReactions, PostResponse, PostStatusRequest,
+    ReactionRequest, RefreshRequest, RegisterRequest, ResendVerificationRequest,
+    RestoreRevisionRequest, RevisionDiff, RevokePersonalAccessTokenRequest,
+    RevokePersonalAccessTokenResponse, SearchPostsRequest, SearchPostsResponse, SetUserRoleRequest,
+    TwoFactorCodeRequest, TwoFactorEnrollment, TwoFactorResponse, UpdateCommentRequest,
+    UpdatePostRequest, UpdateProfileRequest, UploadAttachmentRequest, UserProfileResponse,
+    UserResponse, VerifyEmailRequest, VerifyEmailResponse, upload_attachment_request,
 };
+use crate::data::attachment_repository::PostgresAttachmentRepository;
+use crate::data::comment_repository::PostgresCommentRepository;
 use crate::data::posr_repository::PostgresPostRepository;
+use crate::data::storage::LocalFileStorage;
 use crate::data::user_repository::PostgresUserRepository;
-use crate::domain::auth::Auth;
-use crate::domain::post::{Post, UpdatePost};
-use crate::presentation::auth::extract_user_from_token;
+use crate::domain::account::DeleteAccount;
+use crate::domain::attachment::UploadAttachment;
+use crate::domain::auth::{Auth, ChangePassword, LoginTwoFactor, PasswordResetConfirm};
+use crate::domain::comment::UpdateComment;
+use crate::domain::content::ContentFormat;
+use crate::domain::error::BlogError;
+use crate::domain::pagination::PageRequest;
+use crate::domain::personal_token::{CreatePersonalToken, TokenScope};
+use crate::domain::post::{CreatePost, Post, PostStatus, UpdatePost};
+use crate::domain::role::Role;
+use crate::presentation::auth::{AuthenticatedUser, extract_user_from_token};
 
 use tonic::metadata::MetadataMap;
-use tonic::{Request, Response, Status};
+use tonic::{Request, Response, Status, Streaming};
+use uuid::Uuid;
 
 #[derive(Clone)]
 pub struct BlogGrpcService {
     auth_service: crate::application::AuthService<PostgresUserRepository>,
     blog_service: crate::application::BlogService<PostgresPostRepository>,
+    comment_service:
+        crate::application::CommentService<PostgresCommentRepository, PostgresPostRepository>,
+    attachment_service: crate::application::AttachmentService<
+        PostgresAttachmentRepository,
+        LocalFileStorage,
+        PostgresPostRepository,
+    >,
+    account_service: crate::application::AccountService<
+        PostgresUserRepository,
+        PostgresPostRepository,
+        PostgresCommentRepository,
+        PostgresAttachmentRepository,
+        LocalFileStorage,
+    >,
 }
 
 impl BlogGrpcService {
     pub fn new(
         auth_service: crate::application::AuthService<PostgresUserRepository>,
         blog_service: crate::application::BlogService<PostgresPostRepository>,
+        comment_service: crate::application::CommentService<
+            PostgresCommentRepository,
+            PostgresPostRepository,
+        >,
+        attachment_service: crate::application::AttachmentService<
+            PostgresAttachmentRepository,
+            LocalFileStorage,
+            PostgresPostRepository,
+        >,
+        account_service: crate::application::AccountService<
+            PostgresUserRepository,
+            PostgresPostRepository,
+            PostgresCommentRepository,
+            PostgresAttachmentRepository,
+            LocalFileStorage,
+        >,
     ) -> Self {
         Self {
             auth_service,
             blog_service,
+            comment_service,
+            attachment_service,
+            account_service,
         }
     }
+
+    /// Пользователь открытого запроса. Без токена или с недействительным
+    /// токеном запрос выполняется анонимно
+    async fn optional_user(&self, metadata: &MetadataMap) -> Option<AuthenticatedUser> {
+        let token = extract_token_from_metadata(metadata).ok()?;
+        extract_user_from_token(token, &self.auth_service)
+            .await
+            .ok()
+    }
+
+    /// Неопубликованный пост виден только автору и модераторам
+    fn ensure_visible(&self, post: &Post, user: Option<&AuthenticatedUser>) -> Result<(), Status> {
+        if !post.is_visible_to(user.map(|u| &u.session)) {
+            return Err(BlogError::NotFound("Posts not found".to_string()).into());
+        }
+        Ok(())
+    }
+
+    async fn change_status(
+        &self,
+        request: Request<PostStatusRequest>,
+        status: PostStatus,
+    ) -> Result<Response<PostResponse>, Status> {
+        let token = extract_token_from_metadata(request.metadata())?;
+        let user_id = extract_user_from_token(token, &self.auth_service).await?;
+        user_id.session.require_scope(TokenScope::PostsWrite)?;
+        if status == PostStatus::Published {
+            self.auth_service.ensure_can_publish(&user_id.session)?;
+        }
+        let post = self
+            .blog_service
+            .get_post_by_id(request.into_inner().id)
+            .await?;
+        user_id.session.ensure_can_modify(post.author_id)?;
+
+        let post = self.blog_service.change_status(&post, status).await?;
+        Ok(Response::new(PostResponse {
+            post: Some(post.into()),
+        }))
+    }
 }
 
 #[tonic::async_trait]
@@ -51,10 +152,10 @@ impl BlogService for BlogGrpcService {
 
         let acc = self
             .auth_service
-            .login_by_username(&user.username, &user.password)
+            .login_by_password(&user.username, &user.password)
             .await?;
 
-        Ok(Response::new(AuthResponse { token: acc.token }))
+        Ok(Response::new(acc.into()))
     }
 
     async fn login(
@@ -64,10 +165,176 @@ impl BlogService for BlogGrpcService {
         let user = request.into_inner();
         let acc = self
             .auth_service
-            .login_by_username(&user.username, &user.password)
+            .login_by_password(&user.username, &user.password)
             .await?;
 
-        Ok(Response::new(AuthResponse { token: acc.token }))
+        Ok(Response::new(acc.into()))
+    }
+
+    async fn refresh(
+        &self,
+        request: Request<RefreshRequest>,
+    ) -> Result<Response<AuthResponse>, Status> {
+        let acc = self
+            .auth_service
+            .refresh(&request.into_inner().refresh_token)
+            .await?;
+
+        Ok(Response::new(acc.into()))
+    }
+
+    async fn logout(
+        &self,
+        request: Request<LogoutRequest>,
+    ) -> Result<Response<LogoutResponse>, Status> {
+        let token = extract_token_from_metadata(request.metadata())?;
+        let user = extract_user_from_token(token, &self.auth_service).await?;
+        user.session.require_interactive()?;
+        self.auth_service
+            .logout(&user.session, &request.into_inner().refresh_token)
+            .await?;
+
+        Ok(Response::new(LogoutResponse { success: true }))
+    }
+
+    async fn change_password(
+        &self,
+        request: Request<ChangePasswordRequest>,
+    ) -> Result<Response<AuthResponse>, Status> {
+        let token = extract_token_from_metadata(request.metadata())?;
+        let user = extract_user_from_token(token, &self.auth_service).await?;
+        user.session.require_interactive()?;
+        let data = request.into_inner();
+        let acc = self
+            .auth_service
+            .change_password(
+                user.id,
+                ChangePassword {
+                    current_password: data.current_password,
+                    new_password: data.new_password,
+                },
+            )
+            .await?;
+
+        Ok(Response::new(acc.into()))
+    }
+
+    async fn request_password_reset(
+        &self,
+        request: Request<PasswordResetRequest>,
+    ) -> Result<Response<PasswordResetResponse>, Status> {
+        self.auth_service
+            .request_password_reset(&request.into_inner().email)
+            .await?;
+
+        Ok(Response::new(PasswordResetResponse { success: true }))
+    }
+
+    async fn confirm_password_reset(
+        &self,
+        request: Request<ConfirmPasswordResetRequest>,
+    ) -> Result<Response<PasswordResetResponse>, Status> {
+        let data = request.into_inner();
+        self.auth_service
+            .confirm_password_reset(PasswordResetConfirm {
+                token: data.token,
+                new_password: data.new_password,
+            })
+            .await?;
+
+        Ok(Response::new(PasswordResetResponse { success: true }))
+    }
+
+    async fn verify_email(
+        &self,
+        request: Request<VerifyEmailRequest>,
+    ) -> Result<Response<VerifyEmailResponse>, Status> {
+        self.auth_service
+            .verify_email(&request.into_inner().token)
+            .await?;
+
+        Ok(Response::new(VerifyEmailResponse { success: true }))
+    }
+
+    async fn resend_verification(
+        &self,
+        request: Request<ResendVerificationRequest>,
+    ) -> Result<Response<VerifyEmailResponse>, Status> {
+        let token = extract_token_from_metadata(request.metadata())?;
+        let user = extract_user_from_token(token, &self.auth_service).await?;
+        user.session.require_interactive()?;
+        self.auth_service.resend_verification(user.id).await?;
+
+        Ok(Response::new(VerifyEmailResponse { success: true }))
+    }
+
+    async fn login_two_factor(
+        &self,
+        request: Request<LoginTwoFactorRequest>,
+    ) -> Result<Response<AuthResponse>, Status> {
+        let request = request.into_inner();
+        let acc = self
+            .auth_service
+            .complete_two_factor_login(LoginTwoFactor {
+                challenge: request.challenge,
+                code: request.code,
+            })
+            .await?;
+
+        Ok(Response::new(acc.into()))
+    }
+
+    async fn enroll_two_factor(
+        &self,
+        request: Request<EnrollTwoFactorRequest>,
+    ) -> Result<Response<TwoFactorEnrollment>, Status> {
+        let token = extract_token_from_metadata(request.metadata())?;
+        let user = extract_user_from_token(token, &self.auth_service).await?;
+        let enrollment = self.auth_service.enroll_two_factor(&user.session).await?;
+
+        Ok(Response::new(TwoFactorEnrollment {
+            secret: enrollment.secret,
+            otpauth_uri: enrollment.otpauth_uri,
+            recovery_codes: enrollment.recovery_codes,
+        }))
+    }
+
+    async fn confirm_two_factor(
+        &self,
+        request: Request<TwoFactorCodeRequest>,
+    ) -> Result<Response<TwoFactorResponse>, Status> {
+        let token = extract_token_from_metadata(request.metadata())?;
+        let user = extract_user_from_token(token, &self.auth_service).await?;
+        self.auth_service
+            .confirm_two_factor(&user.session, &request.into_inner().code)
+            .await?;
+
+        Ok(Response::new(TwoFactorResponse { success: true }))
+    }
+
+    async fn disable_two_factor(
+        &self,
+        request: Request<TwoFactorCodeRequest>,
+    ) -> Result<Response<TwoFactorResponse>, Status> {
+        let token = extract_token_from_metadata(request.metadata())?;
+        let user = extract_user_from_token(token, &self.auth_service).await?;
+        self.auth_service
+            .disable_two_factor(&user.session, &request.into_inner().code)
+            .await?;
+
+        Ok(Response::new(TwoFactorResponse { success: true }))
+    }
+
+    async fn logout_all(
+        &self,
+        request: Request<LogoutAllRequest>,
+    ) -> Result<Response<LogoutResponse>, Status> {
+        let token = extract_token_from_metadata(request.metadata())?;
+        let user = extract_user_from_token(token, &self.auth_service).await?;
+        user.session.require_interactive()?;
+        self.auth_service.logout_everywhere(user.id).await?;
+
+        Ok(Response::new(LogoutResponse { success: true }))
     }
 
     async fn create_post(
@@ -76,11 +343,13 @@ impl BlogService for BlogGrpcService {
     ) -> Result<Response<PostResponse>, Status> {
         let token = extract_token_from_metadata(request.metadata())?;
         let user_id = extract_user_from_token(token, &self.auth_service).await?;
-        let post = request.into_inner();
-        let post = self
-            .blog_service
-            .create_post(post.into(), user_id.id)
-            .await?;
+        user_id.session.require_scope(TokenScope::PostsWrite)?;
+        user_id.session.require_role(Role::Author)?;
+        let post: CreatePost = request.into_inner().try_into()?;
+        if post.publishes() {
+            self.auth_service.ensure_can_publish(&user_id.session)?;
+        }
+        let post = self.blog_service.create_post(post, user_id.id).await?;
         return Ok(Response::new(PostResponse {
             post: Some(post.into()),
         }));
@@ -90,32 +359,68 @@ impl BlogService for BlogGrpcService {
         &self,
         request: Request<GetPostRequest>,
     ) -> Result<Response<PostResponse>, Status> {
+        let user = self.optional_user(request.metadata()).await;
         let id = request.into_inner().id;
-        let post = self.blog_service.get_post_by_id(id).await?;
+        let mut post = self.blog_service.get_post_by_id(id).await?;
+        self.ensure_visible(&post, user.as_ref())?;
+        self.blog_service
+            .mark_bookmarked(user.map(|u| u.id), std::slice::from_mut(&mut post))
+            .await?;
         let post = <Post as std::convert::Into<blog_grpc::Post>>::into(post);
         Ok(Response::new(PostResponse { post: Some(post) }))
     }
 
+    async fn get_post_by_slug(
+        &self,
+        request: Request<GetPostBySlugRequest>,
+    ) -> Result<Response<PostResponse>, Status> {
+        let user = self.optional_user(request.metadata()).await;
+        let slug = request.into_inner().slug;
+        let mut post = self.blog_service.get_post_by_slug(&slug).await?;
+        self.ensure_visible(&post, user.as_ref())?;
+        self.blog_service
+            .mark_bookmarked(user.map(|u| u.id), std::slice::from_mut(&mut post))
+            .await?;
+        Ok(Response::new(PostResponse {
+            post: Some(post.into()),
+        }))
+    }
+
     async fn update_post(
         &self,
         request: Request<UpdatePostRequest>,
     ) -> Result<Response<PostResponse>, Status> {
         let token = extract_token_from_metadata(request.metadata())?;
         let user_id = extract_user_from_token(token, &self.auth_service).await?;
+        user_id.session.require_scope(TokenScope::PostsWrite)?;
         let update_post = request.into_inner();
         let post = self.blog_service.get_post_by_id(update_post.id).await?;
-        if post.author_id != user_id.id {
-            return Err(Status::permission_denied(
-                "You are not the author of this post",
-            ));
-        }
+        user_id.session.ensure_can_modify(post.author_id)?;
 
         let check_update = UpdatePost {
             title: update_post.title.unwrap_or(post.title.clone()),
             content: update_post.content.unwrap_or(post.content.clone()),
+            tags: update_post.tags.map(|t| t.names),
+            status: update_post
+                .status
+                .map(|s| s.parse::<PostStatus>())
+                .transpose()?,
+            publish_at: update_post
+                .publish_at
+                .and_then(|e| chrono::DateTime::from_timestamp(e, 0)),
+            content_format: update_post
+                .content_format
+                .map(|f| f.parse::<ContentFormat>())
+                .transpose()?,
         };
+        if check_update.publishes() {
+            self.auth_service.ensure_can_publish(&user_id.session)?;
+        }
 
-        let post = self.blog_service.update_post(&post, check_update).await?;
+        let post = self
+            .blog_service
+            .update_post(&post, check_update, user_id.id)
+            .await?;
         return Ok(Response::new(PostResponse {
             post: Some(post.into()),
         }));
@@ -127,16 +432,13 @@ impl BlogService for BlogGrpcService {
     ) -> Result<Response<DeletePostResponse>, Status> {
         let token = extract_token_from_metadata(request.metadata())?;
         let user_id = extract_user_from_token(token, &self.auth_service).await?;
+        user_id.session.require_scope(TokenScope::PostsWrite)?;
         let delete_post = request.into_inner();
         let post = self
             .blog_service
             .get_post_by_id(delete_post.post_id)
             .await?;
-        if post.author_id != user_id.id {
-            return Err(Status::permission_denied(
-                "You are not the author of this post",
-            ));
-        }
+        user_id.session.ensure_can_modify(post.author_id)?;
 
         self.blog_service.delete_post(post.id).await?;
         return Ok(Response::new(DeletePostResponse { success: true }));
@@ -146,20 +448,630 @@ impl BlogService for BlogGrpcService {
         &self,
         request: Request<ListPostsRequest>,
     ) -> Result<Response<ListPostsResponse>, Status> {
+        let user = self.optional_user(request.metadata()).await;
+        let request = request.into_inner();
+
+        let page = PageRequest::new(request.offset.into(), request.cursor.as_deref())?;
+        let mut page = self
+            .blog_service
+            .get_next_posts(
+                page,
+                request.limit.into(),
+                request.tags,
+                request.order.as_deref().unwrap_or_default().parse()?,
+            )
+            .await?;
+        self.blog_service
+            .mark_bookmarked(user.map(|u| u.id), &mut page.posts)
+            .await?;
+        Ok(Response::new(ListPostsResponse {
+            post: page.posts.into_iter().map(|p| p.into()).collect(),
+            total: page.total as i32,
+            limit: request.limit,
+            offset: request.offset,
+            next_cursor: page.next_cursor,
+        }))
+    }
+
+    async fn list_drafts(
+        &self,
+        request: Request<ListDraftsRequest>,
+    ) -> Result<Response<ListPostsResponse>, Status> {
+        let token = extract_token_from_metadata(request.metadata())?;
+        let user_id = extract_user_from_token(token, &self.auth_service).await?;
+        user_id.session.require_scope(TokenScope::PostsRead)?;
+        let request = request.into_inner();
+
+        let page = self
+            .blog_service
+            .get_drafts(user_id.id, request.offset.into(), request.limit.into())
+            .await?;
+        Ok(Response::new(ListPostsResponse {
+            post: page.posts.into_iter().map(|p| p.into()).collect(),
+            total: page.total as i32,
+            limit: request.limit,
+            offset: request.offset,
+            next_cursor: page.next_cursor,
+        }))
+    }
+
+    async fn publish_post(
+        &self,
+        request: Request<PostStatusRequest>,
+    ) -> Result<Response<PostResponse>, Status> {
+        self.change_status(request, PostStatus::Published).await
+    }
+
+    async fn unpublish_post(
+        &self,
+        request: Request<PostStatusRequest>,
+    ) -> Result<Response<PostResponse>, Status> {
+        self.change_status(request, PostStatus::Draft).await
+    }
+
+    async fn archive_post(
+        &self,
+        request: Request<PostStatusRequest>,
+    ) -> Result<Response<PostResponse>, Status> {
+        self.change_status(request, PostStatus::Archived).await
+    }
+
+    async fn list_tags(
+        &self,
+        _request: Request<ListTagsRequest>,
+    ) -> Result<Response<ListTagsResponse>, Status> {
+        let tags = self.blog_service.get_tags().await?;
+        Ok(Response::new(ListTagsResponse {
+            tags: tags.into_iter().map(|t| t.into()).collect(),
+        }))
+    }
+
+    async fn list_comments(
+        &self,
+        request: Request<ListCommentsRequest>,
+    ) -> Result<Response<ListCommentsResponse>, Status> {
+        let user = self.optional_user(request.metadata()).await;
+        let post_id = request.into_inner().post_id;
+        let comments = self
+            .comment_service
+            .get_comments(post_id, user.as_ref().map(|u| &u.session))
+            .await?;
+        Ok(Response::new(ListCommentsResponse {
+            comments: comments.into_iter().map(|c| c.into()).collect(),
+        }))
+    }
+
+    async fn create_comment(
+        &self,
+        request: Request<CreateCommentRequest>,
+    ) -> Result<Response<CommentResponse>, Status> {
+        let token = extract_token_from_metadata(request.metadata())?;
+        let user_id = extract_user_from_token(token, &self.auth_service).await?;
+        user_id.session.require_scope(TokenScope::CommentsWrite)?;
+        let comment = request.into_inner();
+        let post_id = comment.post_id;
+        let comment = self
+            .comment_service
+            .create_comment(post_id, comment.into(), &user_id.session)
+            .await?;
+        Ok(Response::new(CommentResponse {
+            comment: Some(comment.into()),
+        }))
+    }
+
+    async fn react(
+        &self,
+        request: Request<ReactionRequest>,
+    ) -> Result<Response<PostReactions>, Status> {
+        let token = extract_token_from_metadata(request.metadata())?;
+        let user_id = extract_user_from_token(token, &self.auth_service).await?;
+        user_id.session.require_scope(TokenScope::CommentsWrite)?;
+        let request = request.into_inner();
+
+        let reactions = self
+            .blog_service
+            .react(request.post_id, user_id.id, request.kind.parse()?)
+            .await?;
+        Ok(Response::new(reactions.into()))
+    }
+
+    async fn unreact(
+        &self,
+        request: Request<ReactionRequest>,
+    ) -> Result<Response<PostReactions>, Status> {
+        let token = extract_token_from_metadata(request.metadata())?;
+        let user_id = extract_user_from_token(token, &self.auth_service).await?;
+        user_id.session.require_scope(TokenScope::CommentsWrite)?;
+        let request = request.into_inner();
+
+        let reactions = self
+            .blog_service
+            .unreact(request.post_id, user_id.id, request.kind.parse()?)
+            .await?;
+        Ok(Response::new(reactions.into()))
+    }
+
+    async fn update_comment(
+        &self,
+        request: Request<UpdateCommentRequest>,
+    ) -> Result<Response<CommentResponse>, Status> {
+        let token = extract_token_from_metadata(request.metadata())?;
+        let user_id = extract_user_from_token(token, &self.auth_service).await?;
+        user_id.session.require_scope(TokenScope::CommentsWrite)?;
+        let update = request.into_inner();
+        let comment = self
+            .comment_service
+            .update_comment(
+                update.id,
+                UpdateComment {
+                    content: update.content,
+                },
+                user_id.id,
+            )
+            .await?;
+        Ok(Response::new(CommentResponse {
+            comment: Some(comment.into()),
+        }))
+    }
+
+    async fn delete_comment(
+        &self,
+        request: Request<DeleteCommentRequest>,
+    ) -> Result<Response<DeleteCommentResponse>, Status> {
+        let token = extract_token_from_metadata(request.metadata())?;
+        let user_id = extract_user_from_token(token, &self.auth_service).await?;
+        user_id.session.require_scope(TokenScope::CommentsWrite)?;
+        let comment_id = request.into_inner().comment_id;
+        self.comment_service
+            .delete_comment(comment_id, &user_id.session)
+            .await?;
+        Ok(Response::new(DeleteCommentResponse { success: true }))
+    }
+
+    async fn list_revisions(
+        &self,
+        request: Request<ListRevisionsRequest>,
+    ) -> Result<Response<ListRevisionsResponse>, Status> {
+        let post_id = request.into_inner().post_id;
+        let post = self.blog_service.get_published_post(post_id).await?;
+        let revisions = self.blog_service.get_revisions(post.id).await?;
+        Ok(Response::new(ListRevisionsResponse {
+            revisions: revisions.into_iter().map(|r| r.into()).collect(),
+        }))
+    }
+
+    async fn diff_revisions(
+        &self,
+        request: Request<DiffRevisionsRequest>,
+    ) -> Result<Response<RevisionDiff>, Status> {
+        let diff_request = request.into_inner();
+        let post = self
+            .blog_service
+            .get_published_post(diff_request.post_id)
+            .await?;
+        let diff = self
+            .blog_service
+            .diff_revisions(&post, diff_request.from, diff_request.to)
+            .await?;
+        Ok(Response::new(diff.into()))
+    }
+
+    async fn restore_revision(
+        &self,
+        request: Request<RestoreRevisionRequest>,
+    ) -> Result<Response<PostResponse>, Status> {
+        let token = extract_token_from_metadata(request.metadata())?;
+        let user_id = extract_user_from_token(token, &self.auth_service).await?;
+        user_id.session.require_scope(TokenScope::PostsWrite)?;
+        let restore = request.into_inner();
+        let post = self.blog_service.get_post_by_id(restore.post_id).await?;
+        user_id.session.ensure_can_modify(post.author_id)?;
+
+        let post = self
+            .blog_service
+            .restore_revision(&post, restore.revision, user_id.id)
+            .await?;
+        Ok(Response::new(PostResponse {
+            post: Some(post.into()),
+        }))
+    }
+
+    async fn search_posts(
+        &self,
+        request: Request<SearchPostsRequest>,
+    ) -> Result<Response<SearchPostsResponse>, Status> {
+        let search = request.into_inner();
+        let results = self
+            .blog_service
+            .search_posts(&search.query, search.offset as i64, search.limit as i64)
+            .await?;
+        Ok(Response::new(SearchPostsResponse {
+            hits: results.hits.into_iter().map(|h| h.into()).collect(),
+            total: results.total as i32,
+        }))
+    }
+
+    async fn upload_attachment(
+        &self,
+        request: Request<Streaming<UploadAttachmentRequest>>,
+    ) -> Result<Response<AttachmentResponse>, Status> {
+        let token = extract_token_from_metadata(request.metadata())?;
+        let user_id = extract_user_from_token(token, &self.auth_service).await?;
+        user_id.session.require_scope(TokenScope::PostsWrite)?;
+        let mut stream = request.into_inner();
+
+        let meta = match stream.message().await? {
+            Some(UploadAttachmentRequest {
+                data: Some(upload_attachment_request::Data::Meta(meta)),
+            }) => meta,
+            _ => {
+                return Err(Status::invalid_argument(
+                    "first message must contain attachment meta",
+                ));
+            }
+        };
+
+        let mut data = Vec::new();
+        while let Some(message) = stream.message().await? {
+            match message.data {
+                Some(upload_attachment_request::Data::Chunk(chunk)) => {
+                    self.attachment_service
+                        .check_size(data.len() + chunk.len())?;
+                    data.extend_from_slice(&chunk);
+                }
+                _ => return Err(Status::invalid_argument("expected file chunk")),
+            }
+        }
+
+        let attachment = self
+            .attachment_service
+            .upload(
+                user_id.id,
+                UploadAttachment {
+                    file_name: meta.file_name,
+                    post_id: meta.post_id,
+                    data,
+                },
+            )
+            .await?;
+        Ok(Response::new(AttachmentResponse {
+            attachment: Some(attachment.into()),
+        }))
+    }
+
+    async fn list_users(
+        &self,
+        request: Request<ListUsersRequest>,
+    ) -> Result<Response<ListUsersResponse>, Status> {
+        let token = extract_token_from_metadata(request.metadata())?;
+        let user = extract_user_from_token(token, &self.auth_service).await?;
+        let request = request.into_inner();
+
+        let users = self
+            .auth_service
+            .list_users(&user.session, request.limit.into(), request.offset.into())
+            .await?;
+        Ok(Response::new(ListUsersResponse {
+            users: users.into_iter().map(|u| u.into()).collect(),
+        }))
+    }
+
+    async fn set_user_role(
+        &self,
+        request: Request<SetUserRoleRequest>,
+    ) -> Result<Response<UserResponse>, Status> {
+        let token = extract_token_from_metadata(request.metadata())?;
+        let user = extract_user_from_token(token, &self.auth_service).await?;
+        let request = request.into_inner();
+        let target_id = Uuid::parse_str(&request.user_id)
+            .map_err(|_| Status::invalid_argument("invalid user id"))?;
+        let role: Role = request.role.parse()?;
+
+        let target = self
+            .auth_service
+            .set_user_role(&user.session, target_id, role)
+            .await?;
+        Ok(Response::new(UserResponse {
+            user: Some(target.into()),
+        }))
+    }
+
+    async fn create_personal_access_token(
+        &self,
+        request: Request<CreatePersonalAccessTokenRequest>,
+    ) -> Result<Response<CreatePersonalAccessTokenResponse>, Status> {
+        let token = extract_token_from_metadata(request.metadata())?;
+        let user = extract_user_from_token(token, &self.auth_service).await?;
+        let request = request.into_inner();
+        let data = CreatePersonalToken {
+            name: request.name,
+            scopes: request
+                .scopes
+                .iter()
+                .map(|s| s.parse())
+                .collect::<Result<_, BlogError>>()?,
+            expires_in_days: request.expires_in_days,
+        };
+
+        let (personal_token, secret) = self
+            .auth_service
+            .create_personal_token(&user.session, data)
+            .await?;
+        Ok(Response::new(CreatePersonalAccessTokenResponse {
+            personal_access_token: Some(personal_token.into()),
+            token: secret,
+        }))
+    }
+
+    async fn list_personal_access_tokens(
+        &self,
+        request: Request<ListPersonalAccessTokensRequest>,
+    ) -> Result<Response<ListPersonalAccessTokensResponse>, Status> {
+        let token = extract_token_from_metadata(request.metadata())?;
+        let user = extract_user_from_token(token, &self.auth_service).await?;
+
+        let tokens = self
+            .auth_service
+            .list_personal_tokens(&user.session)
+            .await?;
+        Ok(Response::new(ListPersonalAccessTokensResponse {
+            tokens: tokens.into_iter().map(|t| t.into()).collect(),
+        }))
+    }
+
+    async fn revoke_personal_access_token(
+        &self,
+        request: Request<RevokePersonalAccessTokenRequest>,
+    ) -> Result<Response<RevokePersonalAccessTokenResponse>, Status> {
+        let token = extract_token_from_metadata(request.metadata())?;
+        let user = extract_user_from_token(token, &self.auth_service).await?;
+        let token_id = Uuid::parse_str(&request.into_inner().id)
+            .map_err(|_| Status::invalid_argument("invalid token id"))?;
+
+        self.auth_service
+            .revoke_personal_token(&user.session, token_id)
+            .await?;
+        Ok(Response::new(RevokePersonalAccessTokenResponse {
+            success: true,
+        }))
+    }
+
+    async fn get_user_profile(
+        &self,
+        request: Request<GetUserProfileRequest>,
+    ) -> Result<Response<UserProfileResponse>, Status> {
+        let profile = self
+            .auth_service
+            .get_profile(&request.into_inner().username)
+            .await?;
+
+        Ok(Response::new(UserProfileResponse {
+            profile: Some(profile.into()),
+        }))
+    }
+
+    async fn list_user_posts(
+        &self,
+        request: Request<ListUserPostsRequest>,
+    ) -> Result<Response<ListPostsResponse>, Status> {
+        let user = self.optional_user(request.metadata()).await;
+        let request = request.into_inner();
+        let profile = self.auth_service.get_profile(&request.username).await?;
+        let mut page = self
+            .blog_service
+            .get_posts_by_user(profile.id, request.offset.into(), request.limit.into())
+            .await?;
+        self.blog_service
+            .mark_bookmarked(user.map(|u| u.id), &mut page.posts)
+            .await?;
+
+        Ok(Response::new(ListPostsResponse {
+            post: page.posts.into_iter().map(|p| p.into()).collect(),
+            total: page.total as i32,
+            limit: request.limit,
+            offset: request.offset,
+            next_cursor: page.next_cursor,
+        }))
+    }
+
+    async fn update_profile(
+        &self,
+        request: Request<UpdateProfileRequest>,
+    ) -> Result<Response<UserProfileResponse>, Status> {
+        let token = extract_token_from_metadata(request.metadata())?;
+        let user = extract_user_from_token(token, &self.auth_service).await?;
+        user.session.require_interactive()?;
+
+        let profile = self
+            .auth_service
+            .update_profile(user.id, request.into_inner().into())
+            .await?;
+        Ok(Response::new(UserProfileResponse {
+            profile: Some(profile.into()),
+        }))
+    }
+
+    async fn follow(
+        &self,
+        request: Request<FollowRequest>,
+    ) -> Result<Response<FollowStatus>, Status> {
+        let token = extract_token_from_metadata(request.metadata())?;
+        let user = extract_user_from_token(token, &self.auth_service).await?;
+        user.session.require_interactive()?;
+
+        let status = self
+            .auth_service
+            .follow(user.id, &request.into_inner().username)
+            .await?;
+        Ok(Response::new(status.into()))
+    }
+
+    async fn unfollow(
+        &self,
+        request: Request<FollowRequest>,
+    ) -> Result<Response<FollowStatus>, Status> {
+        let token = extract_token_from_metadata(request.metadata())?;
+        let user = extract_user_from_token(token, &self.auth_service).await?;
+        user.session.require_interactive()?;
+
+        let status = self
+            .auth_service
+            .unfollow(user.id, &request.into_inner().username)
+            .await?;
+        Ok(Response::new(status.into()))
+    }
+
+    async fn list_followers(
+        &self,
+        request: Request<ListFollowsRequest>,
+    ) -> Result<Response<ListFollowsResponse>, Status> {
+        let request = request.into_inner();
+        let page = self
+            .auth_service
+            .get_followers(
+                &request.username,
+                request.limit.into(),
+                request.offset.into(),
+            )
+            .await?;
+
+        Ok(Response::new(ListFollowsResponse {
+            users: page.users.into_iter().map(|u| u.into()).collect(),
+            total: page.total as i32,
+            limit: request.limit,
+            offset: request.offset,
+        }))
+    }
+
+    async fn list_following(
+        &self,
+        request: Request<ListFollowsRequest>,
+    ) -> Result<Response<ListFollowsResponse>, Status> {
+        let request = request.into_inner();
+        let page = self
+            .auth_service
+            .get_following(
+                &request.username,
+                request.limit.into(),
+                request.offset.into(),
+            )
+            .await?;
+
+        Ok(Response::new(ListFollowsResponse {
+            users: page.users.into_iter().map(|u| u.into()).collect(),
+            total: page.total as i32,
+            limit: request.limit,
+            offset: request.offset,
+        }))
+    }
+
+    async fn get_feed(
+        &self,
+        request: Request<FeedRequest>,
+    ) -> Result<Response<ListPostsResponse>, Status> {
+        let token = extract_token_from_metadata(request.metadata())?;
+        let user = extract_user_from_token(token, &self.auth_service).await?;
+        user.session.require_scope(TokenScope::PostsRead)?;
+        let request = request.into_inner();
+
+        let page = PageRequest::new(request.offset.into(), request.cursor.as_deref())?;
+        let mut page = self
+            .blog_service
+            .get_feed(user.id, page, request.limit.into())
+            .await?;
+        self.blog_service
+            .mark_bookmarked(Some(user.id), &mut page.posts)
+            .await?;
+        Ok(Response::new(ListPostsResponse {
+            post: page.posts.into_iter().map(|p| p.into()).collect(),
+            total: page.total as i32,
+            limit: request.limit,
+            offset: request.offset,
+            next_cursor: page.next_cursor,
+        }))
+    }
+
+    async fn add_bookmark(
+        &self,
+        request: Request<BookmarkRequest>,
+    ) -> Result<Response<BookmarkResponse>, Status> {
+        let token = extract_token_from_metadata(request.metadata())?;
+        let user = extract_user_from_token(token, &self.auth_service).await?;
+        user.session.require_scope(TokenScope::PostsRead)?;
+
+        let bookmark = self
+            .blog_service
+            .add_bookmark(user.id, request.into_inner().post_id)
+            .await?;
+        Ok(Response::new(bookmark.into()))
+    }
+
+    async fn remove_bookmark(
+        &self,
+        request: Request<BookmarkRequest>,
+    ) -> Result<Response<BookmarkResponse>, Status> {
+        let token = extract_token_from_metadata(request.metadata())?;
+        let user = extract_user_from_token(token, &self.auth_service).await?;
+        user.session.require_scope(TokenScope::PostsRead)?;
+
+        let bookmark = self
+            .blog_service
+            .remove_bookmark(user.id, request.into_inner().post_id)
+            .await?;
+        Ok(Response::new(bookmark.into()))
+    }
+
+    async fn list_bookmarks(
+        &self,
+        request: Request<ListBookmarksRequest>,
+    ) -> Result<Response<ListPostsResponse>, Status> {
+        let token = extract_token_from_metadata(request.metadata())?;
+        let user = extract_user_from_token(token, &self.auth_service).await?;
+        user.session.require_scope(TokenScope::PostsRead)?;
         let request = request.into_inner();
 
-        let posts = self
+        let page = self
             .blog_service
-            .get_next_posts(request.offset.into(), request.limit.into())
+            .get_bookmarks(user.id, request.offset.into(), request.limit.into())
             .await?;
-        let total = posts.len() as i32;
         Ok(Response::new(ListPostsResponse {
-            post: posts.into_iter().map(|p| p.into()).collect(),
-            total,
+            post: page.posts.into_iter().map(|p| p.into()).collect(),
+            total: page.total as i32,
             limit: request.limit,
             offset: request.offset,
+            next_cursor: page.next_cursor,
+        }))
+    }
+
+    async fn export_account(
+        &self,
+        request: Request<ExportAccountRequest>,
+    ) -> Result<Response<ExportAccountResponse>, Status> {
+        let token = extract_token_from_metadata(request.metadata())?;
+        let user = extract_user_from_token(token, &self.auth_service).await?;
+        user.session.require_interactive()?;
+
+        let export = self.account_service.export(user.id).await?;
+        let data =
+            serde_json::to_vec_pretty(&export).map_err(|e| BlogError::Internal(e.to_string()))?;
+        Ok(Response::new(ExportAccountResponse {
+            file_name: export.file_name(),
+            data,
         }))
     }
+
+    async fn delete_account(
+        &self,
+        request: Request<DeleteAccountRequest>,
+    ) -> Result<Response<DeleteAccountResponse>, Status> {
+        let token = extract_token_from_metadata(request.metadata())?;
+        let user = extract_user_from_token(token, &self.auth_service).await?;
+        user.session.require_interactive()?;
+
+        let data = DeleteAccount::try_from(request.into_inner())?;
+        self.account_service.delete_account(user.id, data).await?;
+        Ok(Response::new(DeleteAccountResponse { success: true }))
+    }
 }
 
 fn extract_token_from_metadata(metadata: &MetadataMap) -> Result<&str, Status> {
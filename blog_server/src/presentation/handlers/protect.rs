@@ -1,39 +1,89 @@
-use actix_web::{HttpMessage, HttpRequest, HttpResponse, Scope, delete, post, put, web};
-
+use actix_multipart::Multipart;
+use actix_web::{
+    HttpMessage, HttpRequest, HttpResponse, Scope, delete, get, http::header, post, put, web,
+};
+use futures_util::TryStreamExt;
 use uuid::Uuid;
 
 use crate::{
-    application::BlogService,
-    data::posr_repository::PostgresPostRepository,
+    application::{AccountService, AttachmentService, AuthService, BlogService, CommentService},
+    data::{
+        attachment_repository::PostgresAttachmentRepository,
+        comment_repository::PostgresCommentRepository, posr_repository::PostgresPostRepository,
+        storage::LocalFileStorage, user_repository::PostgresUserRepository,
+    },
     domain::{
+        account::DeleteAccount,
+        attachment::UploadAttachment,
+        auth::{ChangePassword, RefreshRequest, SetRole, TwoFactorCode},
+        comment::{CreateComment, UpdateComment},
         error::BlogError,
-        post::{CreatePost, UpdatePost},
+        pagination::PageRequest,
+        personal_token::{CreatePersonalToken, TokenScope},
+        post::{CreatePost, PostStatus, UpdatePost},
+        profile::UpdateProfile,
+        role::Role,
+    },
+    presentation::{
+        auth::AuthenticatedUser,
+        dto::{self, AuthResponse},
     },
-    presentation::auth::AuthenticatedUser,
 };
 
 pub fn scope() -> Scope {
     web::scope("")
         .service(create_post)
+        .service(get_own_post)
+        .service(get_drafts)
+        .service(get_feed)
         .service(update_post)
+        .service(publish_post)
+        .service(unpublish_post)
+        .service(archive_post)
+        .service(restore_revision)
         .service(delipe_post)
-}
-
-fn ensure_owner(autor: Uuid, user: &AuthenticatedUser) -> Result<(), BlogError> {
-    if autor != user.id {
-        Err(BlogError::Forbidden)
-    } else {
-        Ok(())
-    }
+        .service(create_comment)
+        .service(update_comment)
+        .service(delete_comment)
+        .service(react)
+        .service(unreact)
+        .service(add_bookmark)
+        .service(remove_bookmark)
+        .service(get_bookmarks)
+        .service(upload_attachment)
+        .service(logout)
+        .service(logout_all)
+        .service(change_password)
+        .service(resend_verification)
+        .service(update_profile)
+        .service(get_follow_status)
+        .service(follow)
+        .service(unfollow)
+        .service(export_account)
+        .service(delete_account)
+        .service(enroll_two_factor)
+        .service(confirm_two_factor)
+        .service(disable_two_factor)
+        .service(list_users)
+        .service(set_user_role)
+        .service(create_personal_token)
+        .service(list_personal_tokens)
+        .service(revoke_personal_token)
 }
 
 #[post("/post")]
 async fn create_post(
     req: HttpRequest,
     user: AuthenticatedUser,
+    auth_service: web::Data<AuthService<PostgresUserRepository>>,
     blog_service: web::Data<BlogService<PostgresPostRepository>>,
     payload: web::Json<CreatePost>,
 ) -> Result<HttpResponse, BlogError> {
+    user.session.require_scope(TokenScope::PostsWrite)?;
+    user.session.require_role(Role::Author)?;
+    if payload.publishes() {
+        auth_service.ensure_can_publish(&user.session)?;
+    }
     let post = blog_service
         .create_post(payload.into_inner(), user.id)
         .await?;
@@ -48,20 +98,84 @@ async fn create_post(
     Ok(HttpResponse::Created().json(post))
 }
 
+/// Пост в любом статусе, доступен автору и модераторам
+#[get("/post/{id}")]
+async fn get_own_post(
+    user: AuthenticatedUser,
+    blog_service: web::Data<BlogService<PostgresPostRepository>>,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, BlogError> {
+    user.session.require_scope(TokenScope::PostsRead)?;
+    let post = blog_service.get_post_by_id(path.into_inner()).await?;
+    user.session.ensure_can_modify(post.author_id)?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "post": post })))
+}
+
+/// Посты авторов из подписок, листаются как `/api/posts`
+#[get("/feed")]
+async fn get_feed(
+    user: AuthenticatedUser,
+    blog_service: web::Data<BlogService<PostgresPostRepository>>,
+    params: web::Query<dto::PaginationParams>,
+) -> Result<HttpResponse, BlogError> {
+    user.session.require_scope(TokenScope::PostsRead)?;
+    let page = PageRequest::new(params.offset as i64, params.cursor.as_deref())?;
+    let mut page = blog_service
+        .get_feed(user.id, page, params.limit as i64)
+        .await?;
+    blog_service
+        .mark_bookmarked(Some(user.id), &mut page.posts)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(dto::ListPostsResponse {
+        post: page.posts,
+        total: page.total as i32,
+        limit: params.limit,
+        offset: params.offset,
+        next_cursor: page.next_cursor,
+    }))
+}
+
+#[get("/drafts")]
+async fn get_drafts(
+    user: AuthenticatedUser,
+    blog_service: web::Data<BlogService<PostgresPostRepository>>,
+    params: web::Query<dto::PaginationParams>,
+) -> Result<HttpResponse, BlogError> {
+    user.session.require_scope(TokenScope::PostsRead)?;
+    let page = blog_service
+        .get_drafts(user.id, params.offset as i64, params.limit as i64)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(dto::ListPostsResponse {
+        post: page.posts,
+        total: page.total as i32,
+        limit: params.limit,
+        offset: params.offset,
+        next_cursor: page.next_cursor,
+    }))
+}
+
 #[put("/post/{id}")]
 async fn update_post(
     req: HttpRequest,
     user: AuthenticatedUser,
+    auth_service: web::Data<AuthService<PostgresUserRepository>>,
     blog_service: web::Data<BlogService<PostgresPostRepository>>,
     path: web::Path<i64>,
     payload: web::Json<UpdatePost>,
 ) -> Result<HttpResponse, BlogError> {
+    user.session.require_scope(TokenScope::PostsWrite)?;
+    if payload.publishes() {
+        auth_service.ensure_can_publish(&user.session)?;
+    }
     let post_id = path.into_inner();
     let post = blog_service.get_post_by_id(post_id).await?;
-    ensure_owner(post.author_id, &user)?;
+    user.session.ensure_can_modify(post.author_id)?;
 
     let update_post = blog_service
-        .update_post(&post, payload.into_inner())
+        .update_post(&post, payload.into_inner(), user.id)
         .await?;
 
     tracing::info!(
@@ -74,6 +188,110 @@ async fn update_post(
     Ok(HttpResponse::Ok().json(update_post))
 }
 
+#[post("/post/{id}/publish")]
+async fn publish_post(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    auth_service: web::Data<AuthService<PostgresUserRepository>>,
+    blog_service: web::Data<BlogService<PostgresPostRepository>>,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, BlogError> {
+    auth_service.ensure_can_publish(&user.session)?;
+    change_status(
+        req,
+        user,
+        blog_service,
+        path.into_inner(),
+        PostStatus::Published,
+    )
+    .await
+}
+
+#[post("/post/{id}/unpublish")]
+async fn unpublish_post(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    blog_service: web::Data<BlogService<PostgresPostRepository>>,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, BlogError> {
+    change_status(
+        req,
+        user,
+        blog_service,
+        path.into_inner(),
+        PostStatus::Draft,
+    )
+    .await
+}
+
+#[post("/post/{id}/archive")]
+async fn archive_post(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    blog_service: web::Data<BlogService<PostgresPostRepository>>,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, BlogError> {
+    change_status(
+        req,
+        user,
+        blog_service,
+        path.into_inner(),
+        PostStatus::Archived,
+    )
+    .await
+}
+
+async fn change_status(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    blog_service: web::Data<BlogService<PostgresPostRepository>>,
+    post_id: i64,
+    status: PostStatus,
+) -> Result<HttpResponse, BlogError> {
+    user.session.require_scope(TokenScope::PostsWrite)?;
+    let post = blog_service.get_post_by_id(post_id).await?;
+    user.session.ensure_can_modify(post.author_id)?;
+
+    let post = blog_service.change_status(&post, status).await?;
+
+    tracing::info!(
+        request_id = %request_id(&req),
+        user_id = %user.id,
+        post_id = %post.id,
+        status = %status.as_str(),
+        "Post status changed",
+    );
+
+    Ok(HttpResponse::Ok().json(post))
+}
+
+#[post("/post/{id}/revisions/{revision}/restore")]
+async fn restore_revision(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    blog_service: web::Data<BlogService<PostgresPostRepository>>,
+    path: web::Path<(i64, i32)>,
+) -> Result<HttpResponse, BlogError> {
+    user.session.require_scope(TokenScope::PostsWrite)?;
+    let (post_id, revision) = path.into_inner();
+    let post = blog_service.get_post_by_id(post_id).await?;
+    user.session.ensure_can_modify(post.author_id)?;
+
+    let post = blog_service
+        .restore_revision(&post, revision, user.id)
+        .await?;
+
+    tracing::info!(
+        request_id = %request_id(&req),
+        user_id = %user.id,
+        post_id = %post.id,
+        revision = %revision,
+        "Post revision restored",
+    );
+
+    Ok(HttpResponse::Ok().json(post))
+}
+
 #[delete("/post/{id}")]
 async fn delipe_post(
     req: HttpRequest,
@@ -81,9 +299,10 @@ async fn delipe_post(
     blog_service: web::Data<BlogService<PostgresPostRepository>>,
     path: web::Path<i64>,
 ) -> Result<HttpResponse, BlogError> {
+    user.session.require_scope(TokenScope::PostsWrite)?;
     let post_id = path.into_inner();
     let post = blog_service.get_post_by_id(post_id).await?;
-    ensure_owner(post.author_id, &user)?;
+    user.session.ensure_can_modify(post.author_id)?;
 
     blog_service.delete_post(post.id).await?;
 
@@ -97,9 +316,710 @@ async fn delipe_post(
     Ok(HttpResponse::Ok().json(serde_json::json!({"post": post_id, "delete": true})))
 }
 
+#[post("/post/{id}/comment")]
+async fn create_comment(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    comment_service: web::Data<CommentService<PostgresCommentRepository, PostgresPostRepository>>,
+    path: web::Path<i64>,
+    payload: web::Json<CreateComment>,
+) -> Result<HttpResponse, BlogError> {
+    user.session.require_scope(TokenScope::CommentsWrite)?;
+    let post_id = path.into_inner();
+    let comment = comment_service
+        .create_comment(post_id, payload.into_inner(), &user.session)
+        .await?;
+
+    tracing::info!(
+        request_id = %request_id(&req),
+        user_id = %user.id,
+        post_id = %post_id,
+        comment_id = %comment.id,
+        "Comment created",
+    );
+
+    Ok(HttpResponse::Created().json(comment))
+}
+
+/// Реакции ставятся с тем же правом токена доступа, что и комментарии
+#[put("/post/{id}/reaction/{kind}")]
+async fn react(
+    user: AuthenticatedUser,
+    blog_service: web::Data<BlogService<PostgresPostRepository>>,
+    path: web::Path<(i64, String)>,
+) -> Result<HttpResponse, BlogError> {
+    user.session.require_scope(TokenScope::CommentsWrite)?;
+    let (post_id, kind) = path.into_inner();
+    let reactions = blog_service.react(post_id, user.id, kind.parse()?).await?;
+
+    Ok(HttpResponse::Ok().json(reactions))
+}
+
+#[delete("/post/{id}/reaction/{kind}")]
+async fn unreact(
+    user: AuthenticatedUser,
+    blog_service: web::Data<BlogService<PostgresPostRepository>>,
+    path: web::Path<(i64, String)>,
+) -> Result<HttpResponse, BlogError> {
+    user.session.require_scope(TokenScope::CommentsWrite)?;
+    let (post_id, kind) = path.into_inner();
+    let reactions = blog_service
+        .unreact(post_id, user.id, kind.parse()?)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(reactions))
+}
+
+/// Закладки - личный список чтения, поэтому и читаются, и меняются с правом `posts:read`
+#[put("/post/{id}/bookmark")]
+async fn add_bookmark(
+    user: AuthenticatedUser,
+    blog_service: web::Data<BlogService<PostgresPostRepository>>,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, BlogError> {
+    user.session.require_scope(TokenScope::PostsRead)?;
+    let bookmark = blog_service
+        .add_bookmark(user.id, path.into_inner())
+        .await?;
+
+    Ok(HttpResponse::Ok().json(bookmark))
+}
+
+#[delete("/post/{id}/bookmark")]
+async fn remove_bookmark(
+    user: AuthenticatedUser,
+    blog_service: web::Data<BlogService<PostgresPostRepository>>,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, BlogError> {
+    user.session.require_scope(TokenScope::PostsRead)?;
+    let bookmark = blog_service
+        .remove_bookmark(user.id, path.into_inner())
+        .await?;
+
+    Ok(HttpResponse::Ok().json(bookmark))
+}
+
+/// Сохранённые посты, от новых закладок к старым
+#[get("/bookmarks")]
+async fn get_bookmarks(
+    user: AuthenticatedUser,
+    blog_service: web::Data<BlogService<PostgresPostRepository>>,
+    params: web::Query<dto::PaginationParams>,
+) -> Result<HttpResponse, BlogError> {
+    user.session.require_scope(TokenScope::PostsRead)?;
+    let page = blog_service
+        .get_bookmarks(user.id, params.offset as i64, params.limit as i64)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(dto::ListPostsResponse {
+        post: page.posts,
+        total: page.total as i32,
+        limit: params.limit,
+        offset: params.offset,
+        next_cursor: page.next_cursor,
+    }))
+}
+
+#[put("/comment/{id}")]
+async fn update_comment(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    comment_service: web::Data<CommentService<PostgresCommentRepository, PostgresPostRepository>>,
+    path: web::Path<i64>,
+    payload: web::Json<UpdateComment>,
+) -> Result<HttpResponse, BlogError> {
+    user.session.require_scope(TokenScope::CommentsWrite)?;
+    let comment = comment_service
+        .update_comment(path.into_inner(), payload.into_inner(), user.id)
+        .await?;
+
+    tracing::info!(
+        request_id = %request_id(&req),
+        user_id = %user.id,
+        comment_id = %comment.id,
+        "Comment update",
+    );
+
+    Ok(HttpResponse::Ok().json(comment))
+}
+
+#[delete("/comment/{id}")]
+async fn delete_comment(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    comment_service: web::Data<CommentService<PostgresCommentRepository, PostgresPostRepository>>,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, BlogError> {
+    user.session.require_scope(TokenScope::CommentsWrite)?;
+    let comment_id = path.into_inner();
+    let comment = comment_service
+        .delete_comment(comment_id, &user.session)
+        .await?;
+
+    tracing::info!(
+        request_id = %request_id(&req),
+        user_id = %user.id,
+        post_id = %comment.post_id,
+        comment_id = %comment_id,
+        "Comment delete",
+    );
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({"comment": comment_id, "delete": true})))
+}
+
+/// multipart/form-data с полем `file`. Размер проверяется по мере чтения
+#[post("/attachments")]
+async fn upload_attachment(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    attachment_service: web::Data<
+        AttachmentService<PostgresAttachmentRepository, LocalFileStorage, PostgresPostRepository>,
+    >,
+    params: web::Query<dto::UploadParams>,
+    mut payload: Multipart,
+) -> Result<HttpResponse, BlogError> {
+    user.session.require_scope(TokenScope::PostsWrite)?;
+    let mut upload = None;
+
+    while let Some(mut field) = payload
+        .try_next()
+        .await
+        .map_err(|e| BlogError::Validation(e.to_string()))?
+    {
+        if field.name() != Some("file") {
+            continue;
+        }
+
+        let file_name = field
+            .content_disposition()
+            .and_then(|cd| cd.get_filename())
+            .unwrap_or_default()
+            .to_string();
+        let mut data = Vec::new();
+        while let Some(chunk) = field
+            .try_next()
+            .await
+            .map_err(|e| BlogError::Validation(e.to_string()))?
+        {
+            attachment_service.check_size(data.len() + chunk.len())?;
+            data.extend_from_slice(&chunk);
+        }
+
+        upload = Some(UploadAttachment {
+            file_name,
+            post_id: params.post_id,
+            data,
+        });
+        break;
+    }
+
+    let upload =
+        upload.ok_or_else(|| BlogError::Validation("file field is missing".to_string()))?;
+    let attachment = attachment_service.upload(user.id, upload).await?;
+
+    tracing::info!(
+        request_id = %request_id(&req),
+        user_id = %user.id,
+        attachment_id = %attachment.id,
+        "Attachment uploaded",
+    );
+
+    Ok(HttpResponse::Created().json(dto::AttachmentResponse {
+        attachment: attachment.into(),
+    }))
+}
+
+/// Отзывает текущий access токен и семейство refresh токена,
+/// т.е. завершает сессию на этом устройстве
+#[post("/logout")]
+async fn logout(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    auth_service: web::Data<AuthService<PostgresUserRepository>>,
+    payload: web::Json<RefreshRequest>,
+) -> Result<HttpResponse, BlogError> {
+    user.session.require_interactive()?;
+    auth_service
+        .logout(&user.session, &payload.refresh_token)
+        .await?;
+
+    tracing::info!(
+        request_id = %request_id(&req),
+        user_id = %user.id,
+        "User logged out",
+    );
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Завершает все сессии пользователя
+#[post("/logout-all")]
+async fn logout_all(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    auth_service: web::Data<AuthService<PostgresUserRepository>>,
+) -> Result<HttpResponse, BlogError> {
+    user.session.require_interactive()?;
+    auth_service.logout_everywhere(user.id).await?;
+
+    tracing::info!(
+        request_id = %request_id(&req),
+        user_id = %user.id,
+        "User logged out everywhere",
+    );
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Смена пароля. Остальные сессии завершаются, в ответе новая пара токенов
+#[post("/account/password")]
+async fn change_password(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    auth_service: web::Data<AuthService<PostgresUserRepository>>,
+    payload: web::Json<ChangePassword>,
+) -> Result<HttpResponse, BlogError> {
+    user.session.require_interactive()?;
+    let answer = auth_service
+        .change_password(user.id, payload.into_inner())
+        .await?;
+
+    tracing::info!(
+        request_id = %request_id(&req),
+        user_id = %user.id,
+        "Password changed",
+    );
+
+    Ok(HttpResponse::Ok().json(AuthResponse::from(answer)))
+}
+
+/// Профиль заменяется целиком, в ответе сохранённый профиль
+#[put("/account/profile")]
+async fn update_profile(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    auth_service: web::Data<AuthService<PostgresUserRepository>>,
+    payload: web::Json<UpdateProfile>,
+) -> Result<HttpResponse, BlogError> {
+    user.session.require_interactive()?;
+    let profile = auth_service
+        .update_profile(user.id, payload.into_inner())
+        .await?;
+
+    tracing::info!(
+        request_id = %request_id(&req),
+        user_id = %user.id,
+        "Profile updated",
+    );
+
+    Ok(HttpResponse::Ok().json(dto::UserProfileResponse { profile }))
+}
+
+/// Подписан ли пользователь на автора
+#[get("/users/{username}/follow")]
+async fn get_follow_status(
+    user: AuthenticatedUser,
+    auth_service: web::Data<AuthService<PostgresUserRepository>>,
+    path: web::Path<String>,
+) -> Result<HttpResponse, BlogError> {
+    let status = auth_service
+        .get_follow_status(user.id, &path.into_inner())
+        .await?;
+
+    Ok(HttpResponse::Ok().json(status))
+}
+
+#[put("/users/{username}/follow")]
+async fn follow(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    auth_service: web::Data<AuthService<PostgresUserRepository>>,
+    path: web::Path<String>,
+) -> Result<HttpResponse, BlogError> {
+    user.session.require_interactive()?;
+    let status = auth_service.follow(user.id, &path.into_inner()).await?;
+
+    tracing::info!(
+        request_id = %request_id(&req),
+        user_id = %user.id,
+        followee = %status.username,
+        "Author followed",
+    );
+
+    Ok(HttpResponse::Ok().json(status))
+}
+
+#[delete("/users/{username}/follow")]
+async fn unfollow(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    auth_service: web::Data<AuthService<PostgresUserRepository>>,
+    path: web::Path<String>,
+) -> Result<HttpResponse, BlogError> {
+    user.session.require_interactive()?;
+    let status = auth_service.unfollow(user.id, &path.into_inner()).await?;
+
+    tracing::info!(
+        request_id = %request_id(&req),
+        user_id = %user.id,
+        followee = %status.username,
+        "Author unfollowed",
+    );
+
+    Ok(HttpResponse::Ok().json(status))
+}
+
+/// Все данные пользователя одним JSON файлом
+#[get("/account/export")]
+async fn export_account(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    account_service: web::Data<
+        AccountService<
+            PostgresUserRepository,
+            PostgresPostRepository,
+            PostgresCommentRepository,
+            PostgresAttachmentRepository,
+            LocalFileStorage,
+        >,
+    >,
+) -> Result<HttpResponse, BlogError> {
+    user.session.require_interactive()?;
+    let export = account_service.export(user.id).await?;
+    let data =
+        serde_json::to_vec_pretty(&export).map_err(|e| BlogError::Internal(e.to_string()))?;
+
+    tracing::info!(
+        request_id = %request_id(&req),
+        user_id = %user.id,
+        "Account data exported",
+    );
+
+    Ok(HttpResponse::Ok()
+        .insert_header(header::ContentDisposition {
+            disposition: header::DispositionType::Attachment,
+            parameters: vec![header::DispositionParam::Filename(export.file_name())],
+        })
+        .content_type("application/json")
+        .body(data))
+}
+
+/// Удаление аккаунта. Посты и комментарии удаляются или обезличиваются
+#[post("/account/delete")]
+async fn delete_account(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    account_service: web::Data<
+        AccountService<
+            PostgresUserRepository,
+            PostgresPostRepository,
+            PostgresCommentRepository,
+            PostgresAttachmentRepository,
+            LocalFileStorage,
+        >,
+    >,
+    payload: web::Json<DeleteAccount>,
+) -> Result<HttpResponse, BlogError> {
+    user.session.require_interactive()?;
+    account_service
+        .delete_account(user.id, payload.into_inner())
+        .await?;
+
+    tracing::info!(
+        request_id = %request_id(&req),
+        user_id = %user.id,
+        "Account deleted by user",
+    );
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Повторная отправка ссылки для подтверждения email
+#[post("/account/verify-email/resend")]
+async fn resend_verification(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    auth_service: web::Data<AuthService<PostgresUserRepository>>,
+) -> Result<HttpResponse, BlogError> {
+    user.session.require_interactive()?;
+    auth_service.resend_verification(user.id).await?;
+
+    tracing::info!(
+        request_id = %request_id(&req),
+        user_id = %user.id,
+        "Verification mail resent",
+    );
+
+    Ok(HttpResponse::Accepted().finish())
+}
+
+/// Секрет TOTP и коды восстановления. 2FA включается после подтверждения кодом
+#[post("/account/2fa/enroll")]
+async fn enroll_two_factor(
+    user: AuthenticatedUser,
+    auth_service: web::Data<AuthService<PostgresUserRepository>>,
+) -> Result<HttpResponse, BlogError> {
+    let enrollment = auth_service.enroll_two_factor(&user.session).await?;
+
+    Ok(HttpResponse::Ok().json(enrollment))
+}
+
+#[post("/account/2fa/confirm")]
+async fn confirm_two_factor(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    auth_service: web::Data<AuthService<PostgresUserRepository>>,
+    payload: web::Json<TwoFactorCode>,
+) -> Result<HttpResponse, BlogError> {
+    auth_service
+        .confirm_two_factor(&user.session, &payload.code)
+        .await?;
+
+    tracing::info!(
+        request_id = %request_id(&req),
+        user_id = %user.id,
+        "Two-factor authentication enabled",
+    );
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[post("/account/2fa/disable")]
+async fn disable_two_factor(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    auth_service: web::Data<AuthService<PostgresUserRepository>>,
+    payload: web::Json<TwoFactorCode>,
+) -> Result<HttpResponse, BlogError> {
+    auth_service
+        .disable_two_factor(&user.session, &payload.code)
+        .await?;
+
+    tracing::info!(
+        request_id = %request_id(&req),
+        user_id = %user.id,
+        "Two-factor authentication disabled",
+    );
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[get("/admin/users")]
+async fn list_users(
+    user: AuthenticatedUser,
+    auth_service: web::Data<AuthService<PostgresUserRepository>>,
+    params: web::Query<dto::PaginationParams>,
+) -> Result<HttpResponse, BlogError> {
+    let users = auth_service
+        .list_users(&user.session, params.limit as i64, params.offset as i64)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(dto::ListUsersResponse {
+        users: users.into_iter().map(Into::into).collect(),
+    }))
+}
+
+/// Смена роли пользователя, только для администраторов
+#[put("/admin/users/{id}/role")]
+async fn set_user_role(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    auth_service: web::Data<AuthService<PostgresUserRepository>>,
+    path: web::Path<Uuid>,
+    payload: web::Json<SetRole>,
+) -> Result<HttpResponse, BlogError> {
+    let target = auth_service
+        .set_user_role(&user.session, path.into_inner(), payload.role)
+        .await?;
+
+    tracing::info!(
+        request_id = %request_id(&req),
+        user_id = %user.id,
+        target_id = %target.id,
+        role = target.role.as_str(),
+        "User role changed",
+    );
+
+    Ok(HttpResponse::Ok().json(dto::UserResponse {
+        user: target.into(),
+    }))
+}
+
+/// Новый токен доступа. Значение токена есть только в этом ответе
+#[post("/tokens")]
+async fn create_personal_token(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    auth_service: web::Data<AuthService<PostgresUserRepository>>,
+    payload: web::Json<CreatePersonalToken>,
+) -> Result<HttpResponse, BlogError> {
+    let (token, secret) = auth_service
+        .create_personal_token(&user.session, payload.into_inner())
+        .await?;
+
+    tracing::info!(
+        request_id = %request_id(&req),
+        user_id = %user.id,
+        token_id = %token.id,
+        "Personal access token created",
+    );
+
+    Ok(
+        HttpResponse::Created().json(dto::CreatePersonalTokenResponse {
+            personal_access_token: token,
+            token: secret,
+        }),
+    )
+}
+
+#[get("/tokens")]
+async fn list_personal_tokens(
+    user: AuthenticatedUser,
+    auth_service: web::Data<AuthService<PostgresUserRepository>>,
+) -> Result<HttpResponse, BlogError> {
+    let tokens = auth_service.list_personal_tokens(&user.session).await?;
+
+    Ok(HttpResponse::Ok().json(dto::ListPersonalTokensResponse { tokens }))
+}
+
+#[delete("/tokens/{id}")]
+async fn revoke_personal_token(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    auth_service: web::Data<AuthService<PostgresUserRepository>>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, BlogError> {
+    let token_id = path.into_inner();
+    auth_service
+        .revoke_personal_token(&user.session, token_id)
+        .await?;
+
+    tracing::info!(
+        request_id = %request_id(&req),
+        user_id = %user.id,
+        token_id = %token_id,
+        "Personal access token revoked",
+    );
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
 fn request_id(req: &HttpRequest) -> String {
     req.extensions()
         .get::<crate::presentation::RequestId>()
         .map(|rid| rid.0.clone())
         .unwrap_or_else(|| "unknown".into())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use actix_web::{App, dev::Service, http::StatusCode, test};
+
+    use super::*;
+    use crate::data::posr_repository::PostRepository;
+    use crate::data::user_repository::UserRepository;
+    use crate::domain::auth::{AccessSession, User};
+    use crate::domain::content::ContentFormat;
+
+    fn token_user(user_id: Uuid, scopes: Vec<TokenScope>) -> AuthenticatedUser {
+        AuthenticatedUser {
+            id: user_id,
+            session: AccessSession {
+                user_id,
+                jti: Uuid::new_v4(),
+                expires_at: chrono::Utc::now(),
+                email_verified: true,
+                role: Role::Author,
+                scopes: Some(scopes),
+            },
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_feed_requires_posts_read_scope() {
+        // Права проверяются до обращения к базе, поэтому пул не подключается
+        let pool = sqlx::postgres::PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/blog")
+            .expect("valid database url");
+        let blog_service = BlogService::new(Arc::new(PostgresPostRepository::new(pool)));
+        let user_id = Uuid::new_v4();
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(blog_service))
+                .wrap_fn(move |req, srv| {
+                    req.extensions_mut().insert(token_user(
+                        user_id,
+                        vec![TokenScope::PostsWrite, TokenScope::CommentsWrite],
+                    ));
+                    srv.call(req)
+                })
+                .service(get_feed),
+        )
+        .await;
+
+        let response =
+            test::call_service(&app, test::TestRequest::get().uri("/feed").to_request()).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[sqlx::test]
+    async fn test_bookmarks_with_posts_read_token(pool: sqlx::PgPool) {
+        let user = PostgresUserRepository::new(pool.clone())
+            .create_user(User::new(
+                "reader".to_string(),
+                "reader@example.com".to_string(),
+                "hash".to_string(),
+            ))
+            .await
+            .unwrap();
+        let posts = PostgresPostRepository::new(pool);
+        let post = posts
+            .create_post(
+                CreatePost {
+                    title: "Saved".to_string(),
+                    content: "text".to_string(),
+                    content_format: ContentFormat::default(),
+                    tags: vec![],
+                    draft: false,
+                    publish_at: None,
+                },
+                user.id,
+                "<p>text</p>",
+            )
+            .await
+            .unwrap();
+        let blog_service = BlogService::new(Arc::new(posts));
+        let user_id = user.id;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(blog_service))
+                .wrap_fn(move |req, srv| {
+                    req.extensions_mut()
+                        .insert(token_user(user_id, vec![TokenScope::PostsRead]));
+                    srv.call(req)
+                })
+                .service(add_bookmark)
+                .service(remove_bookmark)
+                .service(get_bookmarks),
+        )
+        .await;
+
+        let uri = format!("/post/{}/bookmark", post.id);
+        let response =
+            test::call_service(&app, test::TestRequest::put().uri(&uri).to_request()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let page: serde_json::Value = test::call_and_read_body_json(
+            &app,
+            test::TestRequest::get().uri("/bookmarks").to_request(),
+        )
+        .await;
+        assert_eq!(page["post"][0]["id"], post.id);
+
+        let response =
+            test::call_service(&app, test::TestRequest::delete().uri(&uri).to_request()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
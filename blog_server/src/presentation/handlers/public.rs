@@ -1,21 +1,74 @@
-use actix_web::{HttpResponse, Responder, Scope, get, post, web};
+use actix_web::{HttpRequest, HttpResponse, Responder, Scope, get, http::header, post, web};
 
 use crate::{
-    application::{AuthService, BlogService},
-    data::{posr_repository::PostgresPostRepository, user_repository::PostgresUserRepository},
+    application::{AttachmentService, AuthService, BlogService, CommentService},
+    data::{
+        attachment_repository::PostgresAttachmentRepository,
+        comment_repository::PostgresCommentRepository, posr_repository::PostgresPostRepository,
+        storage::LocalFileStorage, user_repository::PostgresUserRepository,
+    },
     domain::{
-        auth::{Auth, Login},
+        auth::{
+            Auth, Login, LoginOutcome, LoginTwoFactor, PasswordResetConfirm, PasswordResetRequest,
+            RefreshRequest, VerifyEmail,
+        },
         error::BlogError,
+        pagination::PageRequest,
+    },
+    presentation::{
+        RateLimitMiddleware,
+        auth::AuthenticatedUser,
+        dto::{self, AuthResponse},
     },
-    presentation::dto::{self, AuthResponse},
 };
 
+/// Сколько другие сервисы могут кешировать набор ключей, в секундах
+const JWKS_MAX_AGE_SECS: u32 = 300;
+
 pub fn scope() -> Scope {
     web::scope("")
         .service(healrh)
         .service(get_post)
+        .service(get_post_by_slug)
         .service(get_post_by_id)
-        .service(web::scope("/auth").service(register).service(login))
+        .service(get_comments)
+        .service(get_revisions)
+        .service(get_revision_diff)
+        .service(get_post_attachments)
+        .service(get_attachment)
+        .service(get_tags)
+        .service(search)
+        .service(get_user_profile)
+        .service(get_user_posts)
+        .service(get_followers)
+        .service(get_following)
+        .service(
+            web::scope("/auth")
+                .wrap(RateLimitMiddleware)
+                .service(register)
+                .service(login)
+                .service(login_two_factor)
+                .service(refresh)
+                .service(request_password_reset)
+                .service(confirm_password_reset)
+                .service(verify_email),
+        )
+}
+
+/// Стандартные адреса вне `/api`
+pub fn well_known() -> Scope {
+    web::scope("/.well-known").service(jwks)
+}
+
+/// Открытые ключи, которыми другие сервисы проверяют access токены без обращения к блогу
+#[get("/jwks.json")]
+async fn jwks(auth_service: web::Data<AuthService<PostgresUserRepository>>) -> impl Responder {
+    HttpResponse::Ok()
+        .insert_header(header::CacheControl(vec![
+            header::CacheDirective::Public,
+            header::CacheDirective::MaxAge(JWKS_MAX_AGE_SECS),
+        ]))
+        .json(auth_service.jwks())
 }
 
 #[get("/health")]
@@ -36,38 +89,139 @@ async fn register(
     let acc = auth_service.register(payload.clone()).await?;
 
     tracing::info!(user_id = %acc.id, username = %acc.username, email = %acc.email, "user registered");
-    let acc = auth_service
-        .login_by_username(&payload.username, &payload.password)
-        .await?;
-    Ok(HttpResponse::Ok().json(AuthResponse {
-        token: acc.token,
-        uuid: acc.uuid,
-    }))
+    // У нового пользователя 2FA ещё не может быть включена
+    let LoginOutcome::Authenticated(acc) = auth_service
+        .login_by_password(&payload.username, &payload.password)
+        .await?
+    else {
+        return Err(BlogError::Internal(
+            "unexpected two-factor challenge".to_string(),
+        ));
+    };
+    Ok(HttpResponse::Ok().json(AuthResponse::from(acc)))
 }
 
 #[post("/login")]
 async fn login(
     auth_service: web::Data<AuthService<PostgresUserRepository>>,
     payload: web::Json<Login>,
+) -> Result<impl Responder, BlogError> {
+    let outcome = auth_service
+        .login_by_password(&payload.username, &payload.password)
+        .await?;
+
+    match outcome {
+        LoginOutcome::Authenticated(acc) => {
+            tracing::info!(username = %payload.username, "user logged in");
+            Ok(HttpResponse::Ok().json(AuthResponse::from(acc)))
+        }
+        // Пароль верный, вход завершается кодом через /login/two-factor
+        LoginOutcome::TwoFactorRequired(challenge) => {
+            tracing::info!(username = %payload.username, "two-factor code required");
+            Ok(HttpResponse::Accepted().json(dto::TwoFactorChallengeResponse::from(challenge)))
+        }
+    }
+}
+
+#[post("/login/two-factor")]
+async fn login_two_factor(
+    auth_service: web::Data<AuthService<PostgresUserRepository>>,
+    payload: web::Json<LoginTwoFactor>,
 ) -> Result<impl Responder, BlogError> {
     let acc = auth_service
-        .login_by_username(&payload.username, &payload.password)
+        .complete_two_factor_login(payload.into_inner())
         .await?;
 
-    tracing::info!(username = %payload.username, "user logged in");
+    tracing::info!(user_id = %acc.uuid, "user logged in with two-factor code");
 
-    Ok(HttpResponse::Ok().json(AuthResponse {
-        token: acc.token,
-        uuid: acc.uuid,
-    }))
+    Ok(HttpResponse::Ok().json(AuthResponse::from(acc)))
+}
+
+/// Новая пара токенов в обмен на refresh токен. Старый становится недействительным
+#[post("/refresh")]
+async fn refresh(
+    auth_service: web::Data<AuthService<PostgresUserRepository>>,
+    payload: web::Json<RefreshRequest>,
+) -> Result<impl Responder, BlogError> {
+    let acc = auth_service.refresh(&payload.refresh_token).await?;
+
+    Ok(HttpResponse::Ok().json(AuthResponse::from(acc)))
+}
+
+/// Отвечает одинаково для известного и неизвестного email
+#[post("/password-reset")]
+async fn request_password_reset(
+    auth_service: web::Data<AuthService<PostgresUserRepository>>,
+    payload: web::Json<PasswordResetRequest>,
+) -> Result<impl Responder, BlogError> {
+    auth_service.request_password_reset(&payload.email).await?;
+
+    Ok(HttpResponse::Accepted().finish())
+}
+
+#[post("/password-reset/confirm")]
+async fn confirm_password_reset(
+    auth_service: web::Data<AuthService<PostgresUserRepository>>,
+    payload: web::Json<PasswordResetConfirm>,
+) -> Result<impl Responder, BlogError> {
+    auth_service
+        .confirm_password_reset(payload.into_inner())
+        .await?;
+
+    tracing::info!("Password reset confirmed");
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[post("/verify-email")]
+async fn verify_email(
+    auth_service: web::Data<AuthService<PostgresUserRepository>>,
+    payload: web::Json<VerifyEmail>,
+) -> Result<impl Responder, BlogError> {
+    auth_service.verify_email(&payload.token).await?;
+
+    tracing::info!("Email verified");
+    Ok(HttpResponse::NoContent().finish())
 }
 
 #[get("/posts/{id}")]
 async fn get_post_by_id(
+    user: Option<AuthenticatedUser>,
     blog_service: web::Data<BlogService<PostgresPostRepository>>,
     path: web::Path<i64>,
 ) -> Result<impl Responder, BlogError> {
-    let post = blog_service.get_post_by_id(path.into_inner()).await?;
+    let mut post = blog_service.get_published_post(path.into_inner()).await?;
+    blog_service
+        .mark_bookmarked(user.map(|u| u.id), std::slice::from_mut(&mut post))
+        .await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!(
+        {
+            "post": post,
+        }
+    )))
+}
+
+/// По прежнему slug отвечает постоянным перенаправлением на актуальный
+#[get("/posts/by-slug/{slug}")]
+async fn get_post_by_slug(
+    user: Option<AuthenticatedUser>,
+    blog_service: web::Data<BlogService<PostgresPostRepository>>,
+    path: web::Path<String>,
+) -> Result<HttpResponse, BlogError> {
+    let slug = path.into_inner();
+    let mut post = blog_service.get_published_post_by_slug(&slug).await?;
+
+    if post.slug != slug {
+        return Ok(HttpResponse::MovedPermanently()
+            .insert_header((
+                header::LOCATION,
+                format!("/api/posts/by-slug/{}", post.slug),
+            ))
+            .finish());
+    }
+    blog_service
+        .mark_bookmarked(user.map(|u| u.id), std::slice::from_mut(&mut post))
+        .await?;
 
     Ok(HttpResponse::Ok().json(serde_json::json!(
         {
@@ -78,21 +232,248 @@ async fn get_post_by_id(
 
 #[get("/posts")]
 async fn get_post(
+    user: Option<AuthenticatedUser>,
     blog_service: web::Data<BlogService<PostgresPostRepository>>,
     params: web::Query<dto::PaginationParams>,
+    order: web::Query<dto::PostOrderParams>,
+    query: web::Query<Vec<(String, String)>>,
 ) -> Result<impl Responder, BlogError> {
-    let post = blog_service
-        .get_next_posts(params.offset as i64, params.limit as i64)
+    let page = PageRequest::new(params.offset as i64, params.cursor.as_deref())?;
+    let mut page = blog_service
+        .get_next_posts(
+            page,
+            params.limit as i64,
+            dto::tags_from_query(&query),
+            order.order,
+        )
+        .await?;
+    blog_service
+        .mark_bookmarked(user.map(|u| u.id), &mut page.posts)
         .await?;
-
-    let total = post.len() as i32;
 
     Ok(
         HttpResponse::Ok().json(serde_json::json!(dto::ListPostsResponse {
-            post,
-            total,
+            post: page.posts,
+            total: page.total as i32,
             limit: params.limit,
-            offset: params.offset
+            offset: params.offset,
+            next_cursor: page.next_cursor,
         })),
     )
 }
+
+#[get("/posts/{id}/comments")]
+async fn get_comments(
+    user: Option<AuthenticatedUser>,
+    comment_service: web::Data<CommentService<PostgresCommentRepository, PostgresPostRepository>>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, BlogError> {
+    let comments = comment_service
+        .get_comments(path.into_inner(), user.as_ref().map(|u| &u.session))
+        .await?;
+
+    Ok(HttpResponse::Ok().json(dto::ListCommentsResponse { comments }))
+}
+
+#[get("/tags")]
+async fn get_tags(
+    blog_service: web::Data<BlogService<PostgresPostRepository>>,
+) -> Result<impl Responder, BlogError> {
+    let tags = blog_service.get_tags().await?;
+
+    Ok(HttpResponse::Ok().json(dto::ListTagsResponse { tags }))
+}
+
+#[get("/posts/{id}/attachments")]
+async fn get_post_attachments(
+    blog_service: web::Data<BlogService<PostgresPostRepository>>,
+    attachment_service: web::Data<
+        AttachmentService<PostgresAttachmentRepository, LocalFileStorage, PostgresPostRepository>,
+    >,
+    path: web::Path<i64>,
+) -> Result<impl Responder, BlogError> {
+    let post = blog_service.get_published_post(path.into_inner()).await?;
+    let attachments = attachment_service.get_post_attachments(post.id).await?;
+
+    Ok(HttpResponse::Ok().json(dto::ListAttachmentsResponse {
+        attachments: attachments.into_iter().map(Into::into).collect(),
+    }))
+}
+
+/// Содержимое неизменно для данного id, поэтому вложения опубликованных постов
+/// кэшируются навсегда. Остальные кэшируются только в браузере и с перепроверкой
+#[get("/attachments/{id}")]
+async fn get_attachment(
+    req: HttpRequest,
+    user: Option<AuthenticatedUser>,
+    attachment_service: web::Data<
+        AttachmentService<PostgresAttachmentRepository, LocalFileStorage, PostgresPostRepository>,
+    >,
+    path: web::Path<i64>,
+) -> Result<impl Responder, BlogError> {
+    let (attachment, public) = attachment_service
+        .get_visible_attachment(path.into_inner(), user.as_ref().map(|u| &u.session))
+        .await?;
+    let etag = header::EntityTag::new_strong(attachment.sha256.clone());
+    let cache_control = if public {
+        header::CacheControl(vec![
+            header::CacheDirective::Public,
+            header::CacheDirective::MaxAge(31_536_000),
+            header::CacheDirective::Extension("immutable".to_string(), None),
+        ])
+    } else {
+        header::CacheControl(vec![
+            header::CacheDirective::Private,
+            header::CacheDirective::NoCache,
+        ])
+    };
+
+    let not_modified = req
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.split(',').any(|tag| tag.trim() == etag.to_string()));
+    if not_modified {
+        return Ok(HttpResponse::NotModified()
+            .insert_header(header::ETag(etag))
+            .insert_header(cache_control)
+            .finish());
+    }
+
+    let data = attachment_service.read_content(&attachment).await?;
+
+    Ok(HttpResponse::Ok()
+        .content_type(attachment.content_type)
+        .insert_header(header::ETag(etag))
+        .insert_header(cache_control)
+        .insert_header(header::ContentDisposition {
+            disposition: header::DispositionType::Inline,
+            parameters: Vec::new(),
+        })
+        .body(data))
+}
+
+#[get("/posts/{id}/revisions")]
+async fn get_revisions(
+    blog_service: web::Data<BlogService<PostgresPostRepository>>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, BlogError> {
+    let post = blog_service.get_published_post(path.into_inner()).await?;
+    let revisions = blog_service.get_revisions(post.id).await?;
+
+    Ok(HttpResponse::Ok().json(dto::ListRevisionsResponse { revisions }))
+}
+
+#[get("/posts/{id}/revisions/{revision}/diff")]
+async fn get_revision_diff(
+    blog_service: web::Data<BlogService<PostgresPostRepository>>,
+    path: web::Path<(i64, i32)>,
+    params: web::Query<dto::DiffParams>,
+) -> Result<impl Responder, BlogError> {
+    let (post_id, revision) = path.into_inner();
+    let post = blog_service.get_published_post(post_id).await?;
+    let diff = blog_service
+        .diff_revisions(&post, revision, params.to)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(diff))
+}
+
+#[get("/search")]
+async fn search(
+    blog_service: web::Data<BlogService<PostgresPostRepository>>,
+    params: web::Query<dto::SearchParams>,
+) -> Result<impl Responder, BlogError> {
+    let results = blog_service
+        .search_posts(&params.q, params.offset as i64, params.limit as i64)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(dto::SearchResponse {
+        hits: results.hits,
+        total: results.total as i32,
+        limit: params.limit,
+        offset: params.offset,
+    }))
+}
+
+#[get("/users/{username}")]
+async fn get_user_profile(
+    auth_service: web::Data<AuthService<PostgresUserRepository>>,
+    path: web::Path<String>,
+) -> Result<impl Responder, BlogError> {
+    let profile = auth_service.get_profile(&path.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(dto::UserProfileResponse { profile }))
+}
+
+/// Опубликованные посты автора, от новых к старым
+#[get("/users/{username}/posts")]
+async fn get_user_posts(
+    user: Option<AuthenticatedUser>,
+    auth_service: web::Data<AuthService<PostgresUserRepository>>,
+    blog_service: web::Data<BlogService<PostgresPostRepository>>,
+    path: web::Path<String>,
+    params: web::Query<dto::PaginationParams>,
+) -> Result<impl Responder, BlogError> {
+    let profile = auth_service.get_profile(&path.into_inner()).await?;
+    let mut page = blog_service
+        .get_posts_by_user(profile.id, params.offset as i64, params.limit as i64)
+        .await?;
+    blog_service
+        .mark_bookmarked(user.map(|u| u.id), &mut page.posts)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(dto::ListPostsResponse {
+        post: page.posts,
+        total: page.total as i32,
+        limit: params.limit,
+        offset: params.offset,
+        next_cursor: page.next_cursor,
+    }))
+}
+
+/// Подписчики автора, от новых подписок к старым
+#[get("/users/{username}/followers")]
+async fn get_followers(
+    auth_service: web::Data<AuthService<PostgresUserRepository>>,
+    path: web::Path<String>,
+    params: web::Query<dto::PaginationParams>,
+) -> Result<impl Responder, BlogError> {
+    let page = auth_service
+        .get_followers(
+            &path.into_inner(),
+            params.limit as i64,
+            params.offset as i64,
+        )
+        .await?;
+
+    Ok(HttpResponse::Ok().json(dto::ListFollowsResponse {
+        users: page.users,
+        total: page.total as i32,
+        limit: params.limit,
+        offset: params.offset,
+    }))
+}
+
+/// Авторы, на которых подписан пользователь
+#[get("/users/{username}/following")]
+async fn get_following(
+    auth_service: web::Data<AuthService<PostgresUserRepository>>,
+    path: web::Path<String>,
+    params: web::Query<dto::PaginationParams>,
+) -> Result<impl Responder, BlogError> {
+    let page = auth_service
+        .get_following(
+            &path.into_inner(),
+            params.limit as i64,
+            params.offset as i64,
+        )
+        .await?;
+
+    Ok(HttpResponse::Ok().json(dto::ListFollowsResponse {
+        users: page.users,
+        total: page.total as i32,
+        limit: params.limit,
+        offset: params.offset,
+    }))
+}
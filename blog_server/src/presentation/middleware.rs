@@ -18,7 +18,7 @@ use uuid::Uuid;
 
 use crate::{
     application::AuthService, data::user_repository::PostgresUserRepository,
-    presentation::auth::extract_user_from_token,
+    domain::error::BlogError, presentation::auth::extract_user_from_token,
 };
 
 static REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
@@ -161,11 +161,19 @@ where
     }
 }
 
-pub struct JwtAuthMiddleware {}
+pub struct JwtAuthMiddleware {
+    optional: bool,
+}
 
 impl JwtAuthMiddleware {
     pub fn new() -> Self {
-        Self {}
+        Self { optional: false }
+    }
+
+    /// Для открытых адресов: запрос без токена или с недействительным токеном
+    /// проходит анонимно, иначе пользователь доступен как `Option<AuthenticatedUser>`
+    pub fn optional() -> Self {
+        Self { optional: true }
     }
 }
 
@@ -182,12 +190,14 @@ where
     fn new_transform(&self, service: S) -> Self::Future {
         ready(Ok(JwtAuthService {
             service: Rc::new(RefCell::new(service)),
+            optional: self.optional,
         }))
     }
 }
 
 pub struct JwtAuthService<S> {
     service: Rc<RefCell<S>>,
+    optional: bool,
 }
 
 impl<S, B> Service<ServiceRequest> for JwtAuthService<S>
@@ -215,21 +225,32 @@ where
             .and_then(|value| value.to_str().ok())
             .map(|value| value.to_string());
 
+        let optional = self.optional;
+
         Box::pin(async move {
             let auth_service = auth_servise
                 .ok_or_else(|| actix_web::error::ErrorInternalServerError("AuthService missing"))?;
 
-            let header = auth_header.ok_or_else(|| {
-                actix_web::error::ErrorUnauthorized("mising authorization header")
-            })?;
-
-            let token = header.strip_prefix("Bearer ").ok_or_else(|| {
-                actix_web::error::ErrorUnauthorized("invalid authorization header")
-            })?;
+            let user = async {
+                let header = auth_header.ok_or_else(|| {
+                    actix_web::error::ErrorUnauthorized("mising authorization header")
+                })?;
 
-            let user = extract_user_from_token(token, auth_service.get_ref()).await?;
+                let token = header.strip_prefix("Bearer ").ok_or_else(|| {
+                    actix_web::error::ErrorUnauthorized("invalid authorization header")
+                })?;
 
-            req.extensions_mut().insert(user);
+                Ok::<_, Error>(extract_user_from_token(token, auth_service.get_ref()).await?)
+            }
+            .await;
+
+            match user {
+                Ok(user) => {
+                    req.extensions_mut().insert(user);
+                }
+                Err(err) if !optional => return Err(err),
+                Err(_) => {}
+            }
             let fut = {
                 let svc = service.borrow_mut();
                 svc.call(req)
@@ -240,3 +261,57 @@ where
         })
     }
 }
+
+/// Ограничение частоты запросов с одного IP, ответ `429` с `Retry-After`
+pub struct RateLimitMiddleware;
+
+impl<S, B> Transform<S, ServiceRequest> for RateLimitMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = RateLimitService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RateLimitService { service }))
+    }
+}
+
+pub struct RateLimitService<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for RateLimitService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&self, ctx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        // Адрес соединения, а не заголовки прокси: их может подделать клиент
+        let checked = req
+            .app_data::<web::Data<AuthService<PostgresUserRepository>>>()
+            .zip(req.peer_addr())
+            .map(|(auth_service, addr)| auth_service.rate_limiter().check_ip(addr.ip()));
+
+        if let Some(Err(retry_after)) = checked {
+            tracing::warn!(path = %req.path(), "Rate limit exceeded");
+            let error = BlogError::too_many_requests(retry_after);
+            return Box::pin(async move { Err(error.into()) });
+        }
+
+        let fut = self.service.call(req);
+        Box::pin(fut)
+    }
+}
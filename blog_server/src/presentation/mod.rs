@@ -3,5 +3,9 @@ mod dto;
 pub mod grpc_service;
 pub mod handlers;
 mod middleware;
+mod rate_limit;
 
-pub use middleware::{JwtAuthMiddleware, RequestId, RequestIdMiddleware, TimingMiddleware};
+pub use middleware::{
+    JwtAuthMiddleware, RateLimitMiddleware, RequestId, RequestIdMiddleware, TimingMiddleware,
+};
+pub use rate_limit::AuthRateLimitLayer;
@@ -0,0 +1,138 @@
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures_util::future::BoxFuture;
+use tonic::codegen::http;
+use tonic::transport::server::TcpConnectInfo;
+use tower::{Layer, Service};
+
+use crate::{domain::error::BlogError, infrastructure::RateLimiter};
+
+/// Открытые RPC аутентификации: вход, регистрация, обновление токена,
+/// сброс пароля и подтверждение email. Повторяет область `/api/auth` в REST
+const AUTH_RPC_PATHS: &[&str] = &[
+    "/blog.BlogService/Register",
+    "/blog.BlogService/Login",
+    "/blog.BlogService/LoginTwoFactor",
+    "/blog.BlogService/Refresh",
+    "/blog.BlogService/RequestPasswordReset",
+    "/blog.BlogService/ConfirmPasswordReset",
+    "/blog.BlogService/VerifyEmail",
+];
+
+/// Слой gRPC сервера, ограничивающий частоту запросов аутентификации с одного IP.
+/// Метод определяется по пути запроса, поэтому остальные RPC проходят без проверки
+#[derive(Clone)]
+pub struct AuthRateLimitLayer {
+    limiter: Arc<RateLimiter>,
+}
+
+impl AuthRateLimitLayer {
+    pub fn new(limiter: Arc<RateLimiter>) -> Self {
+        Self { limiter }
+    }
+}
+
+impl<S> Layer<S> for AuthRateLimitLayer {
+    type Service = AuthRateLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AuthRateLimitService {
+            inner,
+            limiter: self.limiter.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct AuthRateLimitService<S> {
+    inner: S,
+    limiter: Arc<RateLimiter>,
+}
+
+impl<S> AuthRateLimitService<S> {
+    /// Без адреса соединения запрос отклоняется: иначе ограничение не действует
+    fn check<B>(&self, req: &http::Request<B>) -> Result<(), tonic::Status> {
+        if !AUTH_RPC_PATHS.contains(&req.uri().path()) {
+            return Ok(());
+        }
+
+        let Some(addr) = req
+            .extensions()
+            .get::<TcpConnectInfo>()
+            .and_then(TcpConnectInfo::remote_addr)
+        else {
+            tracing::warn!(path = %req.uri().path(), "Remote address is unknown");
+            return Err(tonic::Status::unavailable("remote address is unknown"));
+        };
+
+        self.limiter.check_ip(addr.ip()).map_err(|retry_after| {
+            tracing::warn!(path = %req.uri().path(), "Rate limit exceeded");
+            BlogError::too_many_requests(retry_after).into()
+        })
+    }
+}
+
+impl<S, ReqBody, ResBody> Service<http::Request<ReqBody>> for AuthRateLimitService<S>
+where
+    S: Service<http::Request<ReqBody>, Response = http::Response<ResBody>>,
+    S::Future: Send + 'static,
+    ResBody: Default,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<ReqBody>) -> Self::Future {
+        if let Err(status) = self.check(&req) {
+            return Box::pin(async move { Ok(status.into_http()) });
+        }
+
+        Box::pin(self.inner.call(req))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::RateLimitConfig;
+
+    fn request(path: &str, remote_addr: Option<&str>) -> http::Request<()> {
+        let mut req = http::Request::builder().uri(path).body(()).unwrap();
+        req.extensions_mut().insert(TcpConnectInfo {
+            local_addr: None,
+            remote_addr: remote_addr.map(|addr| addr.parse().unwrap()),
+        });
+        req
+    }
+
+    #[test]
+    fn test_auth_rpc_rate_limit() {
+        let limiter = RateLimiter::in_memory(RateLimitConfig {
+            ip_per_minute: 1,
+            ..RateLimitConfig::default()
+        });
+        let service = AuthRateLimitLayer::new(Arc::new(limiter)).layer(());
+
+        let login = "/blog.BlogService/Login";
+        assert!(
+            service
+                .check(&request(login, Some("10.0.0.1:1000")))
+                .is_ok()
+        );
+        let status = service
+            .check(&request(login, Some("10.0.0.1:1001")))
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::ResourceExhausted);
+
+        let list = "/blog.BlogService/ListPosts";
+        assert!(service.check(&request(list, Some("10.0.0.1:1002"))).is_ok());
+
+        let status = service.check(&request(login, None)).unwrap_err();
+        assert_eq!(status.code(), tonic::Code::Unavailable);
+    }
+}
@@ -0,0 +1,467 @@
+use dioxus::prelude::*;
+
+use super::Header;
+use crate::{
+    infrastructure::{self, SaveData},
+    Route,
+};
+
+#[component]
+pub fn ChangePassword() -> Element {
+    let mut current_password = use_signal(|| String::new());
+    let mut new_password = use_signal(|| String::new());
+    let mut error = use_signal(|| String::new());
+    let mut done = use_signal(|| false);
+    let mut is_loading = use_signal(|| false);
+
+    let user = match infrastructure::get_token() {
+        Ok(user) => user,
+        Err(_) => {
+            return rsx!(
+                Header {}
+                p { class: "error-message", "Войдите, чтобы сменить пароль" }
+            )
+        }
+    };
+
+    let on_submit = move |event: FormEvent| {
+        event.stop_propagation();
+        event.prevent_default();
+        error.set(String::new());
+        done.set(false);
+
+        if current_password.read().is_empty() || new_password.read().is_empty() {
+            error.set("Заполните оба поля".to_string());
+            return;
+        }
+
+        is_loading.set(true);
+
+        let current_clone = current_password.read().clone();
+        let new_clone = new_password.read().clone();
+        let username = user.username.clone();
+        let token = user.get_brear();
+
+        spawn(async move {
+            match infrastructure::change_password(&current_clone, &new_clone, &token).await {
+                Ok(auth_responce) => {
+                    // Старые токены больше недействительны
+                    if let Err(e) = infrastructure::save_token(&SaveData::new(
+                        auth_responce.token,
+                        auth_responce.refresh_token,
+                        username,
+                        auth_responce.uuid,
+                    )) {
+                        error.set(e);
+                    } else {
+                        current_password.set(String::new());
+                        new_password.set(String::new());
+                        done.set(true);
+                    }
+                }
+                Err(e) => error.set(format!("Ошибка смены пароля: {}", e)),
+            }
+            is_loading.set(false);
+        });
+    };
+
+    rsx!(
+        Header {}
+        div {
+            class: "form-container",
+            div{
+                h2 { "Смена пароля" }
+            }
+            form {
+                onsubmit: on_submit,
+
+                div {
+                    label { "Текущий пароль"}
+                    input {
+                        r#type: "password",
+                        value: "{current_password}",
+                        oninput: move |e| current_password.set(e.value())
+                    }
+                }
+
+                div {
+                    label { "Новый пароль"}
+                    input {
+                        r#type: "password",
+                        value: "{new_password}",
+                        placeholder: "Не короче 8 символов",
+                        oninput: move |e| new_password.set(e.value())
+                    }
+                }
+                if !error.read().is_empty() {
+                    p {
+                        class: "error-message",
+                        "{error}"
+                    }
+                }
+                if *done.read() {
+                    p {
+                        class: "info-message",
+                        "Пароль изменён, сессии на других устройствах завершены"
+                    }
+                }
+
+                div {
+                    button {
+                        r#type: "submit",
+                        disabled: *is_loading.read(),
+                        "Сменить пароль"
+                    }
+                }
+            }
+        }
+    )
+}
+
+#[component]
+pub fn ForgotPassword() -> Element {
+    let mut email = use_signal(|| String::new());
+    let mut error = use_signal(|| String::new());
+    let mut sent = use_signal(|| false);
+    let mut is_loading = use_signal(|| false);
+
+    let on_submit = move |event: FormEvent| {
+        event.stop_propagation();
+        event.prevent_default();
+        error.set(String::new());
+
+        if email.read().is_empty() {
+            error.set("email обязателен".to_string());
+            return;
+        }
+
+        is_loading.set(true);
+        let email_clone = email.read().clone();
+
+        spawn(async move {
+            match infrastructure::request_password_reset(&email_clone).await {
+                Ok(()) => sent.set(true),
+                Err(e) => error.set(format!("Ошибка: {}", e)),
+            }
+            is_loading.set(false);
+        });
+    };
+
+    rsx!(
+        Header {}
+        div {
+            class: "form-container",
+            div{
+                h2 { "Восстановление пароля" }
+            }
+            if *sent.read() {
+                p {
+                    class: "info-message",
+                    "Если аккаунт с таким email существует, на него отправлено письмо со ссылкой"
+                }
+            } else {
+                form {
+                    onsubmit: on_submit,
+
+                    div {
+                        label { "Email"}
+                        input {
+                            r#type: "email",
+                            value: "{email}",
+                            placeholder: "Введите email",
+                            oninput: move |e| email.set(e.value())
+                        }
+                    }
+                    if !error.read().is_empty() {
+                        p {
+                            class: "error-message",
+                            "{error}"
+                        }
+                    }
+
+                    div {
+                        button {
+                            r#type: "submit",
+                            disabled: *is_loading.read(),
+                            "Отправить ссылку"
+                        }
+                    }
+                }
+            }
+        }
+    )
+}
+
+/// Страница из ссылки в письме
+#[component]
+pub fn ResetPassword(token: String) -> Element {
+    let mut password = use_signal(|| String::new());
+    let mut error = use_signal(|| String::new());
+    let mut is_loading = use_signal(|| false);
+    let nav = navigator();
+
+    let on_submit = move |event: FormEvent| {
+        event.stop_propagation();
+        event.prevent_default();
+        error.set(String::new());
+
+        if password.read().is_empty() {
+            error.set("пароль обязателен".to_string());
+            return;
+        }
+
+        is_loading.set(true);
+        let password_clone = password.read().clone();
+        let token_clone = token.clone();
+
+        spawn(async move {
+            match infrastructure::confirm_password_reset(&token_clone, &password_clone).await {
+                Ok(()) => {
+                    // Сохранённая сессия после сброса недействительна
+                    let _ = infrastructure::delete_token();
+                    nav.push(Route::Login);
+                }
+                Err(e) => {
+                    error.set(format!("Ошибка сброса пароля: {}", e));
+                    is_loading.set(false);
+                }
+            }
+        });
+    };
+
+    rsx!(
+        Header {}
+        div {
+            class: "form-container",
+            div{
+                h2 { "Новый пароль" }
+            }
+            form {
+                onsubmit: on_submit,
+
+                div {
+                    label { "Новый пароль"}
+                    input {
+                        r#type: "password",
+                        value: "{password}",
+                        placeholder: "Не короче 8 символов",
+                        oninput: move |e| password.set(e.value())
+                    }
+                }
+                if !error.read().is_empty() {
+                    p {
+                        class: "error-message",
+                        "{error}"
+                    }
+                }
+
+                div {
+                    button {
+                        r#type: "submit",
+                        disabled: *is_loading.read(),
+                        "Сохранить"
+                    }
+                }
+            }
+        }
+    )
+}
+
+/// Аккаунт создан, email ещё не подтверждён
+#[component]
+pub fn PendingVerification(email: String) -> Element {
+    let nav = navigator();
+    let mut message = use_signal(|| String::new());
+
+    let on_resend = move |_| {
+        let Ok(user) = infrastructure::get_token() else {
+            return;
+        };
+        spawn(async move {
+            match infrastructure::resend_verification(&user.get_brear()).await {
+                Ok(()) => message.set("Письмо отправлено ещё раз".to_string()),
+                Err(e) => message.set(format!("Ошибка: {}", e)),
+            }
+        });
+    };
+
+    rsx!(
+        div {
+            class: "form-container",
+            div {
+                h2 { "Подтвердите email" }
+                p {
+                    class: "info-message",
+                    "Аккаунт создан. На {email} отправлено письмо со ссылкой для подтверждения. \
+                     Пока адрес не подтверждён, публикация постов может быть недоступна, \
+                     черновики сохранять можно."
+                }
+                if !message.read().is_empty() {
+                    p { "{message}" }
+                }
+                button {
+                    class: "auth-btn",
+                    onclick: on_resend,
+                    "Отправить письмо ещё раз"
+                }
+                button {
+                    class: "auth-btn",
+                    onclick: move |_| { nav.push(Route::Index); },
+                    "На главную"
+                }
+            }
+        }
+    )
+}
+
+/// Выгрузка своих данных и удаление аккаунта
+#[component]
+pub fn DeleteAccount() -> Element {
+    let mut password = use_signal(|| String::new());
+    let mut posts = use_signal(|| "anonymize".to_string());
+    // Имя файла и ссылка на выгрузку
+    let mut export = use_signal(|| None::<(String, String)>);
+    let mut error = use_signal(|| String::new());
+    let mut is_loading = use_signal(|| false);
+    let nav = navigator();
+
+    let user = match infrastructure::get_token() {
+        Ok(user) => user,
+        Err(_) => {
+            return rsx!(
+                Header {}
+                p { class: "error-message", "Войдите, чтобы управлять аккаунтом" }
+            )
+        }
+    };
+
+    let token = user.get_brear();
+    let on_export = move |_| {
+        error.set(String::new());
+        let token = token.clone();
+        spawn(async move {
+            match infrastructure::export_account(&token).await {
+                Ok((file_name, data)) => {
+                    export.set(Some((file_name, infrastructure::json_data_url(&data))))
+                }
+                Err(e) => error.set(format!("Ошибка выгрузки: {}", e)),
+            }
+        });
+    };
+
+    let on_submit = move |event: FormEvent| {
+        event.stop_propagation();
+        event.prevent_default();
+        error.set(String::new());
+
+        if password.read().is_empty() {
+            error.set("Введите пароль".to_string());
+            return;
+        }
+
+        is_loading.set(true);
+        let password_clone = password.read().clone();
+        let posts_clone = posts.read().clone();
+        let token = user.get_brear();
+
+        spawn(async move {
+            match infrastructure::delete_account(&password_clone, &posts_clone, &token).await {
+                Ok(()) => {
+                    let _ = infrastructure::delete_token();
+                    nav.push(Route::Index);
+                }
+                Err(e) => {
+                    error.set(format!("Ошибка удаления: {}", e));
+                    is_loading.set(false);
+                }
+            }
+        });
+    };
+
+    rsx!(
+        Header {}
+        div {
+            class: "form-container",
+            div {
+                h2 { "Мои данные" }
+                p { "Профиль, посты, комментарии, вложения и токены доступа одним JSON файлом" }
+                button {
+                    class: "btn",
+                    onclick: on_export,
+                    "Подготовить выгрузку"
+                }
+                if let Some((file_name, href)) = export.read().clone() {
+                    a { class: "btn", href: "{href}", download: "{file_name}", "Скачать {file_name}" }
+                }
+            }
+            div {
+                h2 { "Удаление аккаунта" }
+                p {
+                    class: "error-message",
+                    "Аккаунт удаляется безвозвратно, все сессии и токены доступа перестают действовать"
+                }
+            }
+            form {
+                onsubmit: on_submit,
+
+                div {
+                    label { "Посты и комментарии"}
+                    select {
+                        value: "{posts}",
+                        onchange: move |e| posts.set(e.value()),
+                        option { value: "anonymize", "Оставить от имени [deleted], кроме черновиков" }
+                        option { value: "delete", "Удалить" }
+                    }
+                }
+
+                div {
+                    label { "Пароль"}
+                    input {
+                        r#type: "password",
+                        value: "{password}",
+                        oninput: move |e| password.set(e.value())
+                    }
+                }
+                if !error.read().is_empty() {
+                    p {
+                        class: "error-message",
+                        "{error}"
+                    }
+                }
+
+                div {
+                    button {
+                        r#type: "submit",
+                        disabled: *is_loading.read(),
+                        "Удалить аккаунт"
+                    }
+                }
+            }
+        }
+    )
+}
+
+/// Страница из ссылки в письме подтверждения
+#[component]
+pub fn VerifyEmail(token: String) -> Element {
+    let result = use_resource(use_reactive!(|(token,)| async move {
+        infrastructure::verify_email(&token).await
+    }));
+
+    let content = match &*result.read() {
+        None => rsx!(p { "Проверяем ссылку..." }),
+        Some(Ok(())) => rsx!(p { class: "info-message", "Email подтверждён" }),
+        Some(Err(e)) => rsx!(p { class: "error-message", "Не удалось подтвердить email: {e}" }),
+    };
+
+    rsx!(
+        Header {}
+        div {
+            class: "form-container",
+            div {
+                h2 { "Подтверждение email" }
+                {content}
+            }
+        }
+    )
+}
@@ -1,8 +1,9 @@
 use dioxus::prelude::*;
 
-use super::Header;
+use super::{Header, PendingVerification};
 
 use crate::{
+    dto::LoginResult,
     infrastructure::{self, SaveData},
     Route,
 };
@@ -14,8 +15,7 @@ pub fn Register() -> Element {
     let mut password = use_signal(|| String::new());
     let mut error = use_signal(|| String::new());
     let mut is_loading = use_signal(|| false);
-
-    let nav = navigator();
+    let mut registered = use_signal(|| false);
 
     let on_submit = move |event: FormEvent| {
         event.stop_propagation();
@@ -40,7 +40,6 @@ pub fn Register() -> Element {
         let email_clone = email.read().clone();
         let password_clone = password.read().clone();
         let mut error_clone = error.clone();
-        let nav_clone = nav.clone();
 
         spawn(async move {
             match infrastructure::register_user(&username_clone, &email_clone, &password_clone)
@@ -49,6 +48,7 @@ pub fn Register() -> Element {
                 Ok(auth_responce) => {
                     if let Err(e) = infrastructure::save_token(&SaveData::new(
                         auth_responce.token,
+                        auth_responce.refresh_token,
                         username_clone,
                         auth_responce.uuid,
                     )) {
@@ -56,7 +56,7 @@ pub fn Register() -> Element {
                         is_loading.set(false);
                         return;
                     }
-                    nav_clone.push(Route::Index);
+                    registered.set(true);
                 }
                 Err(e) => {
                     error_clone.set(format!("Ошибка регистрации: {}", e));
@@ -66,6 +66,13 @@ pub fn Register() -> Element {
         });
     };
 
+    if *registered.read() {
+        return rsx!(
+            Header {}
+            PendingVerification { email: email.read().clone() }
+        );
+    }
+
     rsx!(
         Header {}
         div{
@@ -134,6 +141,9 @@ pub fn Register() -> Element {
 pub fn Login() -> Element {
     let mut username = use_signal(|| String::new());
     let mut password = use_signal(|| String::new());
+    let mut code = use_signal(|| String::new());
+    // Токен второго шага входа, пока он есть - форма запрашивает код 2FA
+    let mut challenge = use_signal(|| None::<String>);
     let mut error = use_signal(|| String::new());
     let mut is_loading = use_signal(|| false);
 
@@ -145,25 +155,40 @@ pub fn Login() -> Element {
         error.set(String::new());
 
         if username.read().is_empty() {
-            error.set("Имя пользователя обязательно".to_string());
+            error.set("Имя пользователя или email обязательно".to_string());
         }
 
         if password.read().is_empty() {
             error.set("пароль обязателен".to_string());
         }
 
+        let pending_challenge = challenge.read().clone();
+        if pending_challenge.is_some() && code.read().is_empty() {
+            error.set("Код 2FA обязателен".to_string());
+            return;
+        }
+
         is_loading.set(true);
 
         let username_clone = username.read().clone();
         let password_clone = password.read().clone();
+        let code_clone = code.read().clone();
         let mut error_clone = error.clone();
         let nav_clone = nav.clone();
 
         spawn(async move {
-            match infrastructure::login_user(&username_clone, &password_clone).await {
-                Ok(auth_responce) => {
+            let result = match pending_challenge {
+                Some(pending) => infrastructure::login_two_factor(&pending, &code_clone)
+                    .await
+                    .map(LoginResult::Authenticated),
+                None => infrastructure::login_user(&username_clone, &password_clone).await,
+            };
+
+            match result {
+                Ok(LoginResult::Authenticated(auth_responce)) => {
                     if let Err(e) = infrastructure::save_token(&SaveData::new(
                         auth_responce.token,
+                        auth_responce.refresh_token,
                         username_clone,
                         auth_responce.uuid,
                     )) {
@@ -173,6 +198,10 @@ pub fn Login() -> Element {
                     }
                     nav_clone.push(Route::Index);
                 }
+                Ok(LoginResult::TwoFactorRequired(two_factor)) => {
+                    challenge.set(Some(two_factor.two_factor_challenge));
+                    is_loading.set(false);
+                }
                 Err(e) => {
                     error_clone.set(format!("Ошибка входа: {}", e));
                     is_loading.set(false);
@@ -191,23 +220,36 @@ pub fn Login() -> Element {
             form {
                 onsubmit: on_submit,
 
-                div {
-                    label { "Имя пользователя"}
-                    input {
-                        r#type: "text",
-                        value: "{username}",
-                        placeholder: "Введите имя пользователя",
-                        oninput: move |e| username.set(e.value())
+                if challenge.read().is_none() {
+                    div {
+                        label { "Имя пользователя или email"}
+                        input {
+                            r#type: "text",
+                            value: "{username}",
+                            placeholder: "Введите имя пользователя или email",
+                            oninput: move |e| username.set(e.value())
+                        }
                     }
-                }
 
-                div {
-                    label { "Пароль"}
-                    input {
-                        r#type: "password",
-                        value: "{password}",
-                        placeholder: "Введите Пароль",
-                        oninput: move |e| password.set(e.value())
+                    div {
+                        label { "Пароль"}
+                        input {
+                            r#type: "password",
+                            value: "{password}",
+                            placeholder: "Введите Пароль",
+                            oninput: move |e| password.set(e.value())
+                        }
+                    }
+                } else {
+                    div {
+                        label { "Код 2FA"}
+                        input {
+                            r#type: "text",
+                            value: "{code}",
+                            autocomplete: "one-time-code",
+                            placeholder: "Код из приложения или код восстановления",
+                            oninput: move |e| code.set(e.value())
+                        }
                     }
                 }
                 if !error.read().is_empty() {
@@ -224,12 +266,17 @@ pub fn Login() -> Element {
 
                         if *is_loading.read(){
                             "Вход..."
+                        } else if challenge.read().is_some() {
+                            "Подтвердить"
                         } else {
                             "Войти"
                         }
                     }
                 }
             }
+            div {
+                Link { to: Route::ForgotPassword, "Забыли пароль?" }
+            }
         }
     )
 }
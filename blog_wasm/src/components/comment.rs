@@ -0,0 +1,281 @@
+use dioxus::prelude::*;
+use uuid::Uuid;
+
+use crate::{dto::Comment, infrastructure};
+
+#[component]
+pub fn CommentThread(post_id: i64, post_author: Uuid) -> Element {
+    let mut comments = use_resource(move || infrastructure::get_comments(post_id));
+    let list = match &*comments.read() {
+        Some(Ok(list)) => list.clone(),
+        Some(Err(e)) => return rsx!(p { "Ошибка: `{e}`" }),
+        None => return rsx!(p { "Загрузка комментариев..." }),
+    };
+
+    let roots = list
+        .iter()
+        .filter(|c| c.parent_id.is_none())
+        .cloned()
+        .collect::<Vec<_>>();
+    let count = list.len();
+
+    rsx!(
+        div {
+            class: "comment-thread",
+            h4 { "Комментарии ({count})" }
+            for comment in roots {
+                CommentNode {
+                    key: "{comment.id}",
+                    comment,
+                    all: list.clone(),
+                    post_author,
+                    on_change: move |_| comments.restart(),
+                }
+            }
+            if infrastructure::get_token().is_ok() {
+                CommentForm {
+                    post_id,
+                    parent_id: None,
+                    on_done: move |_| comments.restart(),
+                }
+            }
+        }
+    )
+}
+
+#[component]
+fn CommentNode(
+    comment: Comment,
+    all: Vec<Comment>,
+    post_author: Uuid,
+    on_change: EventHandler<()>,
+) -> Element {
+    let mut is_editing = use_signal(|| false);
+    let mut is_replying = use_signal(|| false);
+    let mut content = use_signal(|| comment.content.clone());
+    let mut error = use_signal(|| String::new());
+
+    let user = infrastructure::get_token().ok();
+    let can_edit = user.as_ref().is_some_and(|u| u.uuid == comment.author_id);
+    let can_delete = can_edit || user.as_ref().is_some_and(|u| u.uuid == post_author);
+    let token = user.as_ref().map(|u| u.get_brear()).unwrap_or_default();
+    let token_delete = token.clone();
+
+    let comment_id = comment.id;
+    let post_id = comment.post_id;
+    let created_at = comment.get_created_at();
+    let replies = all
+        .iter()
+        .filter(|c| c.parent_id == Some(comment_id))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let on_save = move |event: FormEvent| {
+        event.stop_propagation();
+        event.prevent_default();
+
+        if content.read().trim().is_empty() {
+            error.set("Комментарий не может быть пустым".to_string());
+            return;
+        }
+
+        let content_clone = content.read().clone();
+        let token_clone = token.clone();
+
+        spawn(async move {
+            match infrastructure::update_comment(comment_id, &content_clone, &token_clone).await {
+                Ok(_) => {
+                    is_editing.set(false);
+                    on_change.call(());
+                }
+                Err(e) => error.set(format!("Ошибка обновления комментария: {}", e)),
+            }
+        });
+    };
+
+    let on_delete = move |_| {
+        let token_clone = token_delete.clone();
+
+        spawn(async move {
+            match infrastructure::delete_comment(comment_id, &token_clone).await {
+                Ok(_) => on_change.call(()),
+                Err(e) => error.set(format!("Ошибка удаления комментария: {}", e)),
+            }
+        });
+    };
+
+    rsx!(
+        div {
+            class: "comment",
+            p {
+                class: "comment-meta",
+                {created_at}
+                if comment.updated_at.is_some() {
+                    " (изменён)"
+                }
+            }
+
+            if *is_editing.read() {
+                form {
+                    onsubmit: on_save,
+                    textarea {
+                        value: "{content}",
+                        rows: "3",
+                        oninput: move |e| content.set(e.value())
+                    }
+                    button {
+                        class: "btn",
+                        r#type: "submit",
+                        "Сохранить"
+                    }
+                    button {
+                        class: "btn",
+                        r#type: "button",
+                        onclick: move |_| is_editing.set(false),
+                        "Отмена"
+                    }
+                }
+            } else {
+                p { {comment.content.clone()} }
+            }
+
+            div {
+                class: "comment-actions",
+                if user.is_some() {
+                    button {
+                        class: "link-btn",
+                        onclick: move |_| is_replying.toggle(),
+                        "Ответить"
+                    }
+                }
+                if can_edit {
+                    button {
+                        class: "link-btn",
+                        onclick: move |_| is_editing.set(true),
+                        "Редактировать"
+                    }
+                }
+                if can_delete {
+                    button {
+                        class: "link-btn",
+                        onclick: on_delete,
+                        "Удалить"
+                    }
+                }
+            }
+
+            if !error.read().is_empty() {
+                p {
+                    class: "error-message",
+                    "{error}"
+                }
+            }
+
+            if *is_replying.read() {
+                CommentForm {
+                    post_id,
+                    parent_id: Some(comment_id),
+                    on_done: move |_| {
+                        is_replying.set(false);
+                        on_change.call(());
+                    },
+                }
+            }
+
+            div {
+                class: "comment-replies",
+                for reply in replies {
+                    CommentNode {
+                        key: "{reply.id}",
+                        comment: reply,
+                        all: all.clone(),
+                        post_author,
+                        on_change,
+                    }
+                }
+            }
+        }
+    )
+}
+
+#[component]
+fn CommentForm(
+    post_id: i64,
+    #[props(!optional)] parent_id: Option<i64>,
+    on_done: EventHandler<()>,
+) -> Element {
+    let mut content = use_signal(String::new);
+    let mut error = use_signal(|| String::new());
+    let mut is_loading = use_signal(|| false);
+
+    let user = match infrastructure::get_token() {
+        Ok(n) => n,
+        Err(e) => {
+            return rsx!("Ошибка: `{e}`");
+        }
+    };
+
+    let on_submit = move |event: FormEvent| {
+        event.stop_propagation();
+        event.prevent_default();
+        error.set(String::new());
+
+        if content.read().trim().is_empty() {
+            error.set("Комментарий не может быть пустым".to_string());
+            return;
+        }
+
+        is_loading.set(true);
+
+        let content_clone = content.read().clone();
+        let token_clone = user.get_brear();
+
+        spawn(async move {
+            match infrastructure::create_comment(post_id, &content_clone, parent_id, &token_clone)
+                .await
+            {
+                Ok(_) => {
+                    content.set(String::new());
+                    is_loading.set(false);
+                    on_done.call(());
+                }
+                Err(e) => {
+                    error.set(format!("Ошибка создания комментария: {}", e));
+                    is_loading.set(false);
+                }
+            }
+        });
+    };
+
+    rsx!(
+        form {
+            class: "comment-form",
+            onsubmit: on_submit,
+            textarea {
+                value: "{content}",
+                placeholder: "Написать комментарий",
+                rows: "3",
+                oninput: move |e| content.set(e.value())
+            }
+
+            if !error.read().is_empty() {
+                p {
+                    class: "error-message",
+                    "{error}"
+                }
+            }
+
+            button {
+                class: "btn",
+                r#type: "submit",
+                disabled: *is_loading.read(),
+
+                if *is_loading.read() {
+                    "Отправка..."
+                } else {
+                    "Отправить"
+                }
+            }
+        }
+    )
+}
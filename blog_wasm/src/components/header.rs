@@ -29,11 +29,46 @@ pub fn Header() -> Element {
                 "Главная"
             }
             {auth_head}
+            SearchBox {}
         }
 
     )
 }
 
+#[component]
+fn SearchBox() -> Element {
+    let nav = navigator();
+    let mut query = use_signal(String::new);
+
+    let on_submit = move |event: FormEvent| {
+        event.stop_propagation();
+        event.prevent_default();
+
+        let q = query.read().trim().to_string();
+        if !q.is_empty() {
+            nav.push(Route::Search { q });
+        }
+    };
+
+    rsx!(
+        form {
+            class: "search-box",
+            onsubmit: on_submit,
+            input {
+                r#type: "search",
+                value: "{query}",
+                placeholder: "Поиск",
+                oninput: move |e| query.set(e.value())
+            }
+            button {
+                class: "auth-btn",
+                r#type: "submit",
+                "Найти"
+            }
+        }
+    )
+}
+
 #[component]
 pub fn NoAuth() -> Element {
     let nav = navigator();
@@ -56,11 +91,36 @@ pub fn NoAuth() -> Element {
 pub fn Auth() -> Element {
     let nav = navigator();
     rsx!(
+        button {
+            class: "auth-btn",
+            onclick: move |_| { nav.push(Route::Feed);},
+            "Лента"
+        }
+        button {
+            class: "auth-btn",
+            onclick: move |_| { nav.push(Route::Saved);},
+            "Сохранённые"
+        }
         button {
             class: "auth-btn",
             onclick: move |_| { nav.push(Route::CreatePost);},
             "Создать пост"
         }
+        button {
+            class: "auth-btn",
+            onclick: move |_| { nav.push(Route::Drafts);},
+            "Черновики"
+        }
+        button {
+            class: "auth-btn",
+            onclick: move |_| { nav.push(Route::EditProfile);},
+            "Профиль"
+        }
+        button {
+            class: "auth-btn",
+            onclick: move |_| { nav.push(Route::ChangePassword);},
+            "Пароль"
+        }
         ExitButton {  }
     )
 }
@@ -73,16 +133,24 @@ pub fn ExitButton() -> Element {
     rsx!(button {
         class: "logout-btn",
         onclick: move |_| {
-            if let Err(e) = infrastructure::delete_token(){
-                tracing::error!("Ошибка при удалении токена: {e}");
-            };
-            if current_route == Route::Index{
-                if let Some(window) = web_sys::window() {
-                    let _ = window.location().reload();
+            let current_route = current_route.clone();
+            spawn(async move {
+                if let Ok(data) = infrastructure::get_token() {
+                    if let Err(e) = infrastructure::logout(&data.refresh_token, &data.get_brear()).await {
+                        tracing::warn!("Ошибка при завершении сессии: {e}");
+                    }
                 }
-            }else {
-                nav.push(Route::Index);
-            }
+                if let Err(e) = infrastructure::delete_token(){
+                    tracing::error!("Ошибка при удалении токена: {e}");
+                };
+                if current_route == Route::Index{
+                    if let Some(window) = web_sys::window() {
+                        let _ = window.location().reload();
+                    }
+                }else {
+                    nav.push(Route::Index);
+                }
+            });
         },
         "Выход"
     })
@@ -1,5 +1,8 @@
+mod account;
 mod auth;
+mod comment;
 mod header;
 mod post;
+mod profile;
 
-pub use {auth::*, header::*, post::*};
+pub use {account::*, auth::*, comment::*, header::*, post::*, profile::*};
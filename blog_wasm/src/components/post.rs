@@ -1,14 +1,96 @@
+use std::collections::BTreeMap;
+
 use dioxus::prelude::*;
 
-use super::Header;
-use crate::{dto::Post, infrastructure, Route};
+use super::{CommentThread, Header};
+use crate::{
+    dto::{parse_publish_at, parse_tags, Post, REACTION_KINDS, STATUS_DRAFT, STATUS_PUBLISHED},
+    infrastructure, Route,
+};
+/// Постов на одной странице ленты
+const PAGE_SIZE: i32 = 20;
+
 #[component]
 pub fn Index() -> Element {
-    let posts = use_resource(move || infrastructure::get_list_posts(100, 0));
+    rsx!(PostIndex { tags: Vec::new() })
+}
+
+#[component]
+pub fn TagPosts(tag: String) -> Element {
+    // key сбрасывает пагинацию при переходе к другому тегу
+    rsx!(PostIndex {
+        key: "{tag}",
+        tags: vec![tag.clone()]
+    })
+}
+
+#[component]
+fn PostIndex(tags: Vec<String>) -> Element {
+    // Популярные посты листаются по смещению, новые - по курсорам
+    let mut liked = use_signal(|| false);
+    let mut offset = use_signal(|| 0);
+    // Курсоры пройденных страниц, последний указывает на текущую
+    let mut cursors = use_signal(Vec::<String>::new);
+    let posts = use_resource(use_reactive!(|(tags,)| async move {
+        if *liked.read() {
+            let offset = *offset.read();
+            infrastructure::get_list_posts(PAGE_SIZE, None, offset, "liked", &tags).await
+        } else {
+            let cursor = cursors.read().last().cloned();
+            infrastructure::get_list_posts(PAGE_SIZE, cursor.as_deref(), 0, "latest", &tags).await
+        }
+    }));
+    let mut set_order = move |value: bool| {
+        liked.set(value);
+        offset.set(0);
+        cursors.write().clear();
+    };
     let posts_list: Element = match &*posts.read() {
-        Some(Ok(posts)) => {
-            let posts = posts.post.clone();
-            rsx! { PostList { posts } }
+        Some(Ok(page)) => {
+            let posts = page.post.clone();
+            let total = page.total;
+            let next_cursor = page.next_cursor.clone();
+            let is_liked = *liked.read();
+            let page_number = if is_liked {
+                (*offset.read() / PAGE_SIZE) as usize + 1
+            } else {
+                cursors.read().len() + 1
+            };
+            let has_next_offset = is_liked && *offset.read() + PAGE_SIZE < total;
+            rsx! {
+                PostList { posts }
+                div {
+                    class: "pager",
+                    if page_number > 1 {
+                        button {
+                            class: "btn",
+                            onclick: move |_| {
+                                if is_liked {
+                                    offset -= PAGE_SIZE;
+                                } else {
+                                    cursors.write().pop();
+                                }
+                            },
+                            "Назад"
+                        }
+                    }
+                    span { "Страница {page_number}, всего постов: {total}" }
+                    if let Some(cursor) = next_cursor {
+                        button {
+                            class: "btn",
+                            onclick: move |_| cursors.write().push(cursor.clone()),
+                            "Далее"
+                        }
+                    }
+                    if has_next_offset {
+                        button {
+                            class: "btn",
+                            onclick: move |_| offset += PAGE_SIZE,
+                            "Далее"
+                        }
+                    }
+                }
+            }
         }
         Some(Err(e)) => rsx!("Ошибка: `{e}`"),
         None => rsx!("Загрузка ..."),
@@ -16,24 +98,180 @@ pub fn Index() -> Element {
 
     rsx!(
         Header {}
+        TagCloud { selected: tags.clone() }
+        div {
+            class: "order-switch",
+            button {
+                class: if *liked.read() { "tag-chip" } else { "tag-chip active" },
+                onclick: move |_| set_order(false),
+                "Новые"
+            }
+            button {
+                class: if *liked.read() { "tag-chip active" } else { "tag-chip" },
+                onclick: move |_| set_order(true),
+                "Популярные"
+            }
+        }
         div {
             {posts_list}
          }
     )
 }
 
+#[component]
+pub fn Search(q: String) -> Element {
+    let results = use_resource(use_reactive!(|(q,)| async move {
+        infrastructure::search_posts(&q, 20, 0).await
+    }));
+
+    let content = match &*results.read() {
+        Some(Ok(page)) => {
+            let hits = page.hits.clone();
+            let total = page.total;
+            rsx! {
+                p { "Найдено: {total}" }
+                div {
+                    class: "post-list",
+                    for hit in hits {
+                        div {
+                            key: "{hit.post.id}",
+                            class: "post-card",
+                            h3 {
+                                Link {
+                                    to: Route::PostPage { slug: hit.post.slug.clone() },
+                                    {hit.post.title.clone()}
+                                }
+                            }
+                            p {
+                                class: "search-snippet",
+                                dangerous_inner_html: "{hit.snippet}",
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Some(Err(e)) => rsx!("Ошибка: `{e}`"),
+        None => rsx!("Поиск ..."),
+    };
+
+    rsx!(
+        Header {}
+        h2 { "Поиск: {q}" }
+        div {
+            {content}
+        }
+    )
+}
+
+/// Страница поста по постоянной ссылке `/p/:slug`
+#[component]
+pub fn PostPage(slug: String) -> Element {
+    let post = use_resource(use_reactive!(|(slug,)| async move {
+        infrastructure::get_post_by_slug(&slug).await
+    }));
+
+    // Пришли по прежнему slug: показываем актуальный адрес
+    use_effect(use_reactive!(|(slug,)| {
+        if let Some(Ok(post)) = &*post.read() {
+            if post.slug != slug {
+                navigator().replace(Route::PostPage {
+                    slug: post.slug.clone(),
+                });
+            }
+        }
+    }));
+
+    let content = match &*post.read() {
+        Some(Ok(post)) => {
+            let posts = vec![post.clone()];
+            rsx! { PostList { posts } }
+        }
+        Some(Err(e)) => rsx!("Ошибка: `{e}`"),
+        None => rsx!("Загрузка ..."),
+    };
+
+    rsx!(
+        Header {}
+        div {
+            {content}
+        }
+    )
+}
+
+#[component]
+fn TagCloud(selected: Vec<String>) -> Element {
+    let tags = use_resource(infrastructure::get_tags);
+    let tags = match &*tags.read() {
+        Some(Ok(tags)) => tags.clone(),
+        _ => Vec::new(),
+    };
+
+    rsx!(
+        div {
+            class: "tag-cloud",
+            if !selected.is_empty() {
+                button {
+                    class: "tag-chip",
+                    onclick: move |_| { navigator().push(Route::Index); },
+                    "Все посты"
+                }
+            }
+            for tag in tags {
+                TagChip {
+                    key: "{tag.name}",
+                    active: selected.contains(&tag.name),
+                    label: format!("{} ({})", tag.name, tag.post_count),
+                    name: tag.name,
+                }
+            }
+        }
+    )
+}
+
+#[component]
+fn TagChip(name: String, label: String, active: bool) -> Element {
+    rsx!(
+        button {
+            class: if active { "tag-chip active" } else { "tag-chip" },
+            onclick: move |_| {
+                navigator().push(Route::TagPosts { tag: name.clone() });
+            },
+            "#{label}"
+        }
+    )
+}
+
 #[component]
 pub fn PostList(posts: Vec<Post>) -> Element {
     let post_components = posts
         .into_iter()
         .map(|post_data| {
+            let post_id = post_data.id;
+            let post_author = post_data.author_id;
+            let is_draft = post_data.is_draft();
             let create_at = post_data.get_created_at();
             let update_at = post_data.get_update_at();
+            let publish_at = post_data.get_publish_at();
             rsx!(
                 div {
                     class: "post-card",
                     h3 {
-                        {post_data.title}
+                        if post_data.is_published() {
+                            Link {
+                                to: Route::PostPage { slug: post_data.slug.clone() },
+                                {post_data.title.clone()}
+                            }
+                        } else {
+                            {post_data.title.clone()}
+                        }
+                    }
+                    p {
+                        class: "post-author",
+                        Link {
+                            to: Route::AuthorPage { username: post_data.author.username.clone() },
+                            {post_data.author.name().to_string()}
+                        }
                     }
                     p {
                         {create_at}
@@ -41,8 +279,38 @@ pub fn PostList(posts: Vec<Post>) -> Element {
                     if let Some(t) = update_at {
                         p { {t} }
                     }
-                    p {
-                        {post_data.content}
+                    if let Some(t) = publish_at {
+                        p { {t} }
+                    }
+                    if post_data.content_html.is_empty() {
+                        p {
+                            {post_data.content.clone()}
+                        }
+                    } else {
+                        div {
+                            class: "post-content",
+                            dangerous_inner_html: "{post_data.content_html}",
+                        }
+                    }
+                    if !post_data.tags.is_empty() {
+                        div {
+                            class: "tag-cloud",
+                            for tag in post_data.tags.clone() {
+                                TagChip {
+                                    key: "{tag}",
+                                    active: false,
+                                    label: tag.clone(),
+                                    name: tag,
+                                }
+                            }
+                        }
+                    }
+
+                    if post_data.is_published() {
+                        ReactionBar { post_id, counts: post_data.reactions.clone() }
+                        if infrastructure::get_token().is_ok() {
+                            BookmarkButton { post_id, bookmarked: post_data.bookmarked.unwrap_or(false) }
+                        }
                     }
 
                     if let Ok(name) = infrastructure::get_token() {
@@ -55,8 +323,13 @@ pub fn PostList(posts: Vec<Post>) -> Element {
                                     "Редактировать"
                                 }
                                 DeletePost {id: post_data.id}
+                                if is_draft {
+                                    PublishPost { id: post_data.id }
+                                }
                         }
                     }
+
+                    CommentThread { post_id, post_author }
                 }
             )
         })
@@ -82,9 +355,97 @@ pub fn PostList(posts: Vec<Post>) -> Element {
     )
 }
 
+/// Кнопки реакций со счётчиками. Свои реакции сервер сообщает только
+/// в ответе на изменение, поэтому до первого нажатия они не подсвечены
+#[component]
+fn ReactionBar(post_id: i64, counts: BTreeMap<String, i64>) -> Element {
+    let mut counts = use_signal(|| counts);
+    let mut reacted = use_signal(Vec::<String>::new);
+    let mut error = use_signal(String::new);
+    let user = infrastructure::get_token().ok();
+
+    rsx!(
+        div {
+            class: "reaction-bar",
+            for (kind, icon) in REACTION_KINDS {
+                button {
+                    key: "{kind}",
+                    class: if reacted.read().iter().any(|k| k == kind) { "reaction active" } else { "reaction" },
+                    disabled: user.is_none(),
+                    title: if user.is_none() { "Войдите, чтобы ставить реакции" } else { kind },
+                    onclick: {
+                        let token = user.as_ref().map(|u| u.get_brear());
+                        move |_| {
+                            let Some(token) = token.clone() else {
+                                return;
+                            };
+                            let remove = reacted.read().iter().any(|k| k == kind);
+                            spawn(async move {
+                                match infrastructure::react_post(post_id, kind, remove, &token).await {
+                                    Ok(reactions) => {
+                                        error.set(String::new());
+                                        counts.set(reactions.counts);
+                                        reacted.set(reactions.reacted);
+                                    }
+                                    Err(e) => error.set(format!("Ошибка: {}", e)),
+                                }
+                            });
+                        }
+                    },
+                    {format!("{} {}", icon, counts.read().get(kind).copied().unwrap_or_default())}
+                }
+            }
+            if !error.read().is_empty() {
+                span { class: "error-message", "{error}" }
+            }
+        }
+    )
+}
+
+/// Добавление поста в закладки и удаление из них
+#[component]
+fn BookmarkButton(post_id: i64, bookmarked: bool) -> Element {
+    let mut bookmarked = use_signal(|| bookmarked);
+    let mut error = use_signal(String::new);
+    let mut is_loading = use_signal(|| false);
+
+    let on_click = move |_| {
+        error.set(String::new());
+        is_loading.set(true);
+        let remove = bookmarked();
+
+        spawn(async move {
+            let result = match infrastructure::get_token() {
+                Ok(user) => infrastructure::bookmark_post(post_id, remove, &user.get_brear()).await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(bookmark) => bookmarked.set(bookmark.bookmarked),
+                Err(e) => error.set(format!("Ошибка: {}", e)),
+            }
+            is_loading.set(false);
+        });
+    };
+
+    rsx!(
+        button {
+            class: if bookmarked() { "reaction active" } else { "reaction" },
+            onclick: on_click,
+            disabled: *is_loading.read(),
+            if bookmarked() { "🔖 В закладках" } else { "🔖 В закладки" }
+        }
+        if !error.read().is_empty() {
+            span { class: "error-message", "{error}" }
+        }
+    )
+}
+
 #[component]
 pub fn PostUpdate(id: i64) -> Element {
-    let post = use_resource(move || infrastructure::get_post(id));
+    let post = use_resource(move || async move {
+        let user = infrastructure::get_token()?;
+        infrastructure::get_own_post(id, &user.get_brear()).await
+    });
     let post_data = match &*post.read() {
         Some(Ok(post_data)) => post_data.clone(),
         Some(Err(e)) => {
@@ -103,6 +464,9 @@ pub fn PostUpdate(id: i64) -> Element {
 
     let mut title_post = use_signal(|| post_data.title.clone());
     let mut content_post = use_signal(|| post_data.content.clone());
+    let mut tags_post = use_signal(|| post_data.tags.join(", "));
+    let mut as_draft = use_signal(|| post_data.is_draft());
+    let mut publish_at_post = use_signal(|| post_data.publish_at_input());
 
     let mut error = use_signal(|| String::new());
     let mut is_loading = use_signal(|| false);
@@ -133,6 +497,20 @@ pub fn PostUpdate(id: i64) -> Element {
 
         let title_post_clone = title_post.read().clone();
         let content_post_clone = content_post.read().clone();
+        let tags_post_clone = parse_tags(&tags_post.read());
+        let publish_at = match parse_publish_at(&publish_at_post.read()) {
+            Ok(t) => t,
+            Err(e) => {
+                error.set(e);
+                is_loading.set(false);
+                return;
+            }
+        };
+        let status = if *as_draft.read() || publish_at.is_some() {
+            STATUS_DRAFT
+        } else {
+            STATUS_PUBLISHED
+        };
         let mut error_clone = error.clone();
         let nav_clone = nav.clone();
         let token_clone = user.get_brear();
@@ -143,6 +521,9 @@ pub fn PostUpdate(id: i64) -> Element {
                 post_id,
                 &title_post_clone,
                 &content_post_clone,
+                tags_post_clone,
+                status,
+                publish_at,
                 &token_clone,
             )
             .await
@@ -182,10 +563,30 @@ pub fn PostUpdate(id: i64) -> Element {
                     label {"Содержимое"}
                     textarea {
                         value: "{content_post}",
-                        placeholder: "Введите текст",
+                        placeholder: "Введите текст (Markdown)",
                         rows: "15",
                         oninput: move |e| content_post.set(e.value())
                     }
+                    ImagePicker { content: content_post, post_id: Some(post_data.id) }
+                }
+
+                div {
+                    label {"Теги"}
+                    input {
+                        r#type: "text",
+                        value: "{tags_post}",
+                        placeholder: "rust, wasm",
+                        oninput: move |e| tags_post.set(e.value())
+                    }
+                }
+
+                div {
+                    label {"Отложенная публикация"}
+                    input {
+                        r#type: "datetime-local",
+                        value: "{publish_at_post}",
+                        oninput: move |e| publish_at_post.set(e.value())
+                    }
                 }
 
                 if !error.read().is_empty() {
@@ -200,11 +601,22 @@ pub fn PostUpdate(id: i64) -> Element {
                         class: "btn",
                         r#type: "submit",
                         disabled: *is_loading.read(),
+                        onclick: move |_| as_draft.set(true),
+
+                        "Сохранить черновик"
+                    }
+                    button {
+                        class: "btn",
+                        r#type: "submit",
+                        disabled: *is_loading.read(),
+                        onclick: move |_| as_draft.set(false),
 
                         if *is_loading.read(){
                             "Обновление..."
+                        } else if !publish_at_post.read().is_empty() {
+                            "Запланировать"
                         } else {
-                            "Обновить"
+                            "Опубликовать"
                         }
                     }
                 }
@@ -217,6 +629,9 @@ pub fn PostUpdate(id: i64) -> Element {
 pub fn CreatePost() -> Element {
     let mut title_post = use_signal(String::new);
     let mut content_post = use_signal(String::new);
+    let mut tags_post = use_signal(String::new);
+    let mut publish_at_post = use_signal(String::new);
+    let mut as_draft = use_signal(|| false);
     let mut error = use_signal(|| String::new());
     let mut is_loading = use_signal(|| false);
 
@@ -246,13 +661,30 @@ pub fn CreatePost() -> Element {
 
         let title_post_clone = title_post.read().clone();
         let content_post_clone = content_post.read().clone();
+        let tags_post_clone = parse_tags(&tags_post.read());
+        let publish_at = match parse_publish_at(&publish_at_post.read()) {
+            Ok(t) => t,
+            Err(e) => {
+                error.set(e);
+                is_loading.set(false);
+                return;
+            }
+        };
+        let draft = *as_draft.read();
         let mut error_clone = error.clone();
         let nav_clone = nav.clone();
         let token_clone = name.get_brear();
 
         spawn(async move {
-            match infrastructure::create_post(&title_post_clone, &content_post_clone, &token_clone)
-                .await
+            match infrastructure::create_post(
+                &title_post_clone,
+                &content_post_clone,
+                tags_post_clone,
+                draft,
+                publish_at,
+                &token_clone,
+            )
+            .await
             {
                 Ok(_) => {
                     nav_clone.push(Route::Index);
@@ -288,10 +720,30 @@ pub fn CreatePost() -> Element {
                     label {"Содержимое"}
                     textarea {
                         value: "{content_post}",
-                        placeholder: "Введите текст",
+                        placeholder: "Введите текст (Markdown)",
                         rows: "15",
                         oninput: move |e| content_post.set(e.value())
                     }
+                    ImagePicker { content: content_post, post_id: None }
+                }
+
+                div {
+                    label {"Теги"}
+                    input {
+                        r#type: "text",
+                        value: "{tags_post}",
+                        placeholder: "rust, wasm",
+                        oninput: move |e| tags_post.set(e.value())
+                    }
+                }
+
+                div {
+                    label {"Отложенная публикация"}
+                    input {
+                        r#type: "datetime-local",
+                        value: "{publish_at_post}",
+                        oninput: move |e| publish_at_post.set(e.value())
+                    }
                 }
 
                 if !error.read().is_empty() {
@@ -306,16 +758,278 @@ pub fn CreatePost() -> Element {
                         class: "btn",
                         r#type: "submit",
                         disabled: *is_loading.read(),
+                        onclick: move |_| as_draft.set(true),
+
+                        "Сохранить черновик"
+                    }
+                    button {
+                        class: "btn",
+                        r#type: "submit",
+                        disabled: *is_loading.read(),
+                        onclick: move |_| as_draft.set(false),
 
                         if *is_loading.read(){
                             "Создание..."
+                        } else if !publish_at_post.read().is_empty() {
+                            "Запланировать"
                         } else {
-                            "Создать"
+                            "Опубликовать"
+                        }
+                    }
+                }
+            }
+        }
+    )
+}
+
+/// Загружает картинку и дописывает в текст поста Markdown-ссылку на неё
+#[component]
+fn ImagePicker(mut content: Signal<String>, post_id: Option<i64>) -> Element {
+    let mut error = use_signal(String::new);
+    let mut is_uploading = use_signal(|| false);
+
+    let on_change = move |event: FormEvent| async move {
+        let Some(file) = event.files().into_iter().next() else {
+            return;
+        };
+        let token = match infrastructure::get_token() {
+            Ok(user) => user.get_brear(),
+            Err(e) => {
+                error.set(e);
+                return;
+            }
+        };
+
+        is_uploading.set(true);
+        error.set(String::new());
+
+        let result = match file.read_bytes().await {
+            Ok(data) => {
+                infrastructure::upload_attachment(&file.name(), data.to_vec(), post_id, &token)
+                    .await
+            }
+            Err(e) => Err(e.to_string()),
+        };
+        match result {
+            Ok(attachment) => {
+                let alt = attachment.file_name.replace(['[', ']'], "");
+                let mut text = content.read().clone();
+                if !text.is_empty() && !text.ends_with('\n') {
+                    text.push('\n');
+                }
+                text.push_str(&format!("![{}]({})\n", alt, attachment.url));
+                content.set(text);
+            }
+            Err(e) => error.set(format!("Ошибка загрузки: {}", e)),
+        }
+        is_uploading.set(false);
+    };
+
+    rsx!(
+        div {
+            class: "image-picker",
+            label {
+                class: "btn",
+                if *is_uploading.read() {
+                    "Загрузка..."
+                } else {
+                    "Добавить картинку"
+                }
+                input {
+                    r#type: "file",
+                    accept: "image/png,image/jpeg,image/gif,image/webp",
+                    disabled: *is_uploading.read(),
+                    onchange: on_change,
+                }
+            }
+            if !error.read().is_empty() {
+                p {
+                    class: "error-message",
+                    "{error}"
+                }
+            }
+        }
+    )
+}
+
+/// Посты авторов из подписок
+#[component]
+pub fn Feed() -> Element {
+    let mut cursors = use_signal(Vec::<String>::new);
+    let posts = use_resource(move || async move {
+        let user = infrastructure::get_token()?;
+        let cursor = cursors.read().last().cloned();
+        infrastructure::get_feed(PAGE_SIZE, cursor.as_deref(), &user.get_brear()).await
+    });
+    let posts_list: Element = match &*posts.read() {
+        Some(Ok(page)) if page.total == 0 => rsx!(
+            p { "Подпишитесь на авторов, и их новые посты появятся здесь" }
+        ),
+        Some(Ok(page)) => {
+            let posts = page.post.clone();
+            let total = page.total;
+            let next_cursor = page.next_cursor.clone();
+            let page_number = cursors.read().len() + 1;
+            rsx! {
+                PostList { posts }
+                div {
+                    class: "pager",
+                    if page_number > 1 {
+                        button {
+                            class: "btn",
+                            onclick: move |_| {
+                                cursors.write().pop();
+                            },
+                            "Назад"
+                        }
+                    }
+                    span { "Страница {page_number}, всего постов: {total}" }
+                    if let Some(cursor) = next_cursor {
+                        button {
+                            class: "btn",
+                            onclick: move |_| cursors.write().push(cursor.clone()),
+                            "Далее"
+                        }
+                    }
+                }
+            }
+        }
+        Some(Err(e)) => rsx!("Ошибка: `{e}`"),
+        None => rsx!("Загрузка ..."),
+    };
+
+    rsx!(
+        Header {}
+        h2 { "Лента" }
+        div {
+            {posts_list}
+        }
+    )
+}
+
+/// Сохранённые посты, от новых закладок к старым
+#[component]
+pub fn Saved() -> Element {
+    let mut offset = use_signal(|| 0);
+    let posts = use_resource(move || async move {
+        let user = infrastructure::get_token()?;
+        infrastructure::get_bookmarks(PAGE_SIZE, offset(), &user.get_brear()).await
+    });
+    let posts_list: Element = match &*posts.read() {
+        Some(Ok(page)) if page.total == 0 => rsx!(
+            p { "Сохраняйте посты кнопкой «В закладки», и они появятся здесь" }
+        ),
+        Some(Ok(page)) => {
+            let posts = page.post.clone();
+            let total = page.total;
+            let page_number = offset() / PAGE_SIZE + 1;
+            let has_next = offset() + PAGE_SIZE < total;
+            rsx! {
+                PostList { posts }
+                div {
+                    class: "pager",
+                    if page_number > 1 {
+                        button {
+                            class: "btn",
+                            onclick: move |_| offset.set(offset() - PAGE_SIZE),
+                            "Назад"
+                        }
+                    }
+                    span { "Страница {page_number}, всего постов: {total}" }
+                    if has_next {
+                        button {
+                            class: "btn",
+                            onclick: move |_| offset.set(offset() + PAGE_SIZE),
+                            "Далее"
                         }
                     }
                 }
             }
         }
+        Some(Err(e)) => rsx!("Ошибка: `{e}`"),
+        None => rsx!("Загрузка ..."),
+    };
+
+    rsx!(
+        Header {}
+        h2 { "Сохранённые" }
+        div {
+            {posts_list}
+        }
+    )
+}
+
+#[component]
+pub fn Drafts() -> Element {
+    let drafts = use_resource(move || async move {
+        let user = infrastructure::get_token()?;
+        infrastructure::get_drafts(100, 0, &user.get_brear()).await
+    });
+    let drafts_list: Element = match &*drafts.read() {
+        Some(Ok(page)) => {
+            let posts = page.post.clone();
+            rsx! { PostList { posts } }
+        }
+        Some(Err(e)) => rsx!("Ошибка: `{e}`"),
+        None => rsx!("Загрузка ..."),
+    };
+
+    rsx!(
+        Header {}
+        h2 { "Черновики" }
+        div {
+            {drafts_list}
+        }
+    )
+}
+
+#[component]
+pub fn PublishPost(id: i64) -> Element {
+    let mut error = use_signal(|| String::new());
+    let mut is_loading = use_signal(|| false);
+
+    let user = match infrastructure::get_token() {
+        Ok(n) => n,
+        Err(e) => {
+            return rsx!("Ошибка: `{e}`");
+        }
+    };
+
+    let on_click = move |_| {
+        error.set(String::new());
+        is_loading.set(true);
+
+        let mut error_clone = error.clone();
+        let token = user.get_brear();
+
+        spawn(async move {
+            match infrastructure::change_post_status(id, "publish", &token).await {
+                Ok(_) => {
+                    if let Some(window) = web_sys::window() {
+                        let _ = window.location().reload();
+                    }
+                }
+                Err(e) => {
+                    error_clone.set(format!("Ошибка публикации: {}", e));
+                    is_loading.set(false);
+                }
+            }
+        });
+    };
+
+    rsx!(
+        button {
+            class: "btn",
+            onclick: on_click,
+            disabled: *is_loading.read(),
+            "Опубликовать"
+        }
+        if !error.read().is_empty() {
+            p {
+                class: "error-message",
+                "{error}"
+            }
+        }
     )
 }
 
@@ -0,0 +1,305 @@
+use dioxus::prelude::*;
+
+use super::{Header, PostList};
+use crate::{
+    dto::{UpdateProfileRequest, UserProfile},
+    infrastructure, Route,
+};
+/// Постов автора на одной странице
+const PAGE_SIZE: i32 = 20;
+
+/// Страница автора: профиль и его опубликованные посты
+#[component]
+pub fn AuthorPage(username: String) -> Element {
+    let profile = use_resource(use_reactive!(|(username,)| async move {
+        infrastructure::get_user_profile(&username).await
+    }));
+
+    let content = match &*profile.read() {
+        Some(Ok(profile)) => rsx! {
+            ProfileCard { profile: profile.clone() }
+            // key сбрасывает пагинацию при переходе к другому автору
+            AuthorPosts { key: "{profile.username}", username: profile.username.clone() }
+        },
+        Some(Err(e)) => rsx!("Ошибка: `{e}`"),
+        None => rsx!("Загрузка ..."),
+    };
+
+    rsx!(
+        Header {}
+        div {
+            {content}
+        }
+    )
+}
+
+#[component]
+fn AuthorPosts(username: String) -> Element {
+    let mut offset = use_signal(|| 0);
+    let posts = use_resource(use_reactive!(|(username,)| async move {
+        infrastructure::get_user_posts(&username, PAGE_SIZE, offset()).await
+    }));
+
+    match &*posts.read() {
+        Some(Ok(page)) => {
+            let posts = page.post.clone();
+            let total = page.total;
+            let page_number = offset() / PAGE_SIZE + 1;
+            let has_next = offset() + PAGE_SIZE < total;
+            rsx! {
+                PostList { posts }
+                div {
+                    class: "pager",
+                    if page_number > 1 {
+                        button {
+                            class: "btn",
+                            onclick: move |_| offset.set(offset() - PAGE_SIZE),
+                            "Назад"
+                        }
+                    }
+                    span { "Страница {page_number}, всего постов: {total}" }
+                    if has_next {
+                        button {
+                            class: "btn",
+                            onclick: move |_| offset.set(offset() + PAGE_SIZE),
+                            "Далее"
+                        }
+                    }
+                }
+            }
+        }
+        Some(Err(e)) => rsx!("Ошибка: `{e}`"),
+        None => rsx!("Загрузка ..."),
+    }
+}
+
+#[component]
+fn ProfileCard(profile: UserProfile) -> Element {
+    let is_own = infrastructure::get_token()
+        .map(|user| user.uuid == profile.id)
+        .unwrap_or(false);
+
+    rsx!(
+        div {
+            class: "profile-card",
+            if let Some(avatar_url) = profile.avatar_url.clone() {
+                img { class: "avatar", src: "{avatar_url}", alt: "{profile.username}" }
+            }
+            h2 { {profile.display_name.clone().unwrap_or_else(|| profile.username.clone())} }
+            p { class: "username", "@{profile.username}" }
+            if let Some(bio) = profile.bio.clone() {
+                p { class: "bio", "{bio}" }
+            }
+            if !profile.links.is_empty() {
+                ul {
+                    class: "profile-links",
+                    for link in profile.links.clone() {
+                        li {
+                            key: "{link}",
+                            a { href: "{link}", rel: "nofollow noopener", target: "_blank", "{link}" }
+                        }
+                    }
+                }
+            }
+            if is_own {
+                button {
+                    class: "btn",
+                    onclick: move |_| { navigator().push(Route::EditProfile); },
+                    "Редактировать профиль"
+                }
+            } else if infrastructure::get_token().is_ok() {
+                FollowButton { username: profile.username.clone() }
+            }
+        }
+    )
+}
+
+/// Подписка на автора со счётчиком подписчиков
+#[component]
+fn FollowButton(username: String) -> Element {
+    let mut status = use_resource(use_reactive!(|(username,)| async move {
+        let user = infrastructure::get_token()?;
+        infrastructure::get_follow_status(&username, &user.get_brear()).await
+    }));
+    let mut error = use_signal(String::new);
+    let mut is_loading = use_signal(|| false);
+
+    let current = match &*status.read() {
+        Some(Ok(current)) => current.clone(),
+        Some(Err(e)) => return rsx!("Ошибка: `{e}`"),
+        None => return rsx!(),
+    };
+
+    let following = current.following;
+    let followers = current.followers;
+    let on_click = move |_| {
+        error.set(String::new());
+        is_loading.set(true);
+        let username = current.username.clone();
+
+        spawn(async move {
+            let result = match infrastructure::get_token() {
+                Ok(user) => infrastructure::follow(&username, following, &user.get_brear()).await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(_) => status.restart(),
+                Err(e) => error.set(format!("Ошибка: {}", e)),
+            }
+            is_loading.set(false);
+        });
+    };
+
+    rsx!(
+        p { "Подписчиков: {followers}" }
+        button {
+            class: "btn",
+            onclick: on_click,
+            disabled: *is_loading.read(),
+            if following { "Отписаться" } else { "Подписаться" }
+        }
+        if !error.read().is_empty() {
+            p {
+                class: "error-message",
+                "{error}"
+            }
+        }
+    )
+}
+
+#[component]
+pub fn EditProfile() -> Element {
+    let mut display_name = use_signal(|| String::new());
+    let mut bio = use_signal(|| String::new());
+    let mut avatar_url = use_signal(|| String::new());
+    // По одной ссылке на строку
+    let mut links = use_signal(|| String::new());
+    let mut error = use_signal(|| String::new());
+    let mut is_loading = use_signal(|| false);
+
+    let user = match infrastructure::get_token() {
+        Ok(user) => user,
+        Err(_) => {
+            return rsx!(
+                Header {}
+                p { class: "error-message", "Войдите, чтобы изменить профиль" }
+            )
+        }
+    };
+
+    // Форма заполняется текущим профилем
+    let username = user.username.clone();
+    use_resource(move || {
+        let username = username.clone();
+        async move {
+            match infrastructure::get_user_profile(&username).await {
+                Ok(profile) => {
+                    display_name.set(profile.display_name.unwrap_or_default());
+                    bio.set(profile.bio.unwrap_or_default());
+                    avatar_url.set(profile.avatar_url.unwrap_or_default());
+                    links.set(profile.links.join("\n"));
+                }
+                Err(e) => error.set(e),
+            }
+        }
+    });
+
+    let on_submit = move |event: FormEvent| {
+        event.stop_propagation();
+        event.prevent_default();
+        error.set(String::new());
+        is_loading.set(true);
+
+        let non_empty = |value: String| Some(value).filter(|v| !v.trim().is_empty());
+        let request = UpdateProfileRequest {
+            display_name: non_empty(display_name()),
+            bio: non_empty(bio()),
+            avatar_url: non_empty(avatar_url()),
+            links: links()
+                .lines()
+                .map(str::trim)
+                .filter(|link| !link.is_empty())
+                .map(str::to_string)
+                .collect(),
+        };
+        let token = user.get_brear();
+
+        spawn(async move {
+            match infrastructure::update_profile(&request, &token).await {
+                Ok(profile) => {
+                    navigator().push(Route::AuthorPage {
+                        username: profile.username,
+                    });
+                }
+                Err(e) => error.set(format!("Ошибка сохранения профиля: {}", e)),
+            }
+            is_loading.set(false);
+        });
+    };
+
+    rsx!(
+        Header {}
+        div {
+            class: "form-container",
+            div{
+                h2 { "Профиль" }
+            }
+            form {
+                onsubmit: on_submit,
+
+                div {
+                    label { "Отображаемое имя"}
+                    input {
+                        value: "{display_name}",
+                        oninput: move |e| display_name.set(e.value())
+                    }
+                }
+
+                div {
+                    label { "О себе"}
+                    textarea {
+                        value: "{bio}",
+                        oninput: move |e| bio.set(e.value())
+                    }
+                }
+
+                div {
+                    label { "Аватар"}
+                    input {
+                        value: "{avatar_url}",
+                        placeholder: "https://... или /api/attachments/...",
+                        oninput: move |e| avatar_url.set(e.value())
+                    }
+                }
+
+                div {
+                    label { "Ссылки"}
+                    textarea {
+                        value: "{links}",
+                        placeholder: "По одной на строку, не больше 5",
+                        oninput: move |e| links.set(e.value())
+                    }
+                }
+                if !error.read().is_empty() {
+                    p {
+                        class: "error-message",
+                        "{error}"
+                    }
+                }
+
+                div {
+                    button {
+                        r#type: "submit",
+                        disabled: *is_loading.read(),
+                        "Сохранить"
+                    }
+                }
+            }
+            button {
+                class: "btn",
+                onclick: move |_| { navigator().push(Route::DeleteAccount); },
+                "Выгрузка данных и удаление аккаунта"
+            }
+        }
+    )
+}
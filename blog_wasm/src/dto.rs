@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use chrono::{Datelike, Timelike};
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, TimestampSeconds};
@@ -9,16 +11,127 @@ static TIME_ZONE: chrono::FixedOffset = chrono::FixedOffset::east_opt(3 * 3600).
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Post {
     pub id: i64,
+    #[serde(default)]
+    pub slug: String,
     pub title: String,
     pub content: String,
+    #[serde(default)]
+    pub content_format: String,
+    /// HTML, отрендеренный и очищенный сервером
+    #[serde(default)]
+    pub content_html: String,
     pub author_id: Uuid,
+    #[serde(default)]
+    pub author: PostAuthor,
     #[serde_as(as = "TimestampSeconds<i64>")]
     pub created_at: chrono::DateTime<chrono::Utc>,
     #[serde_as(as = "Option<TimestampSeconds<i64>>")]
     pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub status: String,
+    #[serde_as(as = "Option<TimestampSeconds<i64>>")]
+    #[serde(default)]
+    pub published_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde_as(as = "Option<TimestampSeconds<i64>>")]
+    #[serde(default)]
+    pub publish_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Количество реакций по видам
+    #[serde(default)]
+    pub reactions: BTreeMap<String, i64>,
+    /// В закладках у текущего пользователя. Приходит только с токеном
+    #[serde(default)]
+    pub bookmarked: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PostAuthor {
+    pub username: String,
+    #[serde(default)]
+    pub display_name: Option<String>,
+}
+
+impl PostAuthor {
+    /// Отображаемое имя, если оно задано, иначе имя пользователя
+    pub fn name(&self) -> &str {
+        self.display_name.as_deref().unwrap_or(&self.username)
+    }
 }
 
+/// Виды реакций и их значки в порядке отображения
+pub const REACTION_KINDS: [(&str, &str); 4] = [
+    ("like", "👍"),
+    ("love", "❤️"),
+    ("laugh", "😂"),
+    ("wow", "😮"),
+];
+
+/// Реакции поста после изменения
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PostReactions {
+    pub post_id: i64,
+    pub counts: BTreeMap<String, i64>,
+    pub reacted: Vec<String>,
+}
+
+/// Закладка текущего пользователя после изменения
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Bookmark {
+    pub post_id: i64,
+    pub bookmarked: bool,
+}
+
+/// Подписка текущего пользователя на автора
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FollowStatus {
+    pub username: String,
+    pub following: bool,
+    pub followers: i64,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UserProfile {
+    pub id: Uuid,
+    pub username: String,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub bio: Option<String>,
+    #[serde(default)]
+    pub avatar_url: Option<String>,
+    #[serde(default)]
+    pub links: Vec<String>,
+    #[serde_as(as = "TimestampSeconds<i64>")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UserProfileResponse {
+    pub profile: UserProfile,
+}
+
+/// Профиль заменяется целиком, `None` очищает поле
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateProfileRequest {
+    pub display_name: Option<String>,
+    pub bio: Option<String>,
+    pub avatar_url: Option<String>,
+    pub links: Vec<String>,
+}
+
+pub const STATUS_DRAFT: &str = "draft";
+pub const STATUS_PUBLISHED: &str = "published";
+
 impl Post {
+    pub fn is_draft(&self) -> bool {
+        self.status == STATUS_DRAFT
+    }
+
+    pub fn is_published(&self) -> bool {
+        self.status == STATUS_PUBLISHED
+    }
+
     pub fn get_created_at(&self) -> String {
         let now = self.created_at.with_timezone(&TIME_ZONE);
         format!(
@@ -46,6 +159,61 @@ impl Post {
 
         None
     }
+
+    pub fn get_publish_at(&self) -> Option<String> {
+        let now = self.publish_at?.with_timezone(&TIME_ZONE);
+        Some(format!(
+            "Публикация запланирована: {}.{}.{} {}:{:02}",
+            now.day(),
+            now.month(),
+            now.year(),
+            now.hour(),
+            now.minute()
+        ))
+    }
+
+    /// Значение для `<input type="datetime-local">`
+    pub fn publish_at_input(&self) -> String {
+        self.publish_at
+            .map(|t| {
+                t.with_timezone(&TIME_ZONE)
+                    .format(DATETIME_INPUT_FORMAT)
+                    .to_string()
+            })
+            .unwrap_or_default()
+    }
+}
+
+const DATETIME_INPUT_FORMAT: &str = "%Y-%m-%dT%H:%M";
+
+/// Разбирает значение `<input type="datetime-local">` в unix time.
+/// Пустая строка означает публикацию без расписания
+pub fn parse_publish_at(value: &str) -> Result<Option<i64>, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+
+    chrono::NaiveDateTime::parse_from_str(value, DATETIME_INPUT_FORMAT)
+        .ok()
+        .and_then(|t| t.and_local_timezone(TIME_ZONE).single())
+        .map(|t| Some(t.timestamp()))
+        .ok_or_else(|| "Некорректная дата публикации".to_string())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Attachment {
+    pub id: i64,
+    pub post_id: Option<i64>,
+    pub file_name: String,
+    pub content_type: String,
+    pub size: i64,
+    pub url: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AttachmentResponse {
+    pub attachment: Attachment,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -59,6 +227,44 @@ pub struct PostPage {
     pub total: i32,
     pub limit: i32,
     pub offset: i32,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SearchHit {
+    pub post: Post,
+    pub rank: f32,
+    /// Фрагмент с подсветкой `<mark>`, экранирован сервером
+    pub snippet: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchPage {
+    pub hits: Vec<SearchHit>,
+    pub total: i32,
+    pub limit: i32,
+    pub offset: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TagCount {
+    pub name: String,
+    pub post_count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TagList {
+    pub tags: Vec<TagCount>,
+}
+
+/// Разбирает теги, введённые через запятую
+pub fn parse_tags(input: &str) -> Vec<String> {
+    input
+        .split(',')
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect()
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -77,13 +283,72 @@ pub struct LoginRequest {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AuthResponse {
     pub token: String,
+    pub refresh_token: String,
+    pub expires_in: i64,
     pub uuid: Uuid,
 }
 
+/// Ответ 202 на вход с включённой 2FA
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TwoFactorChallenge {
+    pub two_factor_challenge: String,
+    pub expires_in: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LoginTwoFactorRequest {
+    pub challenge: String,
+    pub code: String,
+}
+
+/// Результат первого шага входа
+#[derive(Debug)]
+pub enum LoginResult {
+    Authenticated(AuthResponse),
+    TwoFactorRequired(TwoFactorChallenge),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RefreshRequest {
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChangePasswordRequest {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+/// posts: "delete" или "anonymize"
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeleteAccountRequest {
+    pub password: String,
+    pub posts: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PasswordResetRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VerifyEmailRequest {
+    pub token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PasswordResetConfirm {
+    pub token: String,
+    pub new_password: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreatePostRequest {
     pub title: String,
     pub content: String,
+    pub tags: Vec<String>,
+    pub draft: bool,
+    pub publish_at: Option<i64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -91,6 +356,53 @@ pub struct UpdatePostRequest {
     pub id: i64,
     pub title: String,
     pub content: String,
+    pub tags: Vec<String>,
+    pub status: String,
+    pub publish_at: Option<i64>,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Comment {
+    pub id: i64,
+    pub post_id: i64,
+    pub author_id: Uuid,
+    pub parent_id: Option<i64>,
+    pub content: String,
+    #[serde_as(as = "TimestampSeconds<i64>")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    #[serde_as(as = "Option<TimestampSeconds<i64>>")]
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl Comment {
+    pub fn get_created_at(&self) -> String {
+        let now = self.created_at.with_timezone(&TIME_ZONE);
+        format!(
+            "{}.{}.{} {}:{}",
+            now.day(),
+            now.month(),
+            now.year(),
+            now.hour(),
+            now.minute()
+        )
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CommentList {
+    pub comments: Vec<Comment>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateCommentRequest {
+    pub content: String,
+    pub parent_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateCommentRequest {
+    pub content: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -1,15 +1,67 @@
 use crate::{
     dto::{
-        AuthResponse, CreatePostRequest, ErrorResponse, LoginRequest, Post, PostPage, PostResponse,
-        RegisterRequest, UpdatePostRequest,
+        Attachment, AttachmentResponse, AuthResponse, Bookmark, ChangePasswordRequest, Comment,
+        CommentList, CreateCommentRequest, CreatePostRequest, DeleteAccountRequest, ErrorResponse,
+        FollowStatus, LoginRequest, LoginResult, LoginTwoFactorRequest, PasswordResetConfirm,
+        PasswordResetRequest, Post, PostPage, PostReactions, PostResponse, RefreshRequest,
+        RegisterRequest, SearchPage, TagCount, TagList, TwoFactorChallenge, UpdateCommentRequest,
+        UpdatePostRequest, UpdateProfileRequest, UserProfile, UserProfileResponse,
+        VerifyEmailRequest,
     },
     API_PATH,
 };
 
-use reqwest::Client;
+use reqwest::{Client, RequestBuilder};
 
-pub async fn get_list_posts(limit: i32, offset: i32) -> Result<PostPage, String> {
-    let request_path = format!("{}/api/posts?limit={}&offset={}", API_PATH, limit, offset);
+/// Публичные запросы уходят с токеном, если пользователь вошёл,
+/// чтобы сервер отметил посты из его закладок
+fn with_session(request: RequestBuilder) -> RequestBuilder {
+    match super::get_token() {
+        Ok(data) => request.header(reqwest::header::AUTHORIZATION, data.get_brear()),
+        Err(_) => request,
+    }
+}
+
+/// `cursor` - `next_cursor` предыдущей страницы, `None` для первой страницы.
+/// Порядок `liked` курсоров не выдаёт и листается по `offset`
+pub async fn get_list_posts(
+    limit: i32,
+    cursor: Option<&str>,
+    offset: i32,
+    order: &str,
+    tags: &[String],
+) -> Result<PostPage, String> {
+    let mut request_path = format!(
+        "{}/api/posts?limit={}&offset={}&order={}",
+        API_PATH, limit, offset, order
+    );
+    if let Some(cursor) = cursor {
+        request_path.push_str(&format!("&cursor={}", encode_query_value(cursor)));
+    }
+    for tag in tags {
+        request_path.push_str(&format!("&tag={}", encode_query_value(tag)));
+    }
+
+    let response = with_session(Client::new().get(&request_path))
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if response.status().is_success() {
+        return response.json::<PostPage>().await.map_err(|e| e.to_string());
+    }
+
+    Err(response.status().to_string())
+}
+
+pub async fn search_posts(query: &str, limit: i32, offset: i32) -> Result<SearchPage, String> {
+    let request_path = format!(
+        "{}/api/search?q={}&limit={}&offset={}",
+        API_PATH,
+        encode_query_value(query),
+        limit,
+        offset
+    );
 
     let response = Client::new()
         .get(&request_path)
@@ -18,7 +70,30 @@ pub async fn get_list_posts(limit: i32, offset: i32) -> Result<PostPage, String>
         .map_err(|e| e.to_string())?;
 
     if response.status().is_success() {
-        return response.json::<PostPage>().await.map_err(|e| e.to_string());
+        return response
+            .json::<SearchPage>()
+            .await
+            .map_err(|e| e.to_string());
+    }
+
+    Err(response.status().to_string())
+}
+
+pub async fn get_tags() -> Result<Vec<TagCount>, String> {
+    let request_path = format!("{}/api/tags", API_PATH);
+
+    let response = Client::new()
+        .get(&request_path)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if response.status().is_success() {
+        return response
+            .json::<TagList>()
+            .await
+            .map(|list| list.tags)
+            .map_err(|e| e.to_string());
     }
 
     Err(response.status().to_string())
@@ -26,8 +101,60 @@ pub async fn get_list_posts(limit: i32, offset: i32) -> Result<PostPage, String>
 
 pub async fn get_post(post_id: i64) -> Result<Post, String> {
     let request_path = format!("{}/api/posts/{}", API_PATH, post_id);
+    let response = with_session(Client::new().get(request_path))
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let status = response.status();
+
+    if status.is_success() {
+        let res = response
+            .json::<PostResponse>()
+            .await
+            .map_err(|e| format!("Error parse: {}", e))?;
+        if let Some(post) = res.post {
+            return Ok(post);
+        }
+    }
+
+    Err(status.to_string())
+}
+
+/// Прежний slug сервер перенаправляет на актуальный,
+/// поэтому `slug` у результата может отличаться от запрошенного
+pub async fn get_post_by_slug(slug: &str) -> Result<Post, String> {
+    let request_path = format!(
+        "{}/api/posts/by-slug/{}",
+        API_PATH,
+        encode_query_value(slug)
+    );
+    let response = with_session(Client::new().get(request_path))
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let status = response.status();
+
+    if status.is_success() {
+        let res = response
+            .json::<PostResponse>()
+            .await
+            .map_err(|e| format!("Error parse: {}", e))?;
+        if let Some(post) = res.post {
+            return Ok(post);
+        }
+    }
+
+    Err(status.to_string())
+}
+
+/// Пост в любом статусе. Доступен только автору
+pub async fn get_own_post(post_id: i64, token: &str) -> Result<Post, String> {
+    let request_path = format!("{}/protect/post/{}", API_PATH, post_id);
     let response = Client::new()
         .get(request_path)
+        .header(reqwest::header::AUTHORIZATION, token)
         .send()
         .await
         .map_err(|e| e.to_string())?;
@@ -47,6 +174,196 @@ pub async fn get_post(post_id: i64) -> Result<Post, String> {
     Err(status.to_string())
 }
 
+pub async fn get_drafts(limit: i32, offset: i32, token: &str) -> Result<PostPage, String> {
+    let request_path = format!(
+        "{}/protect/drafts?limit={}&offset={}",
+        API_PATH, limit, offset
+    );
+
+    let response = Client::new()
+        .get(&request_path)
+        .header(reqwest::header::AUTHORIZATION, token)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if response.status().is_success() {
+        return response.json::<PostPage>().await.map_err(|e| e.to_string());
+    }
+
+    Err(response.status().to_string())
+}
+
+/// Лента подписок, листается курсором как общий список
+pub async fn get_feed(limit: i32, cursor: Option<&str>, token: &str) -> Result<PostPage, String> {
+    let mut request_path = format!("{}/protect/feed?limit={}", API_PATH, limit);
+    if let Some(cursor) = cursor {
+        request_path.push_str(&format!("&cursor={}", encode_query_value(cursor)));
+    }
+
+    let response = Client::new()
+        .get(&request_path)
+        .header(reqwest::header::AUTHORIZATION, token)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if response.status().is_success() {
+        return response.json::<PostPage>().await.map_err(|e| e.to_string());
+    }
+
+    Err(response.status().to_string())
+}
+
+pub async fn get_follow_status(username: &str, token: &str) -> Result<FollowStatus, String> {
+    let request_path = format!(
+        "{}/protect/users/{}/follow",
+        API_PATH,
+        encode_query_value(username)
+    );
+
+    let response = Client::new()
+        .get(&request_path)
+        .header(reqwest::header::AUTHORIZATION, token)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if response.status().is_success() {
+        return response
+            .json::<FollowStatus>()
+            .await
+            .map_err(|e| e.to_string());
+    }
+
+    Err(response.status().to_string())
+}
+
+/// Подписывается на автора или отписывается при `remove`
+pub async fn follow(username: &str, remove: bool, token: &str) -> Result<FollowStatus, String> {
+    let request_path = format!(
+        "{}/protect/users/{}/follow",
+        API_PATH,
+        encode_query_value(username)
+    );
+
+    let client = Client::new();
+    let request = if remove {
+        client.delete(request_path)
+    } else {
+        client.put(request_path)
+    };
+    let response = request
+        .header(reqwest::header::AUTHORIZATION, token)
+        .send()
+        .await
+        .map_err(|e| format!("Error request: {}", e))?;
+
+    let status = response.status();
+
+    if status.is_success() {
+        return response
+            .json::<FollowStatus>()
+            .await
+            .map_err(|e| format!("Error parse: {}", e));
+    }
+
+    Err(status.to_string())
+}
+
+pub async fn get_user_profile(username: &str) -> Result<UserProfile, String> {
+    let request_path = format!("{}/api/users/{}", API_PATH, encode_query_value(username));
+    let response = Client::new()
+        .get(request_path)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let status = response.status();
+    if status == reqwest::StatusCode::NOT_FOUND {
+        return Err("Пользователь не найден".to_string());
+    }
+    if !status.is_success() {
+        return Err(status.to_string());
+    }
+
+    response
+        .json::<UserProfileResponse>()
+        .await
+        .map(|res| res.profile)
+        .map_err(|e| format!("Error parse: {}", e))
+}
+
+/// Опубликованные посты автора, от новых к старым
+pub async fn get_user_posts(username: &str, limit: i32, offset: i32) -> Result<PostPage, String> {
+    let request_path = format!(
+        "{}/api/users/{}/posts?limit={}&offset={}",
+        API_PATH,
+        encode_query_value(username),
+        limit,
+        offset
+    );
+
+    let response = with_session(Client::new().get(&request_path))
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if response.status().is_success() {
+        return response.json::<PostPage>().await.map_err(|e| e.to_string());
+    }
+
+    Err(response.status().to_string())
+}
+
+pub async fn update_profile(
+    profile: &UpdateProfileRequest,
+    token: &str,
+) -> Result<UserProfile, String> {
+    let request_path = format!("{}/protect/account/profile", API_PATH);
+
+    let response = Client::new()
+        .put(request_path)
+        .header(reqwest::header::AUTHORIZATION, token)
+        .json(profile)
+        .send()
+        .await
+        .map_err(|e| format!("Error request: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(error_message(response).await);
+    }
+
+    response
+        .json::<UserProfileResponse>()
+        .await
+        .map(|res| res.profile)
+        .map_err(|e| format!("Error parse: {}", e))
+}
+
+/// action: publish, unpublish или archive
+pub async fn change_post_status(id: i64, action: &str, token: &str) -> Result<Post, String> {
+    let request_path = format!("{}/protect/post/{}/{}", API_PATH, id, action);
+
+    let response = Client::new()
+        .post(request_path)
+        .header(reqwest::header::AUTHORIZATION, token)
+        .send()
+        .await
+        .map_err(|e| format!("Error request: {}", e))?;
+
+    let status = response.status();
+
+    if status.is_success() {
+        return response
+            .json::<Post>()
+            .await
+            .map_err(|e| format!("Error parse: {}", e));
+    }
+
+    Err(status.to_string())
+}
+
 pub async fn register_user(
     username: &str,
     email: &str,
@@ -81,7 +398,7 @@ pub async fn register_user(
         .map_err(|e| format!("Error parse: {}", e))
 }
 
-pub async fn login_user(username: &str, password: &str) -> Result<AuthResponse, String> {
+pub async fn login_user(username: &str, password: &str) -> Result<LoginResult, String> {
     let request_path = format!("{}/api/auth/login", API_PATH);
     let request_body = LoginRequest {
         username: username.to_string(),
@@ -104,82 +421,611 @@ pub async fn login_user(username: &str, password: &str) -> Result<AuthResponse,
         return Err(err.details.resource);
     }
 
+    if status == reqwest::StatusCode::ACCEPTED {
+        return response
+            .json::<TwoFactorChallenge>()
+            .await
+            .map(LoginResult::TwoFactorRequired)
+            .map_err(|e| format!("Error parse: {}", e));
+    }
+
     response
         .json::<AuthResponse>()
         .await
+        .map(LoginResult::Authenticated)
         .map_err(|e| format!("Error parse: {}", e))
 }
 
-pub async fn create_post(title: &str, content: &str, token: &str) -> Result<Post, String> {
-    let request_path = format!("{}/protect/post", API_PATH);
-    let request_body = CreatePostRequest {
-        title: title.to_string(),
-        content: content.to_string(),
+/// Второй шаг входа: код из приложения или код восстановления
+pub async fn login_two_factor(challenge: &str, code: &str) -> Result<AuthResponse, String> {
+    let request_path = format!("{}/api/auth/login/two-factor", API_PATH);
+    let request_body = LoginTwoFactorRequest {
+        challenge: challenge.to_string(),
+        code: code.to_string(),
     };
 
     let response = Client::new()
         .post(request_path)
-        .header(reqwest::header::AUTHORIZATION, token)
         .json(&request_body)
         .send()
         .await
         .map_err(|e| format!("Error request: {}", e))?;
 
     let status = response.status();
-
-    if status.is_success() {
-        return response
-            .json::<Post>()
+    if !status.is_success() {
+        let err = response
+            .json::<ErrorResponse>()
             .await
-            .map_err(|e| format!("Error parse: {}", e));
+            .map_err(|e| format!("Error parse ErrorResponse: {}", e))?;
+        return Err(err.details.resource);
     }
 
-    Err(status.to_string())
+    response
+        .json::<AuthResponse>()
+        .await
+        .map_err(|e| format!("Error parse: {}", e))
 }
 
-pub async fn update_post(id: i64, title: &str, content: &str, token: &str) -> Result<Post, String> {
-    let request_path = format!("{}/protect/post/{}", API_PATH, id);
-    let request_body = UpdatePostRequest {
-        id,
-        title: title.to_string(),
-        content: content.to_string(),
+/// Загружает файл и возвращает вложение с абсолютным адресом
+/// Обменивает refresh токен на новую пару токенов, `None` - сессия отозвана или истекла
+pub async fn refresh_session(refresh_token: &str) -> Result<Option<AuthResponse>, String> {
+    let request_path = format!("{}/api/auth/refresh", API_PATH);
+    let request_body = RefreshRequest {
+        refresh_token: refresh_token.to_string(),
     };
 
     let response = Client::new()
-        .put(request_path)
-        .header(reqwest::header::AUTHORIZATION, token)
+        .post(request_path)
         .json(&request_body)
         .send()
         .await
         .map_err(|e| format!("Error request: {}", e))?;
 
     let status = response.status();
-
-    if status.is_success() {
-        return response
-            .json::<Post>()
-            .await
-            .map_err(|e| format!("Error parse: {}", e));
+    if status == reqwest::StatusCode::UNAUTHORIZED {
+        return Ok(None);
+    }
+    if !status.is_success() {
+        return Err(status.to_string());
     }
 
-    Err(status.to_string())
+    response
+        .json::<AuthResponse>()
+        .await
+        .map(Some)
+        .map_err(|e| format!("Error parse: {}", e))
 }
 
-pub async fn delete_post(id: i64, token: &str) -> Result<(), String> {
-    let request_path = format!("{}/protect/post/{}", API_PATH, id);
+pub async fn logout(refresh_token: &str, token: &str) -> Result<(), String> {
+    let request_path = format!("{}/protect/logout", API_PATH);
+    let request_body = RefreshRequest {
+        refresh_token: refresh_token.to_string(),
+    };
 
     let response = Client::new()
-        .delete(request_path)
+        .post(request_path)
         .header(reqwest::header::AUTHORIZATION, token)
+        .json(&request_body)
         .send()
         .await
         .map_err(|e| format!("Error request: {}", e))?;
 
     let status = response.status();
-
     if status.is_success() {
         return Ok(());
     }
 
     Err(status.to_string())
 }
+
+/// Смена пароля. Остальные сессии завершаются, в ответе новая пара токенов
+pub async fn change_password(
+    current_password: &str,
+    new_password: &str,
+    token: &str,
+) -> Result<AuthResponse, String> {
+    let request_path = format!("{}/protect/account/password", API_PATH);
+    let request_body = ChangePasswordRequest {
+        current_password: current_password.to_string(),
+        new_password: new_password.to_string(),
+    };
+
+    let response = Client::new()
+        .post(request_path)
+        .header(reqwest::header::AUTHORIZATION, token)
+        .json(&request_body)
+        .send()
+        .await
+        .map_err(|e| format!("Error request: {}", e))?;
+
+    let status = response.status();
+    if status == reqwest::StatusCode::FORBIDDEN {
+        return Err("Неверный текущий пароль".to_string());
+    }
+    if !status.is_success() {
+        return Err(error_message(response).await);
+    }
+
+    response
+        .json::<AuthResponse>()
+        .await
+        .map_err(|e| format!("Error parse: {}", e))
+}
+
+/// Выгрузка данных аккаунта: имя файла и JSON
+pub async fn export_account(token: &str) -> Result<(String, String), String> {
+    let request_path = format!("{}/protect/account/export", API_PATH);
+
+    let response = Client::new()
+        .get(request_path)
+        .header(reqwest::header::AUTHORIZATION, token)
+        .send()
+        .await
+        .map_err(|e| format!("Error request: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(error_message(response).await);
+    }
+
+    let file_name = response
+        .headers()
+        .get(reqwest::header::CONTENT_DISPOSITION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split_once("filename="))
+        .map(|(_, name)| name.trim_matches('"').to_string())
+        .unwrap_or_else(|| "blog-export.json".to_string());
+    let data = response
+        .text()
+        .await
+        .map_err(|e| format!("Error parse: {}", e))?;
+    Ok((file_name, data))
+}
+
+/// Ссылка для скачивания JSON без обращения к серверу
+pub fn json_data_url(data: &str) -> String {
+    format!(
+        "data:application/json;charset=utf-8,{}",
+        encode_query_value(data)
+    )
+}
+
+pub async fn delete_account(password: &str, posts: &str, token: &str) -> Result<(), String> {
+    let request_path = format!("{}/protect/account/delete", API_PATH);
+    let request_body = DeleteAccountRequest {
+        password: password.to_string(),
+        posts: posts.to_string(),
+    };
+
+    let response = Client::new()
+        .post(request_path)
+        .header(reqwest::header::AUTHORIZATION, token)
+        .json(&request_body)
+        .send()
+        .await
+        .map_err(|e| format!("Error request: {}", e))?;
+
+    let status = response.status();
+    if status == reqwest::StatusCode::FORBIDDEN {
+        return Err("Неверный пароль".to_string());
+    }
+    if !status.is_success() {
+        return Err(error_message(response).await);
+    }
+    Ok(())
+}
+
+pub async fn request_password_reset(email: &str) -> Result<(), String> {
+    let request_path = format!("{}/api/auth/password-reset", API_PATH);
+    let request_body = PasswordResetRequest {
+        email: email.to_string(),
+    };
+
+    let response = Client::new()
+        .post(request_path)
+        .json(&request_body)
+        .send()
+        .await
+        .map_err(|e| format!("Error request: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(error_message(response).await);
+    }
+    Ok(())
+}
+
+/// `reset_token` - токен из ссылки в письме
+pub async fn confirm_password_reset(reset_token: &str, new_password: &str) -> Result<(), String> {
+    let request_path = format!("{}/api/auth/password-reset/confirm", API_PATH);
+    let request_body = PasswordResetConfirm {
+        token: reset_token.to_string(),
+        new_password: new_password.to_string(),
+    };
+
+    let response = Client::new()
+        .post(request_path)
+        .json(&request_body)
+        .send()
+        .await
+        .map_err(|e| format!("Error request: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(error_message(response).await);
+    }
+    Ok(())
+}
+
+/// `verify_token` - токен из ссылки в письме
+pub async fn verify_email(verify_token: &str) -> Result<(), String> {
+    let request_path = format!("{}/api/auth/verify-email", API_PATH);
+    let request_body = VerifyEmailRequest {
+        token: verify_token.to_string(),
+    };
+
+    let response = Client::new()
+        .post(request_path)
+        .json(&request_body)
+        .send()
+        .await
+        .map_err(|e| format!("Error request: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(error_message(response).await);
+    }
+    Ok(())
+}
+
+pub async fn resend_verification(token: &str) -> Result<(), String> {
+    let request_path = format!("{}/protect/account/verify-email/resend", API_PATH);
+
+    let response = Client::new()
+        .post(request_path)
+        .header(reqwest::header::AUTHORIZATION, token)
+        .send()
+        .await
+        .map_err(|e| format!("Error request: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(error_message(response).await);
+    }
+    Ok(())
+}
+
+/// Текст ошибки из тела ответа, если сервер его прислал
+async fn error_message(response: reqwest::Response) -> String {
+    let status = response.status();
+    response
+        .json::<ErrorResponse>()
+        .await
+        .map(|err| err.details.resource)
+        .unwrap_or_else(|_| status.to_string())
+}
+
+pub async fn upload_attachment(
+    file_name: &str,
+    data: Vec<u8>,
+    post_id: Option<i64>,
+    token: &str,
+) -> Result<Attachment, String> {
+    let mut request_path = format!("{}/protect/attachments", API_PATH);
+    if let Some(post_id) = post_id {
+        request_path.push_str(&format!("?post_id={}", post_id));
+    }
+    let form = reqwest::multipart::Form::new().part(
+        "file",
+        reqwest::multipart::Part::bytes(data).file_name(file_name.to_string()),
+    );
+
+    let response = Client::new()
+        .post(request_path)
+        .header(reqwest::header::AUTHORIZATION, token)
+        .multipart(form)
+        .send()
+        .await
+        .map_err(|e| format!("Error request: {}", e))?;
+
+    let status = response.status();
+
+    if status.is_success() {
+        let mut attachment = response
+            .json::<AttachmentResponse>()
+            .await
+            .map_err(|e| format!("Error parse: {}", e))?
+            .attachment;
+        attachment.url = format!("{}{}", API_PATH, attachment.url);
+        return Ok(attachment);
+    }
+
+    if let Ok(error) = response.json::<ErrorResponse>().await {
+        return Err(error.error);
+    }
+
+    Err(status.to_string())
+}
+
+pub async fn create_post(
+    title: &str,
+    content: &str,
+    tags: Vec<String>,
+    draft: bool,
+    publish_at: Option<i64>,
+    token: &str,
+) -> Result<Post, String> {
+    let request_path = format!("{}/protect/post", API_PATH);
+    let request_body = CreatePostRequest {
+        title: title.to_string(),
+        content: content.to_string(),
+        tags,
+        draft,
+        publish_at,
+    };
+
+    let response = Client::new()
+        .post(request_path)
+        .header(reqwest::header::AUTHORIZATION, token)
+        .json(&request_body)
+        .send()
+        .await
+        .map_err(|e| format!("Error request: {}", e))?;
+
+    let status = response.status();
+
+    if status.is_success() {
+        return response
+            .json::<Post>()
+            .await
+            .map_err(|e| format!("Error parse: {}", e));
+    }
+
+    Err(status.to_string())
+}
+
+pub async fn update_post(
+    id: i64,
+    title: &str,
+    content: &str,
+    tags: Vec<String>,
+    status: &str,
+    publish_at: Option<i64>,
+    token: &str,
+) -> Result<Post, String> {
+    let request_path = format!("{}/protect/post/{}", API_PATH, id);
+    let request_body = UpdatePostRequest {
+        id,
+        title: title.to_string(),
+        content: content.to_string(),
+        tags,
+        status: status.to_string(),
+        publish_at,
+    };
+
+    let response = Client::new()
+        .put(request_path)
+        .header(reqwest::header::AUTHORIZATION, token)
+        .json(&request_body)
+        .send()
+        .await
+        .map_err(|e| format!("Error request: {}", e))?;
+
+    let status = response.status();
+
+    if status.is_success() {
+        return response
+            .json::<Post>()
+            .await
+            .map_err(|e| format!("Error parse: {}", e));
+    }
+
+    Err(status.to_string())
+}
+
+pub async fn delete_post(id: i64, token: &str) -> Result<(), String> {
+    let request_path = format!("{}/protect/post/{}", API_PATH, id);
+
+    let response = Client::new()
+        .delete(request_path)
+        .header(reqwest::header::AUTHORIZATION, token)
+        .send()
+        .await
+        .map_err(|e| format!("Error request: {}", e))?;
+
+    let status = response.status();
+
+    if status.is_success() {
+        return Ok(());
+    }
+
+    Err(status.to_string())
+}
+
+pub async fn get_comments(post_id: i64) -> Result<Vec<Comment>, String> {
+    let request_path = format!("{}/api/posts/{}/comments", API_PATH, post_id);
+    let response = Client::new()
+        .get(request_path)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let status = response.status();
+
+    if status.is_success() {
+        let res = response
+            .json::<CommentList>()
+            .await
+            .map_err(|e| format!("Error parse: {}", e))?;
+        return Ok(res.comments);
+    }
+
+    Err(status.to_string())
+}
+
+pub async fn create_comment(
+    post_id: i64,
+    content: &str,
+    parent_id: Option<i64>,
+    token: &str,
+) -> Result<Comment, String> {
+    let request_path = format!("{}/protect/post/{}/comment", API_PATH, post_id);
+    let request_body = CreateCommentRequest {
+        content: content.to_string(),
+        parent_id,
+    };
+
+    let response = Client::new()
+        .post(request_path)
+        .header(reqwest::header::AUTHORIZATION, token)
+        .json(&request_body)
+        .send()
+        .await
+        .map_err(|e| format!("Error request: {}", e))?;
+
+    let status = response.status();
+
+    if status.is_success() {
+        return response
+            .json::<Comment>()
+            .await
+            .map_err(|e| format!("Error parse: {}", e));
+    }
+
+    Err(status.to_string())
+}
+
+pub async fn update_comment(id: i64, content: &str, token: &str) -> Result<Comment, String> {
+    let request_path = format!("{}/protect/comment/{}", API_PATH, id);
+    let request_body = UpdateCommentRequest {
+        content: content.to_string(),
+    };
+
+    let response = Client::new()
+        .put(request_path)
+        .header(reqwest::header::AUTHORIZATION, token)
+        .json(&request_body)
+        .send()
+        .await
+        .map_err(|e| format!("Error request: {}", e))?;
+
+    let status = response.status();
+
+    if status.is_success() {
+        return response
+            .json::<Comment>()
+            .await
+            .map_err(|e| format!("Error parse: {}", e));
+    }
+
+    Err(status.to_string())
+}
+
+/// Ставит реакцию или снимает её при `remove`
+pub async fn react_post(
+    post_id: i64,
+    kind: &str,
+    remove: bool,
+    token: &str,
+) -> Result<PostReactions, String> {
+    let request_path = format!("{}/protect/post/{}/reaction/{}", API_PATH, post_id, kind);
+
+    let client = Client::new();
+    let request = if remove {
+        client.delete(request_path)
+    } else {
+        client.put(request_path)
+    };
+    let response = request
+        .header(reqwest::header::AUTHORIZATION, token)
+        .send()
+        .await
+        .map_err(|e| format!("Error request: {}", e))?;
+
+    let status = response.status();
+
+    if status.is_success() {
+        return response
+            .json::<PostReactions>()
+            .await
+            .map_err(|e| format!("Error parse: {}", e));
+    }
+
+    Err(status.to_string())
+}
+
+/// Добавляет пост в закладки или убирает его оттуда при `remove`
+pub async fn bookmark_post(post_id: i64, remove: bool, token: &str) -> Result<Bookmark, String> {
+    let request_path = format!("{}/protect/post/{}/bookmark", API_PATH, post_id);
+
+    let client = Client::new();
+    let request = if remove {
+        client.delete(request_path)
+    } else {
+        client.put(request_path)
+    };
+    let response = request
+        .header(reqwest::header::AUTHORIZATION, token)
+        .send()
+        .await
+        .map_err(|e| format!("Error request: {}", e))?;
+
+    let status = response.status();
+
+    if status.is_success() {
+        return response
+            .json::<Bookmark>()
+            .await
+            .map_err(|e| format!("Error parse: {}", e));
+    }
+
+    Err(status.to_string())
+}
+
+/// Сохранённые посты, от новых закладок к старым
+pub async fn get_bookmarks(limit: i32, offset: i32, token: &str) -> Result<PostPage, String> {
+    let request_path = format!(
+        "{}/protect/bookmarks?limit={}&offset={}",
+        API_PATH, limit, offset
+    );
+
+    let response = Client::new()
+        .get(&request_path)
+        .header(reqwest::header::AUTHORIZATION, token)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if response.status().is_success() {
+        return response.json::<PostPage>().await.map_err(|e| e.to_string());
+    }
+
+    Err(response.status().to_string())
+}
+
+pub async fn delete_comment(id: i64, token: &str) -> Result<(), String> {
+    let request_path = format!("{}/protect/comment/{}", API_PATH, id);
+
+    let response = Client::new()
+        .delete(request_path)
+        .header(reqwest::header::AUTHORIZATION, token)
+        .send()
+        .await
+        .map_err(|e| format!("Error request: {}", e))?;
+
+    let status = response.status();
+
+    if status.is_success() {
+        return Ok(());
+    }
+
+    Err(status.to_string())
+}
+
+/// Кодирует значение для строки запроса
+fn encode_query_value(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
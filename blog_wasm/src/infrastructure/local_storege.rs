@@ -6,6 +6,8 @@ use crate::NAME_STORAGE_TOKEN;
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SaveData {
     pub token: String,
+    #[serde(default)]
+    pub refresh_token: String,
     pub username: String,
     pub uuid: Uuid,
 }
@@ -17,9 +19,10 @@ impl SaveData {
 }
 
 impl SaveData {
-    pub fn new(token: String, username: String, uuid: Uuid) -> Self {
+    pub fn new(token: String, refresh_token: String, username: String, uuid: Uuid) -> Self {
         Self {
             token,
+            refresh_token,
             username,
             uuid,
         }
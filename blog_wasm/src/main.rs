@@ -3,10 +3,14 @@ mod dto;
 mod infrastructure;
 
 use components::*;
-use dioxus::prelude::*;
+use dioxus::{logger::tracing, prelude::*};
+use gloo_timers::future::TimeoutFuture;
 
 const API_PATH: &str = "http://127.0.0.1:8081";
 const NAME_STORAGE_TOKEN: &str = "auth_token";
+/// За сколько секунд до истечения access токена его обновлять
+const TOKEN_REFRESH_LEEWAY: i64 = 60;
+const TOKEN_REFRESH_RETRY: i64 = 30;
 
 const MAIN_CSS: Asset = asset!("/assets/main.css");
 
@@ -14,6 +18,14 @@ const MAIN_CSS: Asset = asset!("/assets/main.css");
 enum Route {
     #[route("/")]
     Index,
+    #[route("/tag/:tag")]
+    TagPosts { tag: String },
+    #[route("/p/:slug")]
+    PostPage { slug: String },
+    #[route("/search/:q")]
+    Search { q: String },
+    #[route("/u/:username")]
+    AuthorPage { username: String },
 
     #[route("/post/:id")]
     PostUpdate { id: i64 },
@@ -21,8 +33,26 @@ enum Route {
     Login,
     #[route("/register")]
     Register,
+    #[route("/forgot-password")]
+    ForgotPassword,
+    #[route("/reset-password/:token")]
+    ResetPassword { token: String },
+    #[route("/account/password")]
+    ChangePassword,
+    #[route("/account/profile")]
+    EditProfile,
+    #[route("/account/delete")]
+    DeleteAccount,
+    #[route("/verify-email/:token")]
+    VerifyEmail { token: String },
     #[route("/create")]
     CreatePost,
+    #[route("/drafts")]
+    Drafts,
+    #[route("/feed")]
+    Feed,
+    #[route("/saved")]
+    Saved,
     #[route("/:..route")]
     NotFound { route: Vec<String> },
 }
@@ -33,6 +63,8 @@ fn main() {
 
 #[component]
 fn App() -> Element {
+    use_future(refresh_loop);
+
     rsx! {
         document::Link { rel: "stylesheet", href: MAIN_CSS }
         div {
@@ -44,3 +76,39 @@ fn App() -> Element {
 
     }
 }
+
+/// Обновляет пару токенов незадолго до истечения access токена
+async fn refresh_loop() {
+    loop {
+        let delay = match infrastructure::get_token() {
+            Ok(data) if !data.refresh_token.is_empty() => {
+                match infrastructure::refresh_session(&data.refresh_token).await {
+                    Ok(Some(auth)) => {
+                        let expires_in = auth.expires_in;
+                        if let Err(e) = infrastructure::save_token(&infrastructure::SaveData::new(
+                            auth.token,
+                            auth.refresh_token,
+                            data.username,
+                            auth.uuid,
+                        )) {
+                            tracing::error!("Ошибка при сохранении токена: {e}");
+                        }
+                        (expires_in - TOKEN_REFRESH_LEEWAY).max(TOKEN_REFRESH_RETRY)
+                    }
+                    Ok(None) => {
+                        if let Err(e) = infrastructure::delete_token() {
+                            tracing::error!("Ошибка при удалении токена: {e}");
+                        }
+                        TOKEN_REFRESH_RETRY
+                    }
+                    Err(e) => {
+                        tracing::warn!("Ошибка при обновлении токена: {e}");
+                        TOKEN_REFRESH_RETRY
+                    }
+                }
+            }
+            _ => TOKEN_REFRESH_RETRY,
+        };
+        TimeoutFuture::new((delay * 1000) as u32).await;
+    }
+}